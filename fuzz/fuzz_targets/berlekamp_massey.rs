@@ -1,3 +1,4 @@
+use bech32::primitives::correction::NO_ALLOC_MAX_LENGTH;
 use bech32::primitives::LfsrIter;
 use bech32::Fe32;
 use honggfuzz::fuzz;
@@ -17,7 +18,7 @@ fn do_test(data: &[u8]) {
         iv.push(Fe32::try_from(*ch).unwrap());
     }
 
-    for (i, d) in LfsrIter::berlekamp_massey(&iv).take(data.len()).enumerate() {
+    for (i, d) in LfsrIter::<_, NO_ALLOC_MAX_LENGTH>::berlekamp_massey(&iv).take(data.len()).enumerate() {
         assert_eq!(data[i], d.to_u8());
     }
 }
@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: MIT
+
+//! Validation of many bech32 strings at once.
+//!
+//! When validating large numbers of strings, e.g. addresses from a CSV import or a chain scan,
+//! they usually all share the same human-readable part. The validators in this module expand the
+//! expected HRP and input it into the checksum engine once, then reuse that midstate for every
+//! string (see [`PreparedHrp`]).
+//!
+//! With the `rayon` and `std` features enabled, batches can also be validated in parallel.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::batch::{SegwitValidateError, SegwitValidator};
+//! use bech32::hrp;
+//!
+//! let addresses = [
+//!     "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+//!     "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+//!     "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+//! ];
+//!
+//! let validator = SegwitValidator::with_hrp(hrp::BC);
+//! let results = validator.validate_all(addresses.iter().copied());
+//!
+//! assert!(results[0].is_ok());
+//! assert!(matches!(results[1], Err(SegwitValidateError::UnexpectedHrp(_))));
+//! assert!(matches!(results[2], Err(SegwitValidateError::Decode(_))));
+//! ```
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(all(feature = "rayon", feature = "std"))]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::error::write_err;
+use crate::primitives::checksum::PreparedHrp;
+use crate::primitives::decode::{
+    CheckedHrpstring, CheckedHrpstringError, SegwitHrpstring, SegwitHrpstringError,
+};
+use crate::{Bech32m, Checksum, Hrp};
+
+/// Validates strings with the checksum `Ck`, and optionally an expected HRP.
+pub struct Validator<Ck: Checksum> {
+    /// The expected HRP, with its checksum midstate.
+    expected: Option<PreparedHrp<Ck>>,
+}
+
+impl<Ck: Checksum> fmt::Debug for Validator<Ck> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Validator").field("expected", &self.expected).finish()
+    }
+}
+
+impl<Ck: Checksum> Default for Validator<Ck> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<Ck: Checksum> Validator<Ck> {
+    /// Constructs a validator which accepts any HRP.
+    #[inline]
+    pub fn new() -> Self { Validator { expected: None } }
+
+    /// Constructs a validator which only accepts strings with the human-readable part `hrp`.
+    #[inline]
+    pub fn with_hrp(hrp: Hrp) -> Self { Validator { expected: Some(PreparedHrp::new(hrp)) } }
+
+    /// Returns the HRP which strings must have, if any.
+    #[inline]
+    pub fn expected_hrp(&self) -> Option<&Hrp> { self.expected.as_ref().map(PreparedHrp::hrp) }
+
+    /// Validates a single string.
+    ///
+    /// Errors decoding the string take precedence over an unexpected HRP.
+    pub fn validate<'s>(&self, s: &'s str) -> Result<CheckedHrpstring<'s>, ValidateError> {
+        let checked = match self.expected {
+            Some(ref prepared) => CheckedHrpstring::new_with_prepared_hrp(s, prepared)?,
+            None => CheckedHrpstring::new::<Ck>(s)?,
+        };
+        check_hrp(self.expected_hrp(), checked.hrp())?;
+        Ok(checked)
+    }
+
+    /// Validates each string, returning the results in the same order.
+    pub fn validate_all<'s, I>(
+        &self,
+        strings: I,
+    ) -> Vec<Result<CheckedHrpstring<'s>, ValidateError>>
+    where
+        I: IntoIterator<Item = &'s str>,
+    {
+        strings.into_iter().map(|s| self.validate(s)).collect()
+    }
+
+    /// Validates each string in parallel, returning the results in the same order.
+    #[cfg(all(feature = "rayon", feature = "std"))]
+    pub fn par_validate_all<'s, I>(
+        &self,
+        strings: I,
+    ) -> Vec<Result<CheckedHrpstring<'s>, ValidateError>>
+    where
+        I: IntoParallelIterator<Item = &'s str>,
+        I::Iter: rayon::iter::IndexedParallelIterator,
+        Ck::MidstateRepr: Sync,
+    {
+        strings.into_par_iter().map(|s| self.validate(s)).collect()
+    }
+}
+
+/// Validates segwit addresses, and optionally that they have an expected HRP.
+///
+/// The checksum is bech32 or bech32m depending on the witness version, as for
+/// [`SegwitHrpstring::new`].
+#[derive(Debug, Default)]
+pub struct SegwitValidator {
+    /// The expected HRP, with its checksum midstate.
+    expected: Option<PreparedHrp<Bech32m>>,
+}
+
+impl SegwitValidator {
+    /// Constructs a validator which accepts any HRP.
+    #[inline]
+    pub fn new() -> Self { SegwitValidator { expected: None } }
+
+    /// Constructs a validator which only accepts addresses with the human-readable part `hrp`.
+    #[inline]
+    pub fn with_hrp(hrp: Hrp) -> Self { SegwitValidator { expected: Some(PreparedHrp::new(hrp)) } }
+
+    /// Returns the HRP which addresses must have, if any.
+    #[inline]
+    pub fn expected_hrp(&self) -> Option<&Hrp> { self.expected.as_ref().map(PreparedHrp::hrp) }
+
+    /// Validates a single address.
+    ///
+    /// Errors decoding the address take precedence over an unexpected HRP.
+    pub fn validate<'s>(&self, s: &'s str) -> Result<SegwitHrpstring<'s>, SegwitValidateError> {
+        let segwit = match self.expected {
+            Some(ref prepared) => SegwitHrpstring::new_with_prepared_hrp(s, prepared)?,
+            None => SegwitHrpstring::new(s)?,
+        };
+        check_hrp(self.expected_hrp(), segwit.hrp())?;
+        Ok(segwit)
+    }
+
+    /// Validates each address, returning the results in the same order.
+    pub fn validate_all<'s, I>(
+        &self,
+        strings: I,
+    ) -> Vec<Result<SegwitHrpstring<'s>, SegwitValidateError>>
+    where
+        I: IntoIterator<Item = &'s str>,
+    {
+        strings.into_iter().map(|s| self.validate(s)).collect()
+    }
+
+    /// Validates each address in parallel, returning the results in the same order.
+    #[cfg(all(feature = "rayon", feature = "std"))]
+    pub fn par_validate_all<'s, I>(
+        &self,
+        strings: I,
+    ) -> Vec<Result<SegwitHrpstring<'s>, SegwitValidateError>>
+    where
+        I: IntoParallelIterator<Item = &'s str>,
+        I::Iter: rayon::iter::IndexedParallelIterator,
+    {
+        strings.into_par_iter().map(|s| self.validate(s)).collect()
+    }
+}
+
+/// Checks that `actual` is the `expected` HRP, if there is one.
+fn check_hrp(expected: Option<&Hrp>, actual: Hrp) -> Result<(), UnexpectedHrpError> {
+    match expected {
+        Some(expected) if *expected != actual => Err(UnexpectedHrpError { actual }),
+        _ => Ok(()),
+    }
+}
+
+/// An error validating a string with a [`Validator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidateError {
+    /// The string is not a valid checksummed string.
+    Decode(CheckedHrpstringError),
+    /// The string is valid but does not have the expected HRP.
+    UnexpectedHrp(UnexpectedHrpError),
+}
+
+impl fmt::Display for ValidateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ValidateError::*;
+
+        match *self {
+            Decode(ref e) => write_err!(f, "decoding failed"; e),
+            UnexpectedHrp(ref e) => write_err!(f, "unexpected hrp"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use ValidateError::*;
+
+        match *self {
+            Decode(ref e) => Some(e),
+            UnexpectedHrp(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for ValidateError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Decode(e) }
+}
+
+impl From<UnexpectedHrpError> for ValidateError {
+    #[inline]
+    fn from(e: UnexpectedHrpError) -> Self { Self::UnexpectedHrp(e) }
+}
+
+/// An error validating an address with a [`SegwitValidator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SegwitValidateError {
+    /// The string is not a valid segwit address.
+    Decode(SegwitHrpstringError),
+    /// The address is valid but does not have the expected HRP.
+    UnexpectedHrp(UnexpectedHrpError),
+}
+
+impl fmt::Display for SegwitValidateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SegwitValidateError::*;
+
+        match *self {
+            Decode(ref e) => write_err!(f, "decoding segwit address failed"; e),
+            UnexpectedHrp(ref e) => write_err!(f, "unexpected hrp"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SegwitValidateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use SegwitValidateError::*;
+
+        match *self {
+            Decode(ref e) => Some(e),
+            UnexpectedHrp(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<SegwitHrpstringError> for SegwitValidateError {
+    #[inline]
+    fn from(e: SegwitHrpstringError) -> Self { Self::Decode(e) }
+}
+
+impl From<UnexpectedHrpError> for SegwitValidateError {
+    #[inline]
+    fn from(e: UnexpectedHrpError) -> Self { Self::UnexpectedHrp(e) }
+}
+
+/// A string had a different HRP than the one expected by the validator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnexpectedHrpError {
+    actual: Hrp,
+}
+
+impl UnexpectedHrpError {
+    /// Returns the HRP of the string.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.actual }
+}
+
+impl fmt::Display for UnexpectedHrpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "hrp {} is not the expected hrp", self.actual)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnexpectedHrpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(feature = "rayon", feature = "std"))]
+    use rayon::iter::IntoParallelRefIterator;
+
+    use super::*;
+    use crate::{hrp, Bech32};
+
+    const ADDRESSES: [&str; 6] = [
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        "BC1SW50QGDZ25J",
+        "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5",
+        "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+        "not an address",
+    ];
+
+    #[test]
+    fn segwit_validator() {
+        let validator = SegwitValidator::with_hrp(hrp::BC);
+        let results = validator.validate_all(ADDRESSES.iter().copied());
+        assert_eq!(results.len(), ADDRESSES.len());
+        for (s, res) in ADDRESSES.iter().zip(&results) {
+            match SegwitHrpstring::new(s) {
+                Ok(expected) if expected.hrp() == hrp::BC =>
+                    assert_eq!(res.as_ref().unwrap().witness_version(), expected.witness_version()),
+                Ok(expected) => assert_eq!(
+                    *res.as_ref().unwrap_err(),
+                    SegwitValidateError::UnexpectedHrp(UnexpectedHrpError {
+                        actual: expected.hrp(),
+                    }),
+                ),
+                Err(e) => assert_eq!(*res.as_ref().unwrap_err(), SegwitValidateError::Decode(e)),
+            }
+        }
+
+        let any = SegwitValidator::new().validate_all(ADDRESSES.iter().copied());
+        assert_eq!(any.iter().filter(|res| res.is_ok()).count(), 4);
+    }
+
+    #[test]
+    fn checked_validator() {
+        let validator = Validator::<Bech32>::with_hrp(hrp::TB);
+        let results = validator.validate_all(ADDRESSES.iter().copied());
+        assert!(matches!(results[0], Err(ValidateError::UnexpectedHrp(_))));
+        assert!(results[2].is_ok());
+        assert!(matches!(results[3], Err(ValidateError::Decode(_))));
+        assert!(matches!(results[4], Err(ValidateError::Decode(_))));
+
+        let any = Validator::<Bech32>::new().validate_all(ADDRESSES.iter().copied());
+        assert_eq!(any.iter().filter(|res| res.is_ok()).count(), 2);
+
+        assert_eq!(format!("{:?}", Validator::<Bech32>::new()), "Validator { expected: None }");
+    }
+
+    #[test]
+    #[cfg(all(feature = "rayon", feature = "std"))]
+    fn parallel_matches_sequential() {
+        let strings = ADDRESSES.iter().copied().cycle().take(600).collect::<Vec<_>>();
+
+        let validator = SegwitValidator::with_hrp(hrp::BC);
+        let seq = validator.validate_all(strings.iter().copied());
+        let par = validator.par_validate_all(strings.par_iter().copied());
+        assert_eq!(format!("{:?}", seq), format!("{:?}", par));
+
+        let validator = Validator::<Bech32>::new();
+        let seq = validator.validate_all(strings.iter().copied());
+        let par = validator.par_validate_all(strings.par_iter().copied());
+        assert_eq!(format!("{:?}", seq), format!("{:?}", par));
+    }
+}
@@ -0,0 +1,461 @@
+// SPDX-License-Identifier: MIT
+
+//! BOLT-12 API - enables decoding and encoding [BOLT-12] offers, invoice requests and invoices.
+//!
+//! BOLT-12 strings are bech32 encoded _without_ a checksum. To make them easier to display (e.g.
+//! in QR codes or when line wrapping) a string may be split by a `+` followed by optional
+//! whitespace, these continuations are removed before the string is decoded. The data part is a
+//! TLV stream, this module exposes the individual records but does not interpret them.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::bolt12::{Bolt12String, Kind};
+//!
+//! let s = "lno1pqps7sjqpgt+yzm3qv4uxzmtsd3jjqer9wd3hy6tsw3+
+//!          5k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg";
+//! let offer = s.parse::<Bolt12String>().expect("valid offer");
+//!
+//! assert_eq!(offer.kind(), Kind::Offer);
+//! assert_eq!(offer.get(10), Some(&b"An example description"[..]));
+//!
+//! // Encoding produces a single lowercase string without continuations.
+//! assert!(offer.to_string().starts_with("lno1pqps7sjqpgtyzm3qv4u"));
+//! ```
+//!
+//! [BOLT-12]: <https://github.com/lightning/bolts/blob/master/12-offer-encoding.md>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Write as _};
+
+use crate::error::write_err;
+use crate::primitives::decode::{CheckedHrpstring, CheckedHrpstringError, PaddingError};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::Hrp;
+use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::NoChecksum;
+
+/// The character used to join the parts of a split string.
+const CONTINUATION: char = '+';
+
+/// The kind of a BOLT-12 string, determined by its human-readable part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// An offer (`lno`).
+    Offer,
+    /// An invoice request (`lnr`).
+    InvoiceRequest,
+    /// An invoice (`lni`).
+    Invoice,
+}
+
+impl Kind {
+    /// Returns the human-readable part for this kind of string.
+    pub fn hrp(self) -> Hrp {
+        let hrp = match self {
+            Kind::Offer => "lno",
+            Kind::InvoiceRequest => "lnr",
+            Kind::Invoice => "lni",
+        };
+        Hrp::parse_unchecked(hrp)
+    }
+
+    /// Returns the kind of string identified by `hrp` (case-insensitive).
+    pub fn from_hrp(hrp: &Hrp) -> Option<Self> {
+        [Kind::Offer, Kind::InvoiceRequest, Kind::Invoice].iter().copied().find(|k| k.hrp() == *hrp)
+    }
+}
+
+/// A single record in a TLV stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlvRecord {
+    /// The record type.
+    pub record_type: u64,
+    /// The record value.
+    pub value: Vec<u8>,
+}
+
+/// A decoded BOLT-12 string.
+///
+/// The TLV records are always kept in strictly increasing order of type, so every string can be
+/// encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bolt12String {
+    /// The kind of string.
+    kind: Kind,
+    /// The TLV records, in strictly increasing order of type.
+    records: Vec<TlvRecord>,
+}
+
+/// Parses a BOLT-12 string, removing any `+` continuations.
+impl core::str::FromStr for Bolt12String {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let joined = join_continuations(s)?;
+        let checked = CheckedHrpstring::new::<NoChecksum>(&joined)?;
+        let hrp = checked.hrp();
+        let kind = Kind::from_hrp(&hrp).ok_or(DecodeError::UnknownHrp(hrp))?;
+        checked.validate_segwit_padding()?;
+
+        let bytes = checked.byte_iter().collect::<Vec<u8>>();
+        let records = parse_tlv_stream(&bytes)?;
+
+        Ok(Bolt12String { kind, records })
+    }
+}
+
+/// Displays the string as a single lowercase string, without continuations.
+impl fmt::Display for Bolt12String {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hrp = self.kind.hrp();
+        let bytes = self.tlv_bytes();
+        for c in bytes.iter().copied().bytes_to_fes().with_checksum::<NoChecksum>(&hrp).chars() {
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+impl Bolt12String {
+    /// Constructs a string of the given kind with no TLV records.
+    #[inline]
+    pub fn new(kind: Kind) -> Self { Bolt12String { kind, records: Vec::new() } }
+
+    /// Returns the kind of string.
+    #[inline]
+    pub fn kind(&self) -> Kind { self.kind }
+
+    /// Returns the TLV records, in strictly increasing order of type.
+    #[inline]
+    pub fn records(&self) -> &[TlvRecord] { &self.records }
+
+    /// Returns the value of the record with type `record_type`, if present.
+    pub fn get(&self, record_type: u64) -> Option<&[u8]> {
+        self.records
+            .binary_search_by_key(&record_type, |r| r.record_type)
+            .ok()
+            .map(|i| self.records[i].value.as_slice())
+    }
+
+    /// Inserts `record`, keeping the records in order of type.
+    ///
+    /// Returns the record it replaced if there already was one of the same type.
+    pub fn insert(&mut self, record: TlvRecord) -> Option<TlvRecord> {
+        match self.records.binary_search_by_key(&record.record_type, |r| r.record_type) {
+            Ok(i) => Some(core::mem::replace(&mut self.records[i], record)),
+            Err(i) => {
+                self.records.insert(i, record);
+                None
+            }
+        }
+    }
+
+    /// Removes and returns the record with type `record_type`, if present.
+    pub fn remove(&mut self, record_type: u64) -> Option<TlvRecord> {
+        let i = self.records.binary_search_by_key(&record_type, |r| r.record_type).ok()?;
+        Some(self.records.remove(i))
+    }
+
+    /// Encodes the TLV stream of this string as bytes.
+    pub fn tlv_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for record in &self.records {
+            write_bigsize(&mut bytes, record.record_type);
+            write_bigsize(&mut bytes, record.value.len() as u64);
+            bytes.extend_from_slice(&record.value);
+        }
+        bytes
+    }
+
+    /// Encodes the string as a lowercase string, split into lines of at most `chunk_len`
+    /// characters joined with `+` continuations.
+    ///
+    /// # Panics
+    ///
+    /// If `chunk_len` is zero.
+    pub fn to_string_chunked(&self, chunk_len: usize) -> String {
+        assert!(chunk_len > 0, "chunk length must be non-zero");
+
+        let s = self.to_string();
+        // Continuations must sit between two data characters, never directly after the separator.
+        let first_data = self.kind.hrp().len() + 2;
+        let mut chunked = String::with_capacity(s.len() + 2 * (s.len() / chunk_len));
+        for (i, c) in s.chars().enumerate() {
+            if i >= first_data && i % chunk_len == 0 {
+                chunked.push(CONTINUATION);
+                chunked.push('\n');
+            }
+            chunked.push(c);
+        }
+        chunked
+    }
+}
+
+/// Removes `+` continuations (a `+` followed by optional whitespace) from a BOLT-12 string.
+///
+/// Each continuation must sit between two bech32 data characters, so not directly after the `1`
+/// separator, and whitespace is not allowed anywhere else.
+pub fn join_continuations(s: &str) -> Result<String, DecodeError> {
+    let is_data_char = |c: Option<char>| c.map_or(false, |c| Fe32::from_char(c).is_ok());
+
+    let mut joined = String::with_capacity(s.len());
+    for (i, part) in s.split(CONTINUATION).enumerate() {
+        let part =
+            if i == 0 { part } else { part.trim_start_matches(|c: char| c.is_ascii_whitespace()) };
+        if part.contains(|c: char| c.is_ascii_whitespace()) {
+            return Err(DecodeError::InvalidContinuation);
+        }
+        if i > 0 && !(is_data_char(joined.chars().last()) && is_data_char(part.chars().next())) {
+            return Err(DecodeError::InvalidContinuation);
+        }
+        joined.push_str(part);
+    }
+    Ok(joined)
+}
+
+/// Parses a TLV stream, records must be in strictly increasing order of type.
+fn parse_tlv_stream(mut bytes: &[u8]) -> Result<Vec<TlvRecord>, DecodeError> {
+    let mut records: Vec<TlvRecord> = Vec::new();
+    while !bytes.is_empty() {
+        let record_type = read_bigsize(&mut bytes)?;
+        if let Some(previous) = records.last() {
+            if previous.record_type >= record_type {
+                return Err(DecodeError::UnorderedRecords(record_type));
+            }
+        }
+        let len = read_bigsize(&mut bytes)?;
+        if (bytes.len() as u64) < len {
+            return Err(DecodeError::TruncatedTlv);
+        }
+        let (value, rest) = bytes.split_at(len as usize);
+        records.push(TlvRecord { record_type, value: value.to_vec() });
+        bytes = rest;
+    }
+    Ok(records)
+}
+
+/// Reads a BigSize integer, advancing `bytes` past it.
+fn read_bigsize(bytes: &mut &[u8]) -> Result<u64, DecodeError> {
+    use DecodeError::*;
+
+    let (&first, rest) = bytes.split_first().ok_or(TruncatedTlv)?;
+    let (len, min) = match first {
+        0xfd => (2, 0xfd),
+        0xfe => (4, 0x1_0000),
+        0xff => (8, 0x1_0000_0000),
+        n => {
+            *bytes = rest;
+            return Ok(u64::from(n));
+        }
+    };
+    if rest.len() < len {
+        return Err(TruncatedTlv);
+    }
+    let n = rest[..len].iter().fold(0u64, |acc, &b| acc << 8 | u64::from(b));
+    if n < min {
+        return Err(NonMinimalBigSize);
+    }
+    *bytes = &rest[len..];
+    Ok(n)
+}
+
+/// Writes `n` as a BigSize integer.
+fn write_bigsize(bytes: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=0xfc => bytes.push(n as u8),
+        0xfd..=0xffff => {
+            bytes.push(0xfd);
+            bytes.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            bytes.push(0xfe);
+            bytes.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+        _ => {
+            bytes.push(0xff);
+            bytes.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+/// An error while decoding a BOLT-12 string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// A `+` continuation is not between two parts, or the string contains stray whitespace.
+    InvalidContinuation,
+    /// Error while parsing the bech32 string.
+    Checked(CheckedHrpstringError),
+    /// The human-readable part is not one of `lno`, `lnr` or `lni`.
+    UnknownHrp(Hrp),
+    /// The data part has invalid padding.
+    Padding(PaddingError),
+    /// A TLV record extends past the end of the data.
+    TruncatedTlv,
+    /// A BigSize integer is not minimally encoded.
+    NonMinimalBigSize,
+    /// A TLV record type is not greater than the previous record type.
+    UnorderedRecords(u64),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DecodeError::*;
+
+        match *self {
+            InvalidContinuation => write!(f, "invalid + continuation or whitespace"),
+            Checked(ref e) => write_err!(f, "invalid bech32 string"; e),
+            UnknownHrp(ref hrp) => write!(f, "unknown human-readable part: {}", hrp),
+            Padding(ref e) => write_err!(f, "invalid padding"; e),
+            TruncatedTlv => write!(f, "TLV record extends past the end of the data"),
+            NonMinimalBigSize => write!(f, "BigSize integer is not minimally encoded"),
+            UnorderedRecords(t) => write!(f, "TLV record type {} is out of order", t),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use DecodeError::*;
+
+        match *self {
+            Checked(ref e) => Some(e),
+            Padding(ref e) => Some(e),
+            InvalidContinuation | UnknownHrp(_) | TruncatedTlv | NonMinimalBigSize
+            | UnorderedRecords(_) => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for DecodeError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Checked(e) }
+}
+
+impl From<PaddingError> for DecodeError {
+    #[inline]
+    fn from(e: PaddingError) -> Self { Self::Padding(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    // Test vectors from BOLT-12.
+    const OFFER: &str = "lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg";
+    const OFFER_SPLIT: &str = "lno1pqps7sjqpgt+yzm3qv4uxzmtsd3jjqer9wd3hy6tsw3+5k7msjzfpy7nz5yqcn+ygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd+5xvxg";
+    const OFFER_SPLIT_WHITESPACE: &str = "lno1pqps7sjqpgt+ yzm3qv4uxzmtsd3jjqer9wd3hy6tsw3+  5k7msjzfpy7nz5yqcn+\nygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd+\r\n 5xvxg";
+
+    #[test]
+    fn decode_offer() {
+        let offer = Bolt12String::from_str(OFFER).unwrap();
+        assert_eq!(offer.kind(), Kind::Offer);
+
+        let types = offer.records().iter().map(|r| r.record_type).collect::<Vec<_>>();
+        assert_eq!(types, vec![8, 10, 18, 22]);
+        assert_eq!(offer.get(8), Some(&[0x0f, 0x42, 0x40][..])); // 1000000 msat.
+        assert_eq!(offer.get(10), Some(&b"An example description"[..]));
+        assert_eq!(offer.get(18), Some(&b"BOLT 12 industries"[..]));
+        assert_eq!(offer.get(22).map(|v| v.len()), Some(33));
+        assert_eq!(offer.get(12), None);
+    }
+
+    #[test]
+    fn decode_with_continuations() {
+        let offer = Bolt12String::from_str(OFFER).unwrap();
+        assert_eq!(Bolt12String::from_str(OFFER_SPLIT).unwrap(), offer);
+        assert_eq!(Bolt12String::from_str(OFFER_SPLIT_WHITESPACE).unwrap(), offer);
+        assert_eq!(Bolt12String::from_str(&OFFER.to_uppercase()).unwrap(), offer);
+    }
+
+    #[test]
+    fn invalid_continuations() {
+        let invalid = [
+            "lno1pqps7sjqpgt+yzm3qv4uxzmtsd3jjqer9wd3hy6tsw3+5k7msjzfpy7nz5yqcn+ygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd+5xvxg+",
+            "lno1pqps7sjqpgt+yzm3qv4uxzmtsd3jjqer9wd3hy6tsw3+5k7msjzfpy7nz5yqcn+ygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd+5xvxg+ ",
+            "+lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg",
+            "+ lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg",
+            "lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5++xvxg",
+            "lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5+ +xvxg",
+            "lno1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5 xvxg",
+            "lno1+pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg",
+            "lno+1pqps7sjqpgtyzm3qv4uxzmtsd3jjqer9wd3hy6tsw35k7msjzfpy7nz5yqcnygrfdej82um5wf5k2uckyypwa3eyt44h6txtxquqh7lz5djge4afgfjn7k4rgrkuag0jsd5xvxg",
+        ];
+        for s in invalid.iter() {
+            assert_eq!(Bolt12String::from_str(s), Err(DecodeError::InvalidContinuation), "{}", s);
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let offer = Bolt12String::from_str(OFFER).unwrap();
+        assert_eq!(offer.to_string(), OFFER);
+
+        for chunk_len in [7, 20, 64, 500].iter() {
+            let chunked = offer.to_string_chunked(*chunk_len);
+            assert!(chunked.lines().all(|l| l.len() <= chunk_len + 1));
+            assert_eq!(Bolt12String::from_str(&chunked).unwrap(), offer);
+        }
+    }
+
+    #[test]
+    fn bigsize_roundtrip() {
+        let values = [0, 0xfc, 0xfd, 0xffff, 0x1_0000, 0xffff_ffff, 0x1_0000_0000, u64::MAX];
+        for &n in values.iter() {
+            let mut bytes = Vec::new();
+            write_bigsize(&mut bytes, n);
+            let mut slice = bytes.as_slice();
+            assert_eq!(read_bigsize(&mut slice), Ok(n));
+            assert!(slice.is_empty());
+        }
+    }
+
+    #[test]
+    fn invalid_tlv() {
+        let decode = |kind: Kind, bytes: &[u8]| {
+            let s = crate::encode_lower::<NoChecksum>(kind.hrp(), bytes).unwrap();
+            Bolt12String::from_str(&s)
+        };
+
+        assert_eq!(decode(Kind::Invoice, &[0x02, 0x01]), Err(DecodeError::TruncatedTlv));
+        assert_eq!(
+            decode(Kind::Invoice, &[0xfd, 0x00, 0x01, 0x00]),
+            Err(DecodeError::NonMinimalBigSize)
+        );
+        assert_eq!(
+            decode(Kind::InvoiceRequest, &[0x02, 0x00, 0x02, 0x00]),
+            Err(DecodeError::UnorderedRecords(2))
+        );
+
+        let s = crate::encode_lower::<NoChecksum>(Hrp::parse("lnx").unwrap(), &[]).unwrap();
+        assert!(matches!(Bolt12String::from_str(&s), Err(DecodeError::UnknownHrp(_))));
+    }
+
+    #[test]
+    fn insert_keeps_order() {
+        let mut request = Bolt12String::new(Kind::InvoiceRequest);
+        assert_eq!(request.insert(TlvRecord { record_type: 4, value: vec![4] }), None);
+        assert_eq!(request.insert(TlvRecord { record_type: 2, value: vec![2] }), None);
+        assert_eq!(request.insert(TlvRecord { record_type: 8, value: vec![8] }), None);
+        let replaced = request.insert(TlvRecord { record_type: 4, value: vec![5] });
+        assert_eq!(replaced, Some(TlvRecord { record_type: 4, value: vec![4] }));
+
+        let types = request.records().iter().map(|r| r.record_type).collect::<Vec<_>>();
+        assert_eq!(types, vec![2, 4, 8]);
+        assert_eq!(request.get(4), Some(&[5][..]));
+        assert_eq!(request.remove(8), Some(TlvRecord { record_type: 8, value: vec![8] }));
+        assert_eq!(request.remove(8), None);
+
+        let s = request.to_string();
+        assert!(s.starts_with("lnr1"));
+        assert_eq!(Bolt12String::from_str(&s).unwrap(), request);
+    }
+}
@@ -0,0 +1,589 @@
+// SPDX-License-Identifier: MIT
+
+//! Codex32 API - enables parsing and validating [BIP-93] secret shares.
+//!
+//! A codex32 string is a bech32 string with the human-readable part `ms`, a six character header
+//! (threshold, identifier and share index), a payload and a codex32 checksum. Strings of up to 93
+//! characters use the [`Codex32`] checksum and strings of 125 to 127 characters use the
+//! [`Codex32Long`] checksum.
+//!
+//! With an allocator, a secret split into `k`-of-`n` shares can be recovered from any `k` shares
+//! using [`recover_secret`], and further shares can be derived using [`interpolate`].
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use bech32::codex32::Codex32String;
+//! use bech32::Fe32;
+//!
+//! let share = Codex32String::new("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw")
+//!     .expect("valid codex32 string");
+//!
+//! assert_eq!(share.threshold(), 0);
+//! assert_eq!(share.identifier(), [Fe32::T, Fe32::E, Fe32::S, Fe32::T]);
+//! assert_eq!(share.share_index(), Fe32::S);
+//! assert_eq!(share.secret_bytes(), [0x31, 0x8c, 0x63, 0x18, 0xc6, 0x31, 0x8c, 0x63, 0x18, 0xc6, 0x31, 0x8c, 0x63, 0x18, 0xc6, 0x31]);
+//! # }
+//! ```
+//!
+//! [BIP-93]: <https://github.com/bitcoin/bips/blob/master/bip-0093.mediawiki>
+//! [`Codex32`]: crate::primitives::Codex32
+//! [`Codex32Long`]: crate::primitives::Codex32Long
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use core::ops::RangeInclusive;
+
+use crate::error::write_err;
+use crate::primitives::checksum::Checksum;
+use crate::primitives::correction::CorrectableError;
+use crate::primitives::decode::{
+    ByteIter, CheckedHrpstring, ChecksumError, InvalidResidueError, UncheckedHrpstring,
+    UncheckedHrpstringError,
+};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::{self, Hrp};
+#[cfg(feature = "alloc")]
+use crate::primitives::Field as _;
+use crate::primitives::{Codex32, Codex32Long};
+
+/// The number of characters in the header (threshold, identifier and share index).
+const HEADER_LENGTH: usize = 6;
+
+/// The range of string lengths that use the [`Codex32Long`] checksum.
+const LONG_STRING_LENGTHS: RangeInclusive<usize> = 125..=127;
+
+/// The range of valid payload lengths, in bytes (128 to 512 bit secrets).
+const PAYLOAD_BYTE_LENGTHS: RangeInclusive<usize> = 16..=64;
+
+/// A codex32 string that has been parsed and had its checksum and header validated.
+///
+/// # Examples
+///
+/// ```
+/// use bech32::codex32::Codex32String;
+/// use bech32::Fe32;
+///
+/// let s = "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM";
+/// let share = Codex32String::new(s).expect("valid codex32 string");
+///
+/// assert_eq!(share.threshold(), 2);
+/// assert_eq!(share.share_index(), Fe32::A);
+///
+/// // Do something with the share data.
+/// let _ = share.byte_iter();
+/// ```
+#[derive(Debug)]
+pub struct Codex32String<'s> {
+    /// The checked string with the header and checksum removed.
+    inner: CheckedHrpstring<'s>,
+    /// The threshold parameter, 0 for an unshared secret or 2-9 inclusive.
+    threshold: usize,
+    /// The four character identifier, shared by all shares of a secret.
+    identifier: [Fe32; 4],
+    /// The share index, [`Fe32::S`] for the secret itself.
+    share_index: Fe32,
+    /// True if the string uses the long codex32 checksum.
+    is_long: bool,
+    /// The whole data part as ASCII bytes, including the header and the checksum.
+    data_part_ascii: &'s [u8],
+}
+
+impl<'s> Codex32String<'s> {
+    /// Parses and validates a codex32 string.
+    ///
+    /// Selects the [`Codex32`] or [`Codex32Long`] checksum based on the string length, then
+    /// validates the human-readable part, the header, and the payload length.
+    #[inline]
+    pub fn new(s: &'s str) -> Result<Self, Codex32StringError> {
+        use Codex32StringError::*;
+
+        let unchecked = UncheckedHrpstring::new(s)?;
+        if unchecked.hrp() != hrp::MS {
+            return Err(InvalidHrp(unchecked.hrp()));
+        }
+        let data_part_ascii = unchecked.data_part_ascii();
+
+        let (mut inner, checksum_length, is_long) = if s.len() <= Codex32::CODE_LENGTH {
+            (unchecked.validate_and_remove_checksum::<Codex32>()?, Codex32::CHECKSUM_LENGTH, false)
+        } else if LONG_STRING_LENGTHS.contains(&s.len()) {
+            let checked = unchecked.validate_and_remove_checksum::<Codex32Long>()?;
+            (checked, Codex32Long::CHECKSUM_LENGTH, true)
+        } else {
+            return Err(InvalidLength(s.len()));
+        };
+
+        if inner.data_part_ascii_no_checksum().len() < HEADER_LENGTH {
+            return Err(InvalidLength(s.len()));
+        }
+        let header = inner.remove_prefix(HEADER_LENGTH);
+        let header_fe = |i: usize| Fe32::from_char_unchecked(header[i]);
+
+        let threshold = match header_fe(0).to_char() {
+            '0' => 0,
+            c @ '2'..='9' => usize::from(c as u8 - b'0'),
+            _ => return Err(InvalidThreshold(header_fe(0))),
+        };
+        let share_index = header_fe(5);
+        if threshold == 0 && share_index != Fe32::S {
+            return Err(InvalidShareIndex(share_index));
+        }
+
+        let payload_len = s.len() - hrp::MS.len() - 1 - HEADER_LENGTH - checksum_length;
+        if payload_len * 5 % 8 > 4 || !PAYLOAD_BYTE_LENGTHS.contains(&(payload_len * 5 / 8)) {
+            return Err(PayloadLength(payload_len));
+        }
+
+        Ok(Codex32String {
+            inner,
+            threshold,
+            identifier: [header_fe(1), header_fe(2), header_fe(3), header_fe(4)],
+            share_index,
+            is_long,
+            data_part_ascii,
+        })
+    }
+
+    /// Returns the threshold parameter.
+    ///
+    /// This is the number of shares required to recover the secret, or 0 if the secret is not
+    /// split (in which case the only share is the secret itself, with share index `s`).
+    #[inline]
+    pub fn threshold(&self) -> usize { self.threshold }
+
+    /// Returns the identifier, which is the same for all shares of a secret.
+    #[inline]
+    pub fn identifier(&self) -> [Fe32; 4] { self.identifier }
+
+    /// Returns the share index, [`Fe32::S`] if this share is the secret itself.
+    #[inline]
+    pub fn share_index(&self) -> Fe32 { self.share_index }
+
+    /// Returns true if this string uses the [`Codex32Long`] checksum.
+    #[inline]
+    pub fn is_long(&self) -> bool { self.is_long }
+
+    /// Returns the data part as ASCII bytes i.e., everything after the separator '1', including the
+    /// header and the checksum.
+    ///
+    /// The byte values are guaranteed to be valid bech32 characters.
+    #[inline]
+    pub fn data_part_ascii(&self) -> &'s [u8] { self.data_part_ascii }
+
+    /// Returns the payload as ASCII bytes i.e., everything after the header and before the checksum.
+    ///
+    /// The byte values are guaranteed to be valid bech32 characters.
+    #[inline]
+    pub fn payload_ascii(&self) -> &'s [u8] { self.inner.data_part_ascii_no_checksum() }
+
+    /// Returns an iterator that yields the payload as bytes, discarding any padding bits.
+    #[inline]
+    pub fn byte_iter(&self) -> ByteIter<'_> { self.inner.byte_iter() }
+
+    /// Returns the payload as bytes.
+    ///
+    /// If the share index is `s` this is the master secret, otherwise it is share data from which
+    /// the secret can be recovered.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn secret_bytes(&self) -> Vec<u8> { self.byte_iter().collect() }
+}
+
+/// Recovers the secret (the share with index `s`) from `threshold` shares of a split secret.
+///
+/// # Returns
+///
+/// The lowercase codex32 string of the secret share.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bech32::codex32::{self, Codex32String};
+///
+/// let a = Codex32String::new("MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM").unwrap();
+/// let c = Codex32String::new("MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN").unwrap();
+///
+/// let secret = codex32::recover_secret(&[a, c]).expect("valid set of shares");
+/// assert_eq!(secret, "ms12names6xqguzttxkeqnjsjzv4jv3nz5k3kwgsphuh6evw");
+///
+/// let secret = Codex32String::new(&secret).unwrap();
+/// let _ = secret.secret_bytes();
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn recover_secret(shares: &[Codex32String]) -> Result<String, InterpolateError> {
+    interpolate(shares, Fe32::S)
+}
+
+/// Derives the share with index `index` from `threshold` shares of a split secret.
+///
+/// Each character of the data part is treated as the evaluation, at the share index, of a
+/// polynomial of degree `threshold - 1` over GF(32). Lagrange interpolation on these polynomials
+/// yields the characters of the new share, including a valid checksum since the checksum is linear.
+///
+/// # Returns
+///
+/// The lowercase codex32 string of the derived share.
+#[cfg(feature = "alloc")]
+pub fn interpolate(shares: &[Codex32String], index: Fe32) -> Result<String, InterpolateError> {
+    use InterpolateError::*;
+
+    let first = shares.first().ok_or(NoShares)?;
+    // An unshared secret is a single share, from which only the secret can be derived.
+    let threshold = if first.threshold == 0 { 1 } else { first.threshold };
+    if shares.len() != threshold {
+        return Err(ShareCount { threshold, count: shares.len() });
+    }
+    if first.threshold == 0 && index != Fe32::S {
+        return Err(InvalidIndex(index));
+    }
+
+    for (i, share) in shares.iter().enumerate() {
+        if share.identifier != first.identifier {
+            return Err(MismatchedIdentifier);
+        }
+        if share.threshold != first.threshold {
+            return Err(MismatchedThreshold);
+        }
+        if share.data_part_ascii.len() != first.data_part_ascii.len() {
+            return Err(MismatchedLength);
+        }
+        if shares[..i].iter().any(|other| other.share_index == share.share_index) {
+            return Err(DuplicateIndex(share.share_index));
+        }
+    }
+
+    // Lagrange basis polynomials evaluated at `index`. Indices are distinct so we never divide by 0.
+    let weights = shares
+        .iter()
+        .map(|share| {
+            shares.iter().filter(|other| other.share_index != share.share_index).fold(
+                Fe32::ONE,
+                |acc, other| {
+                    acc * (index - other.share_index) / (share.share_index - other.share_index)
+                },
+            )
+        })
+        .collect::<Vec<Fe32>>();
+
+    let mut ret = String::with_capacity(hrp::MS.len() + 1 + first.data_part_ascii.len());
+    ret.push_str(hrp::MS.as_str());
+    ret.push('1');
+    for i in 0..first.data_part_ascii.len() {
+        let fe = shares.iter().zip(&weights).fold(Fe32::ZERO, |acc, (share, &weight)| {
+            acc + weight * Fe32::from_char_unchecked(share.data_part_ascii[i])
+        });
+        ret.push(fe.to_char());
+    }
+    Ok(ret)
+}
+
+/// An error while constructing a [`Codex32String`] type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Codex32StringError {
+    /// Error while parsing the encoded string.
+    Unchecked(UncheckedHrpstringError),
+    /// The human-readable part is not `ms`.
+    InvalidHrp(Hrp),
+    /// String length is not valid for either codex32 checksum.
+    InvalidLength(usize),
+    /// Invalid checksum.
+    Checksum(ChecksumError),
+    /// Invalid threshold character (must be 0 or 2-9 inclusive).
+    InvalidThreshold(Fe32),
+    /// Share index must be `s` when the threshold is 0.
+    InvalidShareIndex(Fe32),
+    /// Invalid payload length (in characters).
+    PayloadLength(usize),
+}
+
+impl fmt::Display for Codex32StringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Codex32StringError::*;
+
+        match *self {
+            Unchecked(ref e) => write_err!(f, "parsing failed"; e),
+            InvalidHrp(ref hrp) => write!(f, "invalid human-readable part {}, expected ms", hrp),
+            InvalidLength(len) => write!(f, "invalid codex32 string length {}", len),
+            Checksum(ref e) => write_err!(f, "invalid checksum"; e),
+            InvalidThreshold(fe) => write!(f, "invalid threshold {}", fe),
+            InvalidShareIndex(fe) =>
+                write!(f, "invalid share index {} for threshold 0, expected s", fe),
+            PayloadLength(len) =>
+                write!(f, "invalid payload length {} characters, must encode 16-64 bytes", len),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Codex32StringError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use Codex32StringError::*;
+
+        match *self {
+            Unchecked(ref e) => Some(e),
+            Checksum(ref e) => Some(e),
+            InvalidHrp(_) | InvalidLength(_) | InvalidThreshold(_) | InvalidShareIndex(_)
+            | PayloadLength(_) => None,
+        }
+    }
+}
+
+impl From<UncheckedHrpstringError> for Codex32StringError {
+    #[inline]
+    fn from(e: UncheckedHrpstringError) -> Self { Self::Unchecked(e) }
+}
+
+impl From<ChecksumError> for Codex32StringError {
+    #[inline]
+    fn from(e: ChecksumError) -> Self { Self::Checksum(e) }
+}
+
+impl CorrectableError for Codex32StringError {
+    fn residue_error(&self) -> Option<&InvalidResidueError> {
+        match self {
+            Codex32StringError::Checksum(ref e) => e.residue_error(),
+            _ => None,
+        }
+    }
+}
+
+/// An error while interpolating codex32 shares.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InterpolateError {
+    /// No shares were provided.
+    NoShares,
+    /// The number of shares does not match the threshold.
+    ShareCount {
+        /// The threshold of the shares.
+        threshold: usize,
+        /// The number of shares provided.
+        count: usize,
+    },
+    /// The shares do not all have the same identifier.
+    MismatchedIdentifier,
+    /// The shares do not all have the same threshold.
+    MismatchedThreshold,
+    /// The shares do not all have the same length.
+    MismatchedLength,
+    /// Two shares have the same share index.
+    DuplicateIndex(Fe32),
+    /// Only the secret (index `s`) can be derived from an unshared secret.
+    InvalidIndex(Fe32),
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for InterpolateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use InterpolateError::*;
+
+        match *self {
+            NoShares => write!(f, "no shares provided"),
+            ShareCount { threshold, count } =>
+                write!(f, "{} shares provided for a threshold of {}", count, threshold),
+            MismatchedIdentifier => write!(f, "shares have different identifiers"),
+            MismatchedThreshold => write!(f, "shares have different thresholds"),
+            MismatchedLength => write!(f, "shares have different lengths"),
+            DuplicateIndex(fe) => write!(f, "duplicate share index {}", fe),
+            InvalidIndex(fe) => write!(f, "cannot derive share {} from an unshared secret", fe),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InterpolateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use InterpolateError::*;
+
+        match *self {
+            NoShares
+            | ShareCount { .. }
+            | MismatchedIdentifier
+            | MismatchedThreshold
+            | MismatchedLength
+            | DuplicateIndex(_)
+            | InvalidIndex(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "alloc")]
+mod tests {
+    use super::*;
+
+    // Test vectors from BIP-93.
+    const VECTOR_1: &str = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+    const VECTOR_2_A: &str = "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM";
+    const VECTOR_2_C: &str = "MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN";
+    const VECTOR_2_D: &str = "MS12NAMEDLL4F8JLH4E5VDVULDLFXU2JHDNLSM97XVENRXEG";
+    const VECTOR_2_S: &str = "MS12NAMES6XQGUZTTXKEQNJSJZV4JV3NZ5K3KWGSPHUH6EVW";
+    const VECTOR_5: &str = "MS100C8VSM32ZXFGUHPCHTLUPZRY9X8GF2TVDW0S3JN54KHCE6MUA7LQPZYGSFJD6AN074RXVCEMLH8WU3TK925ACDEFGHJKLMNPQRSTUVWXY06FHPV80UNDVARHRAK";
+
+    #[test]
+    fn parse_unshared_secret() {
+        let share = Codex32String::new(VECTOR_1).expect("valid codex32 string");
+        assert_eq!(share.threshold(), 0);
+        assert_eq!(share.identifier(), [Fe32::T, Fe32::E, Fe32::S, Fe32::T]);
+        assert_eq!(share.share_index(), Fe32::S);
+        assert!(!share.is_long());
+        assert!(share.byte_iter().eq([0x31, 0x8c, 0x63, 0x18, 0xc6]
+            .iter()
+            .copied()
+            .cycle()
+            .take(16)));
+    }
+
+    #[test]
+    fn parse_share() {
+        let share = Codex32String::new(VECTOR_2_A).expect("valid codex32 string");
+        assert_eq!(share.threshold(), 2);
+        assert_eq!(share.identifier(), [Fe32::N, Fe32::A, Fe32::M, Fe32::E]);
+        assert_eq!(share.share_index(), Fe32::A);
+        assert_eq!(share.payload_ascii(), b"320ZYXWVUTSRQPNMLKJHGFEDCA");
+    }
+
+    #[test]
+    fn parse_long_secret() {
+        let share = Codex32String::new(VECTOR_5).expect("valid codex32 string");
+        assert_eq!(share.threshold(), 0);
+        assert!(share.is_long());
+        assert_eq!(share.byte_iter().len(), 64);
+        assert!(share.byte_iter().take(4).eq([0xdc, 0x54, 0x23, 0x25].iter().copied()));
+    }
+
+    #[test]
+    fn invalid_hrp() {
+        let s = "xs10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+        assert!(matches!(Codex32String::new(s), Err(Codex32StringError::InvalidHrp(_))));
+    }
+
+    #[test]
+    fn invalid_checksum() {
+        let s = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlx";
+        let err = Codex32String::new(s).unwrap_err();
+        assert!(matches!(err, Codex32StringError::Checksum(_)));
+        assert!(err.residue_error().is_some());
+    }
+
+    #[test]
+    fn invalid_length() {
+        // 100 characters is too long for a regular checksum and too short for a long one.
+        let s = "ms10tests".to_owned() + &"x".repeat(91);
+        assert_eq!(Codex32String::new(&s).unwrap_err(), Codex32StringError::InvalidLength(100));
+    }
+
+    #[test]
+    fn invalid_threshold() {
+        // Threshold must be a digit, checksum recomputed for the modified header.
+        let s = encode_with_checksum("ptestsxxxxxxxxxxxxxxxxxxxxxxxxxx");
+        assert_eq!(
+            Codex32String::new(&s).unwrap_err(),
+            Codex32StringError::InvalidThreshold(Fe32::P)
+        );
+    }
+
+    #[test]
+    fn invalid_share_index() {
+        let s = encode_with_checksum("0testaxxxxxxxxxxxxxxxxxxxxxxxxxx");
+        assert_eq!(
+            Codex32String::new(&s).unwrap_err(),
+            Codex32StringError::InvalidShareIndex(Fe32::A)
+        );
+    }
+
+    #[test]
+    fn invalid_payload_length() {
+        // 15 bytes is below the minimum secret length.
+        let s = encode_with_checksum("0testsxxxxxxxxxxxxxxxxxxxxxxxx");
+        assert_eq!(Codex32String::new(&s).unwrap_err(), Codex32StringError::PayloadLength(24));
+    }
+
+    #[test]
+    fn recover_secret_from_shares() {
+        let a = Codex32String::new(VECTOR_2_A).unwrap();
+        let c = Codex32String::new(VECTOR_2_C).unwrap();
+        let secret = recover_secret(&[a, c]).expect("valid set of shares");
+        assert_eq!(secret, VECTOR_2_S.to_lowercase());
+
+        let secret = Codex32String::new(&secret).unwrap();
+        assert_eq!(
+            secret.secret_bytes(),
+            [
+                0xd1, 0x80, 0x8e, 0x09, 0x6b, 0x35, 0xb2, 0x09, 0xca, 0x12, 0x13, 0x2b, 0x26, 0x46,
+                0x62, 0xa5
+            ]
+        );
+    }
+
+    #[test]
+    fn derive_new_share() {
+        let a = Codex32String::new(VECTOR_2_A).unwrap();
+        let c = Codex32String::new(VECTOR_2_C).unwrap();
+        let d = interpolate(&[a, c], Fe32::D).expect("valid set of shares");
+        assert_eq!(d, VECTOR_2_D.to_lowercase());
+
+        // Deriving an existing share returns it unchanged.
+        let a = Codex32String::new(VECTOR_2_A).unwrap();
+        let c = Codex32String::new(VECTOR_2_C).unwrap();
+        assert_eq!(interpolate(&[a, c], Fe32::A).unwrap(), VECTOR_2_A.to_lowercase());
+    }
+
+    #[test]
+    fn recover_unshared_secret() {
+        let s = Codex32String::new(VECTOR_1).unwrap();
+        assert_eq!(recover_secret(&[s]).unwrap(), VECTOR_1);
+
+        let s = Codex32String::new(VECTOR_1).unwrap();
+        assert_eq!(
+            interpolate(&[s], Fe32::A).unwrap_err(),
+            InterpolateError::InvalidIndex(Fe32::A)
+        );
+    }
+
+    #[test]
+    fn interpolate_errors() {
+        use InterpolateError::*;
+
+        assert_eq!(recover_secret(&[]).unwrap_err(), NoShares);
+
+        let a = Codex32String::new(VECTOR_2_A).unwrap();
+        assert_eq!(recover_secret(&[a]).unwrap_err(), ShareCount { threshold: 2, count: 1 });
+
+        let a = Codex32String::new(VECTOR_2_A).unwrap();
+        let a2 = Codex32String::new(VECTOR_2_A).unwrap();
+        assert_eq!(recover_secret(&[a, a2]).unwrap_err(), DuplicateIndex(Fe32::A));
+
+        let a = Codex32String::new(VECTOR_2_A).unwrap();
+        let other = encode_with_checksum("2testcxxxxxxxxxxxxxxxxxxxxxxxxxx");
+        let other = Codex32String::new(&other).unwrap();
+        assert_eq!(recover_secret(&[a, other]).unwrap_err(), MismatchedIdentifier);
+
+        let a = Codex32String::new(VECTOR_2_A).unwrap();
+        let other = encode_with_checksum("3namecxxxxxxxxxxxxxxxxxxxxxxxxxx");
+        let other = Codex32String::new(&other).unwrap();
+        assert_eq!(recover_secret(&[a, other]).unwrap_err(), MismatchedThreshold);
+
+        let a = Codex32String::new(VECTOR_2_A).unwrap();
+        let other = encode_with_checksum("2namecxxxxxxxxxxxxxxxxxxxxxxxxxxxx");
+        let other = Codex32String::new(&other).unwrap();
+        assert_eq!(recover_secret(&[a, other]).unwrap_err(), MismatchedLength);
+    }
+
+    fn encode_with_checksum(data: &str) -> String {
+        use crate::primitives::iter::Fe32IterExt as _;
+
+        data.bytes()
+            .map(Fe32::from_char_unchecked)
+            .with_checksum::<Codex32>(&hrp::MS)
+            .chars()
+            .collect()
+    }
+}
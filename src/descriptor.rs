@@ -0,0 +1,334 @@
+// SPDX-License-Identifier: MIT
+
+//! Output Descriptor Checksum API - enables computing and verifying [BIP-380] checksums.
+//!
+//! Descriptors are not bech32 strings, each character is first mapped to a pair of symbols (its
+//! position within a group of 32 characters, and, bundled three at a time, its group) and the
+//! resulting stream of field elements is checksummed with the [`DescriptorChecksum`] BCH code.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use bech32::descriptor;
+//!
+//! let checksum = descriptor::descriptor_checksum("raw(deadbeef)").expect("valid descriptor");
+//! assert_eq!(checksum, "89f8spxm");
+//!
+//! assert!(descriptor::verify_descriptor("raw(deadbeef)#89f8spxm").is_ok());
+//! assert!(descriptor::verify_descriptor("raw(deadbeef)#89f8spxn").is_err());
+//! # }
+//! ```
+//!
+//! # Error correction
+//!
+//! Since the checksum is a BCH code it can be used to correct errors with a [`Corrector`]. Error
+//! locations yielded by the corrector refer to the symbol stream, use [`map_correction`] to map
+//! them back onto the descriptor string.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use bech32::{descriptor, CorrectableError, DescriptorChecksum};
+//!
+//! let s = "raw(deadbeaf)#89f8spxm"; // The second 'e' of "beef" was changed to an 'a'.
+//! let err = descriptor::verify_descriptor(s).unwrap_err();
+//!
+//! let ctx = err.correction_context::<DescriptorChecksum>().expect("checksum error");
+//! let mut iter = ctx.bch_errors().expect("correctable error");
+//! let (location, error) = iter.next().unwrap();
+//!
+//! assert_eq!(descriptor::map_correction(s, location, error), Some((10, 'e')));
+//! # }
+//! ```
+//!
+//! [BIP-380]: <https://github.com/bitcoin/bips/blob/master/bip-0380.mediawiki>
+//! [`DescriptorChecksum`]: crate::primitives::DescriptorChecksum
+//! [`Corrector`]: crate::primitives::correction::Corrector
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::string::String;
+use core::fmt;
+
+use crate::error::write_err;
+#[cfg(feature = "alloc")]
+use crate::primitives::checksum::PackedFe32;
+use crate::primitives::checksum::{Checksum, Engine};
+use crate::primitives::correction::CorrectableError;
+use crate::primitives::decode::InvalidResidueError;
+use crate::primitives::gf32::Fe32;
+use crate::primitives::DescriptorChecksum;
+
+/// The characters that may appear in a descriptor, in groups of 32.
+///
+/// Copied from Bitcoin Core src/script/descriptor.cpp
+const INPUT_CHARSET: &[u8; 95] = b"0123456789()[],'/*abcdefgh@:$%{}\
+IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~\
+ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+
+/// Separator between the descriptor and its checksum.
+const SEP: char = '#';
+
+/// Computes the checksum of a descriptor (without a `#` suffix).
+///
+/// # Returns
+///
+/// The eight character checksum, to be appended to the descriptor after a `#` character.
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn descriptor_checksum(descriptor: &str) -> Result<String, DescriptorError> {
+    let mut engine = descriptor_engine(descriptor)?;
+    engine.input_target_residue();
+
+    let residue = *engine.residue();
+    let len = DescriptorChecksum::CHECKSUM_LENGTH;
+    Ok((0..len).rev().map(|i| Fe32(residue.unpack(i)).to_char()).collect())
+}
+
+/// Verifies the checksum of a descriptor of the form `descriptor#checksum`.
+#[inline]
+pub fn verify_descriptor(s: &str) -> Result<(), DescriptorError> {
+    use DescriptorError::*;
+
+    let (descriptor, checksum) = match s.find(SEP) {
+        Some(pos) => (&s[..pos], &s[pos + 1..]),
+        None => return Err(MissingChecksum),
+    };
+    if checksum.contains(SEP) {
+        return Err(MultipleSeparators);
+    }
+    if checksum.len() != DescriptorChecksum::CHECKSUM_LENGTH {
+        return Err(InvalidChecksumLength(checksum.len()));
+    }
+
+    let mut engine = descriptor_engine(descriptor)?;
+    for c in checksum.chars() {
+        match Fe32::from_char(c) {
+            // Only lowercase characters are valid in a descriptor checksum.
+            Ok(fe) if fe.to_char() == c => engine.input_fe(fe),
+            _ => return Err(InvalidChecksumChar(c)),
+        }
+    }
+
+    let residue = *engine.residue();
+    if residue != DescriptorChecksum::TARGET_RESIDUE {
+        return Err(InvalidResidue(InvalidResidueError::new(
+            residue,
+            DescriptorChecksum::TARGET_RESIDUE,
+        )));
+    }
+    Ok(())
+}
+
+/// Maps an error found by a [`DescriptorChecksum`] correction context back onto a descriptor.
+///
+/// `s` is the descriptor including its `#` and checksum, and `location` and `error` are an item
+/// yielded by [`ErrorIterator`].
+///
+/// # Returns
+///
+/// The byte index in `s` of the erroneous character and its corrected value, or `None` if the
+/// error does not correspond to a single character (for example if the correction would move a
+/// character to a different group, or if the location is out of range).
+///
+/// [`ErrorIterator`]: crate::primitives::correction::ErrorIterator
+pub fn map_correction(s: &str, location: usize, error: Fe32) -> Option<(usize, char)> {
+    let checksum_len = DescriptorChecksum::CHECKSUM_LENGTH;
+    if s.len() < checksum_len + 1 || !s.is_char_boundary(s.len() - checksum_len - 1) {
+        return None;
+    }
+
+    if location < checksum_len {
+        let idx = s.len() - location - 1;
+        let fe = Fe32::from_char(char::from(s.as_bytes()[idx])).ok()?; // Rejects non-ASCII bytes.
+        return Some((idx, (fe + error).to_char()));
+    }
+
+    // Every three characters are followed by their group symbol, as is a trailing partial group.
+    let descriptor = &s.as_bytes()[..s.len() - checksum_len - 1];
+    let n_symbols = descriptor.len() + (descriptor.len() + 2) / 3;
+    let pos = n_symbols.checked_sub(location - checksum_len + 1)?;
+    let (block, offset) = (pos / 4, pos % 4);
+    let idx = block * 3 + offset;
+    if offset == 3 || idx >= descriptor.len() {
+        return None; // Error is in a group symbol.
+    }
+
+    let v = INPUT_CHARSET.iter().position(|&b| b == descriptor[idx])?;
+    let corrected = (v & !31) | usize::from((Fe32(v as u8 & 31) + error).to_u8());
+    // The last group of `INPUT_CHARSET` has only 31 symbols.
+    Some((idx, char::from(*INPUT_CHARSET.get(corrected)?)))
+}
+
+/// Returns a checksum engine which has had `descriptor` input into it.
+fn descriptor_engine(descriptor: &str) -> Result<Engine<DescriptorChecksum>, DescriptorError> {
+    let mut engine = Engine::new();
+    let mut groups = [0; 3];
+    let mut n_groups = 0;
+
+    for c in descriptor.chars() {
+        let v = INPUT_CHARSET
+            .iter()
+            .position(|&b| char::from(b) == c)
+            .ok_or(DescriptorError::InvalidChar(c))? as u8;
+
+        engine.input_fe(Fe32(v & 31));
+        groups[n_groups] = v >> 5;
+        n_groups += 1;
+        if n_groups == 3 {
+            engine.input_fe(Fe32(groups[0] * 9 + groups[1] * 3 + groups[2]));
+            n_groups = 0;
+        }
+    }
+    match n_groups {
+        1 => engine.input_fe(Fe32(groups[0])),
+        2 => engine.input_fe(Fe32(groups[0] * 3 + groups[1])),
+        _ => {}
+    }
+    Ok(engine)
+}
+
+/// An error while computing or verifying a descriptor checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DescriptorError {
+    /// No `#` separating the descriptor from its checksum.
+    MissingChecksum,
+    /// More than one `#` in the descriptor.
+    MultipleSeparators,
+    /// The checksum is not eight characters long.
+    InvalidChecksumLength(usize),
+    /// A character in the descriptor is not valid in descriptors.
+    InvalidChar(char),
+    /// A character in the checksum is not a lowercase bech32 character.
+    InvalidChecksumChar(char),
+    /// The checksum does not match the descriptor.
+    InvalidResidue(InvalidResidueError),
+}
+
+impl fmt::Display for DescriptorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DescriptorError::*;
+
+        match *self {
+            MissingChecksum => write!(f, "missing descriptor checksum"),
+            MultipleSeparators => write!(f, "multiple '#' symbols"),
+            InvalidChecksumLength(len) =>
+                write!(f, "invalid checksum length {}, expected 8 characters", len),
+            InvalidChar(c) => write!(f, "invalid character in descriptor: {}", c),
+            InvalidChecksumChar(c) => write!(f, "invalid character in checksum: {}", c),
+            InvalidResidue(ref e) => write_err!(f, "checksum mismatch"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DescriptorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use DescriptorError::*;
+
+        match *self {
+            InvalidResidue(ref e) => Some(e),
+            MissingChecksum
+            | MultipleSeparators
+            | InvalidChecksumLength(_)
+            | InvalidChar(_)
+            | InvalidChecksumChar(_) => None,
+        }
+    }
+}
+
+impl From<InvalidResidueError> for DescriptorError {
+    #[inline]
+    fn from(e: InvalidResidueError) -> Self { Self::InvalidResidue(e) }
+}
+
+impl CorrectableError for DescriptorError {
+    fn residue_error(&self) -> Option<&InvalidResidueError> {
+        match self {
+            DescriptorError::InvalidResidue(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "alloc")]
+mod tests {
+    use super::*;
+
+    // Test vectors from BIP-380.
+    const DESCRIPTOR: &str = "raw(deadbeef)";
+    const CHECKSUM: &str = "89f8spxm";
+
+    #[test]
+    fn checksum() {
+        assert_eq!(descriptor_checksum(DESCRIPTOR).unwrap(), CHECKSUM);
+    }
+
+    #[test]
+    fn verify() {
+        use DescriptorError::*;
+
+        assert!(verify_descriptor("raw(deadbeef)#89f8spxm").is_ok());
+        assert_eq!(verify_descriptor("raw(deadbeef)"), Err(MissingChecksum));
+        assert_eq!(verify_descriptor("raw(dead#beef)#89f8spxm"), Err(MultipleSeparators));
+        // Even if the checksum matches everything before the last `#`.
+        let s = format!("a#b#{}", descriptor_checksum("a#b").unwrap());
+        assert_eq!(verify_descriptor(&s), Err(MultipleSeparators));
+        assert_eq!(verify_descriptor("raw(deadbeef)#89f8spxmx"), Err(InvalidChecksumLength(9)));
+        assert_eq!(verify_descriptor("raw(deadbeef)#89f8spx"), Err(InvalidChecksumLength(7)));
+        assert_eq!(verify_descriptor("raw(deadbeef)#89F8SPXM"), Err(InvalidChecksumChar('F')));
+        assert_eq!(verify_descriptor("raw(Ü)#00000000"), Err(InvalidChar('Ü')));
+        assert!(matches!(verify_descriptor("raw(deadbeef)#89f8spxn"), Err(InvalidResidue(_))));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let descriptors = [
+            "pk(0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)",
+            "sh(multi(2,[00000000/111'/222]xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL/0,xpub68NZiKmJWnxxS6aaHmn81bvJeTESw724CRDs6HbuccFQN9Ku14VQrADWgqbhhTHBaohPX4CjNLf9fq9MYo6oDaPPLPxSb7gwQN3ih19Zm4Y/0))",
+            "wpkh([ffffffff/13']xprv9vHkqa6EV4sPZHYqZznhT2NPtPCjKuDKGY38FBWLvgaDx45zo9WQRUT3dKYnjwih2yJD9mkrocEZXo1ex8G81dwSM1fwqWpWkeS3v86pgKt/1/2/*)",
+        ];
+        for descriptor in descriptors.iter() {
+            let checksum = descriptor_checksum(descriptor).unwrap();
+            let s = format!("{}#{}", descriptor, checksum);
+            assert!(verify_descriptor(&s).is_ok());
+        }
+    }
+
+    #[test]
+    fn correct_descriptor_char() {
+        // 'd' replaced by 'c', both are in the same group so this is a single symbol error.
+        let s = "raw(deadbeef)#89f8spxm".replacen('d', "c", 1);
+        let err = verify_descriptor(&s).unwrap_err();
+        let ctx = err.correction_context::<DescriptorChecksum>().unwrap();
+        let mut iter = ctx.bch_errors().unwrap();
+        let (location, error) = iter.next().unwrap();
+        assert_eq!(iter.next(), None);
+        assert_eq!(map_correction(&s, location, error), Some((4, 'd')));
+    }
+
+    #[test]
+    fn correct_checksum_char() {
+        let s = "raw(deadbeef)#89f8spqm";
+        let err = verify_descriptor(s).unwrap_err();
+        let ctx = err.correction_context::<DescriptorChecksum>().unwrap();
+        let mut iter = ctx.bch_errors().unwrap();
+        let (location, error) = iter.next().unwrap();
+        assert_eq!(iter.next(), None);
+        assert_eq!(map_correction(s, location, error), Some((20, 'x')));
+    }
+
+    #[test]
+    fn map_correction_group_symbol() {
+        // Location 14 is the group symbol of "adb", location 8 that of the trailing ")".
+        assert_eq!(map_correction("raw(deadbeef)#89f8spxm", 14, Fe32::P), None);
+        assert_eq!(map_correction("raw(deadbeef)#89f8spxm", 8, Fe32::P), None);
+        assert_eq!(map_correction("raw(deadbeef)#89f8spxm", 9, Fe32::P), Some((12, '(')));
+        assert_eq!(map_correction("raw(deadbeef)#89f8spxm", 100, Fe32::P), None);
+        // Correcting ' ' to the nonexistent 32nd symbol of its group.
+        assert_eq!(map_correction("raw( )#abcdefgh", 10, Fe32::P), None);
+        // Non-ASCII checksum character.
+        assert_eq!(map_correction("raw(a)#1234567ü", 0, Fe32::P), None);
+    }
+}
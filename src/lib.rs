@@ -95,13 +95,16 @@
 //!
 //! ```
 //! # #[cfg(feature = "alloc")] {
+//! use bech32::primitives::decode::CheckedHrpstring;
 //! use bech32::{Checksum, Fe32, Fe1024};
 //!
 //! /// The codex32 checksum algorithm, defined in BIP-93.
+//! ///
+//! /// This is provided by the library as [`bech32::Codex32`], it is repeated here as an example.
 //! #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-//! pub enum Codex32 {}
+//! pub enum MyCodex32 {}
 //!
-//! impl Checksum for Codex32 {
+//! impl Checksum for MyCodex32 {
 //!     type MidstateRepr = u128;
 //!     type CorrectionField = bech32::primitives::gf32_ext::Fe32Ext<2>;
 //!     const ROOT_GENERATOR: Self::CorrectionField = Fe1024::new([Fe32::_9, Fe32::_9]);
@@ -120,6 +123,11 @@
 //!     const TARGET_RESIDUE: u128 = 0x10ce0795c2fd1e62a;
 //! }
 //!
+//! assert_eq!(MyCodex32::GENERATOR_SH, bech32::Codex32::GENERATOR_SH);
+//! assert_eq!(MyCodex32::TARGET_RESIDUE, bech32::Codex32::TARGET_RESIDUE);
+//!
+//! let share = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+//! assert!(CheckedHrpstring::new::<MyCodex32>(share).is_ok());
 //! # }
 //! ```
 //!
@@ -146,21 +154,41 @@ extern crate alloc;
 #[cfg(any(test, feature = "std"))]
 extern crate core;
 
+#[cfg(feature = "alloc")]
+pub mod batch;
+#[cfg(feature = "alloc")]
+pub mod bolt12;
+pub mod codex32;
+pub mod descriptor;
 mod error;
 pub mod hrp;
+#[cfg(feature = "alloc")]
+pub mod lightning;
+#[cfg(feature = "alloc")]
+pub mod multipart;
+#[cfg(feature = "alloc")]
+pub mod nip19;
 pub mod primitives;
 pub mod segwit;
+#[cfg(feature = "serde")]
+mod serde_utils;
+pub mod silent_payments;
+pub mod writer;
 
 #[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
 use alloc::{string::String, vec::Vec};
 use core::fmt;
 
 use crate::error::write_err;
-#[cfg(doc)]
+#[cfg(feature = "alloc")]
+use crate::primitives::correction;
+#[cfg(any(doc, feature = "alloc"))]
 use crate::primitives::decode::CheckedHrpstring;
 use crate::primitives::decode::CodeLengthError;
 #[cfg(feature = "alloc")]
-use crate::primitives::decode::{ChecksumError, UncheckedHrpstring, UncheckedHrpstringError};
+use crate::primitives::decode::{
+    ChecksumCandidate, ChecksumError, ErasedHrpstring, UncheckedHrpstring, UncheckedHrpstringError,
+};
 
 #[rustfmt::skip]                // Keep public re-exports separate.
 #[doc(inline)]
@@ -171,10 +199,12 @@ pub use {
     crate::primitives::gf32_ext::{Fe1024, Fe32768},
     crate::primitives::hrp::Hrp,
     crate::primitives::iter::{ByteIterExt, Fe32IterExt},
-    crate::primitives::{Bech32, Bech32m, NoChecksum},
+    crate::primitives::{Bech32, Bech32m, Codex32, Codex32Long, DescriptorChecksum, NoChecksum},
 };
 #[cfg(feature = "alloc")]
 pub use crate::primitives::checksum::PrintImpl;
+#[cfg(feature = "alloc")]
+pub use crate::primitives::correction::{CharCorrection, CorrectionError, HrpEdit};
 
 // Write to fmt buffer, small during testing to exercise full code path.
 #[cfg(not(test))]
@@ -233,6 +263,167 @@ pub fn decode(s: &str) -> Result<(Hrp, Vec<u8>), DecodeError> {
     Ok((checked.hrp(), checked.byte_iter().collect()))
 }
 
+/// The checksum algorithm used by a string decoded with [`decode_with_variant`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// The original checksum algorithm defined in [BIP-173].
+    ///
+    /// [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>
+    Bech32,
+    /// The modified checksum algorithm defined in [BIP-350].
+    ///
+    /// [BIP-350]: <https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki>
+    Bech32m,
+}
+
+/// Decodes a bech32 encoded string, reporting which checksum algorithm was used.
+///
+/// Like [`decode`] this accepts either a bech32 or a bech32m checksum, both are checked with a
+/// single pass over the string. To detect other checksum algorithms use
+/// [`UncheckedHrpstring::detect_checksum`].
+///
+/// # Returns
+///
+/// The checksum variant, the human-readable part and the encoded data with the checksum removed.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bech32::{decode_with_variant, Variant};
+///
+/// const BECH32: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2atsghld7";
+/// const BECH32M: &str = "abc14w46h2at4w46h2at4w46h2at4w46h2at958ngu";
+///
+/// let (variant, _hrp, _data) = decode_with_variant(&BECH32).expect("valid bech32 string");
+/// assert_eq!(variant, Variant::Bech32);
+/// let (variant, _hrp, _data) = decode_with_variant(&BECH32M).expect("valid bech32m string");
+/// assert_eq!(variant, Variant::Bech32m);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn decode_with_variant(s: &str) -> Result<(Variant, Hrp, Vec<u8>), DecodeError> {
+    let candidates = [ChecksumCandidate::new::<Bech32m>(), ChecksumCandidate::new::<Bech32>()];
+
+    let unchecked = UncheckedHrpstring::new(s)?;
+    let (index, checked) = unchecked.detect_checksum(&candidates).map_err(DecodeError::Checksum)?;
+    let variant = if index == 0 { Variant::Bech32m } else { Variant::Bech32 };
+
+    Ok((variant, checked.hrp(), checked.byte_iter().collect()))
+}
+
+/// Corrects errors in a checksummed string using the `Ck` algorithm.
+///
+/// If the string already has a valid checksum it is returned unchanged. Otherwise the errors are
+/// located and fixed, and the corrected string is validated again before being returned.
+///
+/// # Returns
+///
+/// The corrected string and the corrected characters as `(position, old, new)` tuples, where
+/// `position` is the index of the character in the string. The case of the input is preserved.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bech32::{correct, Bech32};
+///
+/// // The last character should be a 'q'.
+/// let (corrected, corrections) = correct::<Bech32>("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdx").unwrap();
+/// assert_eq!(corrected, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+/// assert_eq!(corrections, [(41, 'x', 'q')]);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn correct<Ck: Checksum>(s: &str) -> Result<(String, Vec<CharCorrection>), CorrectionError> {
+    let unchecked = UncheckedHrpstring::new(s)?;
+    let err = match unchecked.validate_checksum::<Ck>() {
+        Ok(()) => return Ok((s.into(), Vec::new())),
+        Err(e) => e,
+    };
+    let corrector = match err.correction_context::<Ck>() {
+        Some(corrector) => corrector,
+        None if err.residue_error().is_some() => return Err(CorrectionError::TooManyErrors),
+        None => return Err(CorrectionError::Checksum(err)),
+    };
+
+    let (corrected, corrections) = correction::correct_string(s, &corrector, None)?;
+    CheckedHrpstring::new::<Ck>(&corrected).map_err(CorrectionError::Revalidation)?;
+    Ok((corrected, corrections))
+}
+
+/// Corrects errors in a checksummed string using the `Ck` algorithm, where illegible characters
+/// have been replaced by the erasure `marker`.
+///
+/// Because the locations of erased characters are known, twice as many erasures as unknown
+/// errors can be corrected. See [`correct`] and [`ErasedHrpstring`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bech32::{correct_with_erasures, Bech32};
+///
+/// let (corrected, corrections) =
+///     correct_with_erasures::<Bech32>("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5?d?", '?').unwrap();
+/// assert_eq!(corrected, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+/// assert_eq!(corrections, [(39, '?', 'm'), (41, '?', 'q')]);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn correct_with_erasures<Ck: Checksum>(
+    s: &str,
+    marker: char,
+) -> Result<(String, Vec<CharCorrection>), CorrectionError> {
+    let erased = ErasedHrpstring::new(s, marker)?;
+    let corrector = match erased.correction_context::<Ck, { correction::NO_ALLOC_MAX_LENGTH }>() {
+        Some(corrector) => corrector,
+        None => match erased.validate_checksum::<Ck>() {
+            Ok(()) => return Ok((s.into(), Vec::new())),
+            Err(e) if e.residue_error().is_some() => return Err(CorrectionError::TooManyErrors),
+            Err(e) => return Err(CorrectionError::Checksum(e)),
+        },
+    };
+
+    let (corrected, corrections) = correction::correct_string(s, &corrector, Some(marker))?;
+    CheckedHrpstring::new::<Ck>(&corrected).map_err(CorrectionError::Revalidation)?;
+    Ok((corrected, corrections))
+}
+
+/// Corrects errors in a checksummed string using the `Ck` algorithm, where the human-readable part
+/// is known to be one of `expected`.
+///
+/// The closest expected HRP (by edit distance) is substituted for the HRP of the string and the
+/// data part is corrected as in [`correct`]. If that fails the next closest is tried, and so on.
+/// This fixes corrupted HRPs, which [`correct`] cannot do.
+///
+/// # Returns
+///
+/// The corrected string, the HRP substitution if one was made, and the corrected characters of
+/// the data part. Their positions are indices into the corrected string.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use bech32::{correct_with_hrp, hrp, Bech32};
+///
+/// let (corrected, edit, corrections) =
+///     correct_with_hrp::<Bech32>("bx1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdx", &[hrp::BC]).unwrap();
+/// assert_eq!(corrected, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+/// assert_eq!(edit.unwrap().original, "bx");
+/// assert_eq!(corrections, [(41, 'x', 'q')]);
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn correct_with_hrp<Ck: Checksum>(
+    s: &str,
+    expected: &[Hrp],
+) -> Result<(String, Option<HrpEdit>, Vec<CharCorrection>), CorrectionError> {
+    let ((corrected, corrections), edit) = correction::correct_hrp(s, expected, correct::<Ck>)?;
+    Ok((corrected, edit, corrections))
+}
+
 /// Encodes `data` as a lowercase bech32 encoded string.
 ///
 /// Encoded string will be prefixed with the `hrp` and have a checksum appended as specified by the
@@ -674,6 +865,115 @@ mod tests {
         assert_eq!(data, DATA);
     }
 
+    #[test]
+    fn decode_with_variant_reports_checksum() {
+        let s = "test1lu08d6qejxtdg4y5r3zarvary0c5xw7kmz4lky";
+        let (variant, hrp, data) = decode_with_variant(s).expect("valid bech32m string");
+        assert_eq!(variant, Variant::Bech32m);
+        assert_eq!(hrp, Hrp::parse_unchecked("test"));
+        assert_eq!(data, DATA);
+
+        let s = "TEST1LU08D6QEJXTDG4Y5R3ZARVARY0C5XW7KW79NNX";
+        let (variant, _, data) = decode_with_variant(s).expect("valid bech32 string");
+        assert_eq!(variant, Variant::Bech32);
+        assert_eq!(data, DATA);
+
+        let s = "test1lu08d6qejxtdg4y5r3zarvary0c5xw7kw79nnq";
+        assert!(matches!(decode_with_variant(s), Err(DecodeError::Checksum(_))));
+    }
+
+    #[test]
+    fn correct_fixes_errors() {
+        let valid = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        assert_eq!(correct::<Bech32>(valid).unwrap(), (valid.to_string(), vec![]));
+
+        let (corrected, corrections) =
+            correct::<Bech32>("bc1qar0srrr7xfkvy5l64qlydnw9re59gtzzwf5mdq").unwrap();
+        assert_eq!(corrected, valid);
+        assert_eq!(corrections, [(21, 'q', '3')]);
+
+        // Case is preserved.
+        let (corrected, corrections) =
+            correct::<Bech32>("BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDX").unwrap();
+        assert_eq!(corrected, valid.to_uppercase());
+        assert_eq!(corrections, [(41, 'X', 'Q')]);
+    }
+
+    #[test]
+    fn correct_rejects_uncorrectable() {
+        let s = "bc1qar0srrr7xfkvy5l64qlydnw9re59gtzzwf5mdx";
+        assert_eq!(correct::<Bech32m>(s), Err(CorrectionError::TooManyErrors));
+
+        // An error in the HRP is located outside of the data part.
+        let s = "bd1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        assert!(matches!(correct::<Bech32>(s), Err(CorrectionError::OutOfRange(_))));
+
+        assert!(matches!(correct::<Bech32>("bc1qar0s"), Err(CorrectionError::Checksum(_))));
+        assert!(matches!(correct::<Bech32>("bc1qar0sb"), Err(CorrectionError::Parse(_))));
+    }
+
+    #[test]
+    fn correct_with_hrp_substitutes_expected() {
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+
+        // The expected HRP is kept.
+        let (corrected, edit, corrections) =
+            correct_with_hrp::<Bech32>(&s.replace("mdq", "mdx"), &[hrp::TB, hrp::BC]).unwrap();
+        assert_eq!(corrected, s);
+        assert_eq!(edit, None);
+        assert_eq!(corrections, [(41, 'x', 'q')]);
+
+        // A corrupted HRP is replaced, in the case of the data part.
+        let (corrected, edit, corrections) =
+            correct_with_hrp::<Bech32>(&s.replace("bc1", "bx1").to_uppercase(), &[hrp::BC])
+                .unwrap();
+        assert_eq!(corrected, s.to_uppercase());
+        let edit = edit.unwrap();
+        assert_eq!((edit.original.as_str(), edit.corrected, edit.distance), ("BX", hrp::BC, 1));
+        assert!(corrections.is_empty());
+
+        // A valid HRP is replaced if the checksum only matches another expected one.
+        let tb = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+        let (corrected, edit, _) =
+            correct_with_hrp::<Bech32>(&tb.replace("tb1", "bc1"), &[hrp::BC, hrp::TB]).unwrap();
+        assert_eq!(corrected, tb);
+        assert_eq!(edit.unwrap().distance, 2);
+
+        assert_eq!(
+            correct_with_hrp::<Bech32>(&tb.replace("tb1", "bc1"), &[hrp::BC]),
+            Err(CorrectionError::TooManyErrors)
+        );
+        assert_eq!(correct_with_hrp::<Bech32>(s, &[]), Err(CorrectionError::NoExpectedHrp));
+    }
+
+    #[test]
+    fn correct_with_erasures_doubles_capacity() {
+        let valid = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        assert_eq!(
+            correct_with_erasures::<Bech32>(valid, '?').unwrap(),
+            (valid.to_string(), vec![])
+        );
+
+        // Bech32 can correct a single unknown error, but three erasures.
+        let (corrected, corrections) =
+            correct_with_erasures::<Bech32>("bc1?ar0srrr7xfkvy5l643lydnw9re59gtzzw?5md?", '?')
+                .unwrap();
+        assert_eq!(corrected, valid);
+        assert_eq!(corrections, [(3, '?', 'q'), (37, '?', 'f'), (41, '?', 'q')]);
+
+        let s = "bc1?ar0srrr7xfkvy5l643lydnw9re59gtzzw?5m??";
+        assert_eq!(correct_with_erasures::<Bech32>(s, '?'), Err(CorrectionError::TooManyErrors));
+
+        // Too many errors for the checksum to correct must not panic.
+        assert!(correct_with_erasures::<Bech32>("bc1qar0s?", '?').is_err());
+
+        let s = "bc1qa?";
+        assert!(matches!(
+            correct_with_erasures::<Bech32>(s, '?'),
+            Err(CorrectionError::Checksum(ChecksumError::InvalidLength))
+        ));
+    }
+
     #[test]
     fn encoded_length_works() {
         let s = "test1lu08d6qejxtdg4y5r3zarvary0c5xw7kmz4lky";
@@ -0,0 +1,862 @@
+// SPDX-License-Identifier: MIT
+
+//! Lightning Invoice API - enables decoding and encoding [BOLT-11] payment requests.
+//!
+//! A BOLT-11 invoice is a bech32 string whose human-readable part carries the currency and an
+//! optional amount, and whose data part carries a timestamp, a sequence of tagged fields, and a
+//! signature. This module parses that structure, it does _not_ verify the signature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::lightning::{Amount, Invoice, Multiplier, TaggedField};
+//!
+//! let s = "lnbc2500u1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdq5xysxxatsyp3k7enxv4jsxqzpuaztrnwngzn3kdzw5hydlzf03qdgm2hdq27cqv3agm2awhz5se903vruatfhq77w3ls4evs3ch9zw97j25emudupq63nyw24cg27h2rspfj9srp";
+//! let invoice = s.parse::<Invoice>().expect("valid invoice");
+//!
+//! assert_eq!(invoice.currency(), "bc");
+//! assert_eq!(invoice.amount(), Some(Amount { value: 2500, multiplier: Some(Multiplier::Micro) }));
+//! assert_eq!(invoice.amount().unwrap().to_msat(), Some(250_000_000));
+//! assert_eq!(invoice.timestamp(), 1496314658);
+//! assert_eq!(invoice.description(), Some("1 cup coffee"));
+//! assert_eq!(invoice.expiry(), Some(60));
+//!
+//! // Invoices can be serialized back to a string.
+//! assert_eq!(invoice.to_string(), s);
+//! ```
+//!
+//! [BOLT-11]: <https://github.com/lightning/bolts/blob/master/11-payment-encoding.md>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::convert::TryInto;
+use core::fmt::{self, Write as _};
+
+use crate::error::write_err;
+use crate::primitives::correction::CorrectableError;
+use crate::primitives::decode::{
+    CheckedHrpstring, CheckedHrpstringError, CodeLengthError, InvalidResidueError,
+};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::{self, Hrp};
+use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::{Bech32, Checksum, Fe1024};
+
+/// The prefix of the human-readable part of every invoice.
+const PREFIX: &str = "ln";
+
+/// Number of field elements used to encode the timestamp.
+const TIMESTAMP_LENGTH: usize = 7;
+
+/// Number of field elements used to encode the signature (65 bytes).
+const SIGNATURE_LENGTH: usize = 104;
+
+/// Number of bytes used to encode a single routing hint hop.
+const ROUTE_HINT_HOP_LENGTH: usize = 51;
+
+/// The bech32 checksum algorithm with the longer code length allowed for BOLT-11 invoices.
+///
+/// Invoices use the [`Bech32`] checksum but are not limited to its 1023 characters, this allows
+/// up to 7089 characters, the most that fit into a QR code. Error correction is only meaningful
+/// for invoices of up to 1023 characters.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Bolt11 {}
+
+impl Checksum for Bolt11 {
+    type MidstateRepr = u32;
+
+    type CorrectionField = Fe1024;
+    const ROOT_GENERATOR: Self::CorrectionField = Bech32::ROOT_GENERATOR;
+    const ROOT_EXPONENTS: core::ops::RangeInclusive<usize> = Bech32::ROOT_EXPONENTS;
+
+    const CODE_LENGTH: usize = 7089;
+    const CHECKSUM_LENGTH: usize = Bech32::CHECKSUM_LENGTH;
+    const GENERATOR_SH: [u32; 5] = Bech32::GENERATOR_SH;
+    const TARGET_RESIDUE: u32 = Bech32::TARGET_RESIDUE;
+    const PAIR_TABLE: Option<&'static [u32; 1024]> = Bech32::PAIR_TABLE;
+}
+
+/// A decoded BOLT-11 invoice.
+///
+/// Fields are kept in the order in which they appear in the encoded invoice, unknown fields are
+/// retained so that an invoice can be encoded back to the same string.
+///
+/// An invoice can only be created by parsing a string or with [`Invoice::new`], both of which
+/// check that the invoice can be encoded, so displaying an invoice never fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invoice {
+    /// The currency prefix, lowercase ASCII letters.
+    currency: String,
+    /// The amount, if one is specified.
+    amount: Option<Amount>,
+    /// Seconds since the Unix epoch, fits into 35 bits.
+    timestamp: u64,
+    /// The tagged fields, each shorter than 1024 field elements.
+    tagged_fields: Vec<TaggedField>,
+    /// The signature, 64 bytes in compact format followed by the recovery ID.
+    signature: [u8; 65],
+}
+
+/// Parses a BOLT-11 invoice.
+///
+/// Validates the bech32 checksum and the structure of the invoice, does not verify the signature.
+impl core::str::FromStr for Invoice {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use DecodeError::*;
+
+        let checked = CheckedHrpstring::new::<Bolt11>(s)?;
+        let (currency, amount) = parse_hrp(&checked.hrp().to_lowercase())?;
+
+        let data = checked
+            .data_part_ascii_no_checksum()
+            .iter()
+            .map(|&b| Fe32::from_char_unchecked(b))
+            .collect::<Vec<Fe32>>();
+        if data.len() < TIMESTAMP_LENGTH + SIGNATURE_LENGTH {
+            return Err(TooShort(data.len()));
+        }
+        let (data, signature) = data.split_at(data.len() - SIGNATURE_LENGTH);
+        let (timestamp, mut data) = data.split_at(TIMESTAMP_LENGTH);
+        let timestamp = fes_to_int(timestamp).expect("35 bits fit into a u64");
+
+        let mut tagged_fields = Vec::new();
+        while !data.is_empty() {
+            if data.len() < 3 {
+                return Err(TruncatedField);
+            }
+            let len = usize::from(data[1].to_u8()) * 32 + usize::from(data[2].to_u8());
+            if data.len() < 3 + len {
+                return Err(TruncatedField);
+            }
+            tagged_fields.push(TaggedField::parse(data[0], &data[3..3 + len]));
+            data = &data[3 + len..];
+        }
+
+        let signature = signature
+            .iter()
+            .copied()
+            .fes_to_bytes()
+            .collect::<Vec<u8>>()
+            .try_into()
+            .expect("104 field elements are 65 bytes");
+
+        // The checks above and the two character field lengths give the same guarantees as
+        // `Invoice::new`, so the invoice can always be encoded again.
+        Ok(Invoice { currency, amount, timestamp, tagged_fields, signature })
+    }
+}
+
+/// Displays the invoice as a lowercase BOLT-11 string.
+impl fmt::Display for Invoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hrp = Hrp::parse(&self.hrp_string()).expect("checked when the invoice was created");
+        for c in self.data().into_iter().with_checksum::<Bolt11>(&hrp).chars() {
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+impl Invoice {
+    /// Creates a new invoice, checking that it can be encoded.
+    pub fn new(
+        currency: &str,
+        amount: Option<Amount>,
+        timestamp: u64,
+        tagged_fields: Vec<TaggedField>,
+        signature: [u8; 65],
+    ) -> Result<Self, EncodeError> {
+        use EncodeError::*;
+
+        if currency.is_empty() || !currency.bytes().all(|b| b.is_ascii_lowercase()) {
+            return Err(InvalidCurrency);
+        }
+        if let Some(amount) = amount {
+            // Readers reject amounts with leading zeros and pico amounts with sub-msat precision.
+            if amount.value == 0
+                || (amount.multiplier == Some(Multiplier::Pico) && amount.value % 10 != 0)
+            {
+                return Err(InvalidAmount(amount));
+            }
+        }
+        if timestamp >> (TIMESTAMP_LENGTH * 5) != 0 {
+            return Err(TimestampTooLarge(timestamp));
+        }
+        for field in &tagged_fields {
+            let len = field.encode().1.len();
+            if len >= 1024 {
+                return Err(FieldTooLong(len));
+            }
+        }
+
+        let invoice =
+            Invoice { currency: currency.into(), amount, timestamp, tagged_fields, signature };
+        let hrp = Hrp::parse(&invoice.hrp_string())?;
+        let encoded_length = hrp.len() + 1 + invoice.data().len() + Bolt11::CHECKSUM_LENGTH;
+        if encoded_length > Bolt11::CODE_LENGTH {
+            return Err(TooLong(CodeLengthError {
+                encoded_length,
+                code_length: Bolt11::CODE_LENGTH,
+            }));
+        }
+
+        Ok(invoice)
+    }
+
+    /// Returns the currency prefix e.g., `bc` for Bitcoin mainnet or `tb` for testnet.
+    pub fn currency(&self) -> &str { &self.currency }
+
+    /// Returns the amount, if one is specified.
+    pub fn amount(&self) -> Option<Amount> { self.amount }
+
+    /// Returns the timestamp, in seconds since the Unix epoch.
+    pub fn timestamp(&self) -> u64 { self.timestamp }
+
+    /// Returns the tagged fields, in the order in which they appear in the invoice.
+    pub fn tagged_fields(&self) -> &[TaggedField] { &self.tagged_fields }
+
+    /// Returns the signature, 64 bytes in compact format followed by the recovery ID.
+    pub fn signature(&self) -> &[u8; 65] { &self.signature }
+
+    /// Returns the human-readable part, which may not be valid if the invoice was not checked.
+    fn hrp_string(&self) -> String {
+        let mut hrp = String::from(PREFIX);
+        hrp.push_str(&self.currency);
+        if let Some(ref amount) = self.amount {
+            hrp.push_str(&amount.value.to_string());
+            if let Some(multiplier) = amount.multiplier {
+                hrp.push(multiplier.to_char());
+            }
+        }
+        hrp
+    }
+
+    /// Returns the data part without the checksum, assumes the field lengths have been checked.
+    fn data(&self) -> Vec<Fe32> {
+        let mut data = int_to_fes(self.timestamp);
+        while data.len() < TIMESTAMP_LENGTH {
+            data.insert(0, Fe32::Q);
+        }
+        for field in &self.tagged_fields {
+            let (tag, field_data) = field.encode();
+            let len = field_data.len();
+            data.push(tag);
+            data.push(Fe32((len / 32) as u8));
+            data.push(Fe32((len % 32) as u8));
+            data.extend(field_data);
+        }
+        data.extend(self.signature.iter().copied().bytes_to_fes());
+        data
+    }
+
+    /// Returns the payment hash, if the invoice has one.
+    pub fn payment_hash(&self) -> Option<&[u8; 32]> {
+        self.tagged_fields.iter().find_map(|field| match field {
+            TaggedField::PaymentHash(ref hash) => Some(hash),
+            _ => None,
+        })
+    }
+
+    /// Returns the description, if the invoice has one.
+    pub fn description(&self) -> Option<&str> {
+        self.tagged_fields.iter().find_map(|field| match field {
+            TaggedField::Description(ref description) => Some(description.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns the expiry time in seconds, if the invoice specifies one (defaults to 3600).
+    pub fn expiry(&self) -> Option<u64> {
+        self.tagged_fields.iter().find_map(|field| match field {
+            TaggedField::Expiry(expiry) => Some(*expiry),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over all the routing hints in the invoice.
+    pub fn routing_hints(&self) -> impl Iterator<Item = &[RouteHintHop]> {
+        self.tagged_fields.iter().filter_map(|field| match field {
+            TaggedField::RoutingHints(ref hops) => Some(hops.as_slice()),
+            _ => None,
+        })
+    }
+
+    /// Returns the feature bits, if the invoice has any.
+    pub fn features(&self) -> Option<&Features> {
+        self.tagged_fields.iter().find_map(|field| match field {
+            TaggedField::Features(ref features) => Some(features),
+            _ => None,
+        })
+    }
+}
+
+/// The amount encoded in the human-readable part of an invoice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Amount {
+    /// The amount, in units of the multiplier.
+    pub value: u64,
+    /// The multiplier, or `None` if the amount is in whole bitcoin.
+    pub multiplier: Option<Multiplier>,
+}
+
+impl Amount {
+    /// Returns the amount in millisatoshi, or `None` if it overflows a `u64`.
+    pub fn to_msat(&self) -> Option<u64> {
+        match self.multiplier {
+            None => self.value.checked_mul(100_000_000_000),
+            Some(Multiplier::Milli) => self.value.checked_mul(100_000_000),
+            Some(Multiplier::Micro) => self.value.checked_mul(100_000),
+            Some(Multiplier::Nano) => self.value.checked_mul(100),
+            Some(Multiplier::Pico) => Some(self.value / 10),
+        }
+    }
+}
+
+/// The multiplier applied to an invoice amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplier {
+    /// Multiply by 0.001 (`m`).
+    Milli,
+    /// Multiply by 0.000001 (`u`).
+    Micro,
+    /// Multiply by 0.000000001 (`n`).
+    Nano,
+    /// Multiply by 0.000000000001 (`p`).
+    Pico,
+}
+
+impl Multiplier {
+    /// Returns the multiplier for an HRP character.
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'm' => Some(Multiplier::Milli),
+            'u' => Some(Multiplier::Micro),
+            'n' => Some(Multiplier::Nano),
+            'p' => Some(Multiplier::Pico),
+            _ => None,
+        }
+    }
+
+    /// Returns the HRP character for this multiplier.
+    fn to_char(self) -> char {
+        match self {
+            Multiplier::Milli => 'm',
+            Multiplier::Micro => 'u',
+            Multiplier::Nano => 'n',
+            Multiplier::Pico => 'p',
+        }
+    }
+}
+
+/// A tagged field in the data part of an invoice.
+///
+/// Known fields which do not have the length required by BOLT-11 are returned as
+/// [`TaggedField::Unknown`], as the specification requires readers to skip them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TaggedField {
+    /// `p`: SHA-256 payment hash.
+    PaymentHash([u8; 32]),
+    /// `s`: Payment secret.
+    PaymentSecret([u8; 32]),
+    /// `d`: Short description of the purpose of the payment.
+    Description(String),
+    /// `m`: Additional metadata to attach to the payment.
+    Metadata(Vec<u8>),
+    /// `n`: 33-byte public key of the payee node.
+    PayeePubkey([u8; 33]),
+    /// `h`: SHA-256 hash of a description too long to fit into the invoice.
+    DescriptionHash([u8; 32]),
+    /// `x`: Expiry time in seconds.
+    Expiry(u64),
+    /// `c`: `min_final_cltv_expiry_delta` to use for the last HTLC in the route.
+    MinFinalCltvExpiryDelta(u64),
+    /// `f`: On-chain fallback address, a version followed by the program or hash.
+    Fallback {
+        /// Witness version, or 17 for P2PKH and 18 for P2SH.
+        version: Fe32,
+        /// The witness program, or the public key hash or script hash.
+        program: Vec<u8>,
+    },
+    /// `r`: One or more hops of a private route.
+    RoutingHints(Vec<RouteHintHop>),
+    /// `9`: Feature bits.
+    Features(Features),
+    /// A field with an unknown tag, or a known tag and unexpected data.
+    Unknown {
+        /// The tag.
+        tag: Fe32,
+        /// The field data.
+        data: Vec<Fe32>,
+    },
+}
+
+impl TaggedField {
+    /// Parses the data of a tagged field.
+    fn parse(tag: Fe32, data: &[Fe32]) -> Self {
+        use TaggedField::*;
+
+        let bytes = || data.iter().copied().fes_to_bytes().collect::<Vec<u8>>();
+        let parsed = match tag {
+            Fe32::P if data.len() == 52 => bytes().try_into().ok().map(PaymentHash),
+            Fe32::S if data.len() == 52 => bytes().try_into().ok().map(PaymentSecret),
+            Fe32::D => String::from_utf8(bytes()).ok().map(Description),
+            Fe32::M => Some(Metadata(bytes())),
+            Fe32::N if data.len() == 53 => bytes().try_into().ok().map(PayeePubkey),
+            Fe32::H if data.len() == 52 => bytes().try_into().ok().map(DescriptionHash),
+            Fe32::X => fes_to_int(data).map(Expiry),
+            Fe32::C => fes_to_int(data).map(MinFinalCltvExpiryDelta),
+            Fe32::F if !data.is_empty() => Some(Fallback {
+                version: data[0],
+                program: data[1..].iter().copied().fes_to_bytes().collect(),
+            }),
+            Fe32::R => {
+                let bytes = bytes();
+                if !bytes.is_empty() && bytes.len() % ROUTE_HINT_HOP_LENGTH == 0 {
+                    Some(RoutingHints(
+                        bytes.chunks(ROUTE_HINT_HOP_LENGTH).map(RouteHintHop::from_bytes).collect(),
+                    ))
+                } else {
+                    None
+                }
+            }
+            Fe32::_9 => Some(Features(self::Features(data.to_vec()))),
+            _ => None,
+        };
+        parsed.unwrap_or_else(|| Unknown { tag, data: data.to_vec() })
+    }
+
+    /// Encodes the field, returning the tag and the field data.
+    fn encode(&self) -> (Fe32, Vec<Fe32>) {
+        use TaggedField::*;
+
+        let fes = |bytes: &[u8]| bytes.iter().copied().bytes_to_fes().collect::<Vec<Fe32>>();
+        match *self {
+            PaymentHash(ref hash) => (Fe32::P, fes(hash)),
+            PaymentSecret(ref secret) => (Fe32::S, fes(secret)),
+            Description(ref description) => (Fe32::D, fes(description.as_bytes())),
+            Metadata(ref metadata) => (Fe32::M, fes(metadata)),
+            PayeePubkey(ref pubkey) => (Fe32::N, fes(pubkey)),
+            DescriptionHash(ref hash) => (Fe32::H, fes(hash)),
+            Expiry(expiry) => (Fe32::X, int_to_fes(expiry)),
+            MinFinalCltvExpiryDelta(delta) => (Fe32::C, int_to_fes(delta)),
+            Fallback { version, ref program } => {
+                let mut data = fes(program);
+                data.insert(0, version);
+                (Fe32::F, data)
+            }
+            RoutingHints(ref hops) => {
+                let bytes = hops.iter().flat_map(RouteHintHop::to_bytes).collect::<Vec<u8>>();
+                (Fe32::R, fes(&bytes))
+            }
+            Features(ref features) => (Fe32::_9, features.0.clone()),
+            Unknown { tag, ref data } => (tag, data.clone()),
+        }
+    }
+}
+
+/// A single hop of a private route, as carried in the `r` field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteHintHop {
+    /// Public key of the node at the start of the channel.
+    pub pubkey: [u8; 33],
+    /// The short channel ID of the channel.
+    pub short_channel_id: u64,
+    /// Base fee, in millisatoshi.
+    pub fee_base_msat: u32,
+    /// Proportional fee, in millionths.
+    pub fee_proportional_millionths: u32,
+    /// The CLTV expiry delta of the channel.
+    pub cltv_expiry_delta: u16,
+}
+
+impl RouteHintHop {
+    /// Parses a hop from exactly [`ROUTE_HINT_HOP_LENGTH`] bytes.
+    fn from_bytes(bytes: &[u8]) -> Self {
+        RouteHintHop {
+            pubkey: bytes[0..33].try_into().expect("33 bytes"),
+            short_channel_id: u64::from_be_bytes(bytes[33..41].try_into().expect("8 bytes")),
+            fee_base_msat: u32::from_be_bytes(bytes[41..45].try_into().expect("4 bytes")),
+            fee_proportional_millionths: u32::from_be_bytes(
+                bytes[45..49].try_into().expect("4 bytes"),
+            ),
+            cltv_expiry_delta: u16::from_be_bytes(bytes[49..51].try_into().expect("2 bytes")),
+        }
+    }
+
+    /// Encodes the hop as [`ROUTE_HINT_HOP_LENGTH`] bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(ROUTE_HINT_HOP_LENGTH);
+        bytes.extend_from_slice(&self.pubkey);
+        bytes.extend_from_slice(&self.short_channel_id.to_be_bytes());
+        bytes.extend_from_slice(&self.fee_base_msat.to_be_bytes());
+        bytes.extend_from_slice(&self.fee_proportional_millionths.to_be_bytes());
+        bytes.extend_from_slice(&self.cltv_expiry_delta.to_be_bytes());
+        bytes
+    }
+}
+
+/// The feature bits of an invoice, as carried in the `9` field.
+///
+/// Stored as field elements, most significant first, as they appear in the invoice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Features(pub Vec<Fe32>);
+
+impl Features {
+    /// Returns true if feature bit `bit` is set.
+    pub fn is_set(&self, bit: usize) -> bool {
+        let idx = bit / 5;
+        if idx >= self.0.len() {
+            return false;
+        }
+        self.0[self.0.len() - 1 - idx].to_u8() & (1 << (bit % 5)) != 0
+    }
+}
+
+/// Parses the currency and the amount out of a lowercase invoice HRP.
+fn parse_hrp(hrp: &str) -> Result<(String, Option<Amount>), DecodeError> {
+    use DecodeError::*;
+
+    let rest = hrp.strip_prefix(PREFIX).ok_or(MissingPrefix)?;
+    let amount_pos = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+    let (currency, amount) = rest.split_at(amount_pos);
+    if currency.is_empty() {
+        return Err(MissingCurrency);
+    }
+    if !currency.bytes().all(|b| b.is_ascii_lowercase()) {
+        return Err(InvalidCurrency);
+    }
+    if amount.is_empty() {
+        return Ok((currency.into(), None));
+    }
+
+    let (digits, multiplier) = match amount.chars().last().and_then(Multiplier::from_char) {
+        Some(multiplier) => (&amount[..amount.len() - 1], Some(multiplier)),
+        None => (amount, None),
+    };
+    if digits.starts_with('0') || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(InvalidAmount);
+    }
+    let value = digits.parse::<u64>().map_err(|_| InvalidAmount)?;
+    if multiplier == Some(Multiplier::Pico) && value % 10 != 0 {
+        return Err(InvalidAmount);
+    }
+
+    Ok((currency.into(), Some(Amount { value, multiplier })))
+}
+
+/// Converts big-endian field elements to an integer, returns `None` on overflow.
+fn fes_to_int(fes: &[Fe32]) -> Option<u64> {
+    fes.iter().try_fold(0u64, |acc, fe| acc.checked_mul(32).map(|acc| acc | u64::from(fe.to_u8())))
+}
+
+/// Converts an integer to the minimal number of big-endian field elements.
+fn int_to_fes(mut n: u64) -> Vec<Fe32> {
+    let mut fes = Vec::new();
+    while n > 0 {
+        fes.insert(0, Fe32((n % 32) as u8));
+        n /= 32;
+    }
+    fes
+}
+
+/// An error while decoding a BOLT-11 invoice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// Error while parsing the bech32 string.
+    Checked(CheckedHrpstringError),
+    /// The human-readable part does not start with `ln`.
+    MissingPrefix,
+    /// The human-readable part does not contain a currency.
+    MissingCurrency,
+    /// The currency contains characters other than ASCII letters.
+    InvalidCurrency,
+    /// The amount in the human-readable part is invalid.
+    InvalidAmount,
+    /// The data part is too short to contain a timestamp and signature.
+    TooShort(usize),
+    /// A tagged field extends past the end of the data part.
+    TruncatedField,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DecodeError::*;
+
+        match *self {
+            Checked(ref e) => write_err!(f, "invalid bech32 string"; e),
+            MissingPrefix => write!(f, "human-readable part does not start with ln"),
+            MissingCurrency => write!(f, "human-readable part does not contain a currency"),
+            InvalidCurrency => write!(f, "currency must be ASCII letters"),
+            InvalidAmount => write!(f, "invalid amount in human-readable part"),
+            TooShort(len) => write!(f, "data part too short: {} characters", len),
+            TruncatedField => write!(f, "tagged field extends past the end of the data part"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use DecodeError::*;
+
+        match *self {
+            Checked(ref e) => Some(e),
+            MissingPrefix | MissingCurrency | InvalidCurrency | InvalidAmount | TooShort(_)
+            | TruncatedField => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for DecodeError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Checked(e) }
+}
+
+impl CorrectableError for DecodeError {
+    fn residue_error(&self) -> Option<&InvalidResidueError> {
+        match self {
+            DecodeError::Checked(ref e) => e.residue_error(),
+            _ => None,
+        }
+    }
+}
+
+/// An error while creating a BOLT-11 invoice which could not be encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// The currency is empty or contains characters other than lowercase ASCII letters.
+    InvalidCurrency,
+    /// The amount is zero, or a pico amount which is not a whole number of millisatoshi.
+    InvalidAmount(Amount),
+    /// The resulting human-readable part is invalid.
+    InvalidHrp(hrp::Error),
+    /// The timestamp does not fit into 35 bits.
+    TimestampTooLarge(u64),
+    /// A tagged field is too long to encode its length in two characters.
+    FieldTooLong(usize),
+    /// The encoded invoice exceeds the [`Bolt11`] code length.
+    TooLong(CodeLengthError),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use EncodeError::*;
+
+        match *self {
+            InvalidCurrency => write!(f, "currency must be lowercase ASCII letters"),
+            InvalidAmount(amount) => write!(f, "invalid amount: {:?}", amount),
+            InvalidHrp(ref e) => write_err!(f, "invalid human-readable part"; e),
+            TimestampTooLarge(t) => write!(f, "timestamp {} does not fit into 35 bits", t),
+            FieldTooLong(len) => write!(f, "tagged field too long: {} characters", len),
+            TooLong(ref e) => write_err!(f, "encoded invoice too long"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use EncodeError::*;
+
+        match *self {
+            InvalidHrp(ref e) => Some(e),
+            TooLong(ref e) => Some(e),
+            InvalidCurrency | InvalidAmount(_) | TimestampTooLarge(_) | FieldTooLong(_) => None,
+        }
+    }
+}
+
+impl From<hrp::Error> for EncodeError {
+    #[inline]
+    fn from(e: hrp::Error) -> Self { Self::InvalidHrp(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    // Test vectors from BOLT-11.
+    const DONATION: &str = "lnbc1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twvus8g6rfwvs8qun0dfjkxaq8rkx3yf5tcsyz3d73gafnh3cax9rn449d9p5uxz9ezhhypd0elx87sjle52x86fux2ypatgddc6k63n7erqz25le42c4u4ecky03ylcqca784w";
+    const COFFEE: &str = "lnbc2500u1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdq5xysxxatsyp3k7enxv4jsxqzpuaztrnwngzn3kdzw5hydlzf03qdgm2hdq27cqv3agm2awhz5se903vruatfhq77w3ls4evs3ch9zw97j25emudupq63nyw24cg27h2rspfj9srp";
+
+    const PAYMENT_HASH: [u8; 32] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x00, 0x01, 0x02, 0x03, 0x04,
+        0x05, 0x06, 0x07, 0x08, 0x09, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+        0x01, 0x02,
+    ];
+
+    #[test]
+    fn decode_donation() {
+        let invoice = Invoice::from_str(DONATION).expect("valid invoice");
+        assert_eq!(invoice.currency, "bc");
+        assert_eq!(invoice.amount, None);
+        assert_eq!(invoice.timestamp, 1496314658);
+        assert_eq!(invoice.payment_hash(), Some(&PAYMENT_HASH));
+        assert_eq!(invoice.description(), Some("Please consider supporting this project"));
+        assert_eq!(invoice.expiry(), None);
+        assert_eq!(&invoice.signature[..4], &[0x38, 0xec, 0x68, 0x91]);
+        assert_eq!(invoice.signature[64], 0);
+    }
+
+    #[test]
+    fn decode_uppercase() {
+        let upper = COFFEE.to_uppercase();
+        let invoice = Invoice::from_str(&upper).expect("valid invoice");
+        assert_eq!(invoice.to_string(), COFFEE);
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in [DONATION, COFFEE].iter() {
+            let invoice = Invoice::from_str(s).unwrap();
+            assert_eq!(invoice.to_string(), *s);
+        }
+    }
+
+    #[test]
+    fn roundtrip_all_fields() {
+        let hop = RouteHintHop {
+            pubkey: [0x02; 33],
+            short_channel_id: 0x0102_0304_0506_0708,
+            fee_base_msat: 1,
+            fee_proportional_millionths: 20,
+            cltv_expiry_delta: 3,
+        };
+        let invoice = Invoice::new(
+            "tb",
+            Some(Amount { value: 20, multiplier: Some(Multiplier::Milli) }),
+            1496314658,
+            vec![
+                TaggedField::PaymentHash(PAYMENT_HASH),
+                TaggedField::PaymentSecret([0x11; 32]),
+                TaggedField::DescriptionHash([0x22; 32]),
+                TaggedField::PayeePubkey([0x03; 33]),
+                TaggedField::Metadata(vec![0x01, 0xfa, 0xfa]),
+                TaggedField::Expiry(3600),
+                TaggedField::MinFinalCltvExpiryDelta(9),
+                TaggedField::Fallback { version: Fe32::Q, program: vec![0xab; 20] },
+                TaggedField::RoutingHints(vec![hop.clone(), hop]),
+                TaggedField::Features(Features(vec![Fe32::_2, Fe32::Q])),
+                TaggedField::Unknown { tag: Fe32::L, data: vec![Fe32::A, Fe32::C] },
+            ],
+            [0x42; 65],
+        )
+        .unwrap();
+
+        let s = invoice.to_string();
+        assert!(s.starts_with("lntb20m1"));
+        let decoded = Invoice::from_str(&s).unwrap();
+        assert_eq!(decoded, invoice);
+        assert_eq!(decoded.routing_hints().count(), 1);
+        assert_eq!(decoded.amount().unwrap().to_msat(), Some(2_000_000_000));
+
+        let features = decoded.features().unwrap();
+        assert!(features.is_set(6) && features.is_set(8));
+        assert!(!features.is_set(5) && !features.is_set(100));
+    }
+
+    #[test]
+    fn bolt11_sanity() { Bolt11::sanity_check(); }
+
+    #[test]
+    fn roundtrip_long() {
+        let fields =
+            vec![TaggedField::Metadata(vec![0xab; 600]), TaggedField::Description("x".repeat(300))];
+        let invoice = Invoice::new("bc", None, 1496314658, fields, [0x42; 65]).unwrap();
+
+        let s = invoice.to_string();
+        assert!(s.len() > Bech32::CODE_LENGTH);
+        assert_eq!(Invoice::from_str(&s), Ok(invoice));
+        assert_eq!(Invoice::from_str(&s.to_uppercase()).unwrap().to_string(), s);
+    }
+
+    #[test]
+    fn encode_errors() {
+        let invoice = Invoice::from_str(COFFEE).unwrap();
+        let new = |currency: &str, timestamp: u64, tagged_fields: Vec<TaggedField>| {
+            Invoice::new(currency, invoice.amount(), timestamp, tagged_fields, *invoice.signature())
+        };
+        let fields = invoice.tagged_fields().to_vec();
+
+        assert_eq!(new("bc", invoice.timestamp(), fields.clone()), Ok(invoice.clone()));
+        assert_eq!(
+            new("bc", 1 << 35, fields.clone()),
+            Err(EncodeError::TimestampTooLarge(1 << 35))
+        );
+        assert_eq!(new("BC", 0, fields.clone()), Err(EncodeError::InvalidCurrency));
+        assert_eq!(new("", 0, fields.clone()), Err(EncodeError::InvalidCurrency));
+
+        for amount in [
+            Amount { value: 0, multiplier: None },
+            Amount { value: 0, multiplier: Some(Multiplier::Milli) },
+            Amount { value: 11, multiplier: Some(Multiplier::Pico) },
+        ]
+        .iter()
+        {
+            let res = Invoice::new("bc", Some(*amount), 0, fields.clone(), [0; 65]);
+            assert_eq!(res, Err(EncodeError::InvalidAmount(*amount)));
+        }
+        let amount = Amount { value: 10, multiplier: Some(Multiplier::Pico) };
+        assert!(Invoice::new("bc", Some(amount), 0, fields.clone(), [0; 65]).is_ok());
+
+        let long = TaggedField::Metadata(vec![0; 640]);
+        assert_eq!(new("bc", 0, vec![long]), Err(EncodeError::FieldTooLong(1024)));
+        let fields = vec![TaggedField::Metadata(vec![0; 600]); 12];
+        assert!(matches!(new("bc", 0, fields), Err(EncodeError::TooLong(_))));
+    }
+
+    #[test]
+    fn wrong_length_field_is_unknown() {
+        // A payment hash of 51 field elements must be skipped by readers.
+        let field = TaggedField::parse(Fe32::P, &[Fe32::Q; 51]);
+        assert_eq!(field, TaggedField::Unknown { tag: Fe32::P, data: vec![Fe32::Q; 51] });
+    }
+
+    #[test]
+    fn parse_amounts() {
+        use Multiplier::*;
+
+        let amount = |hrp: &str| parse_hrp(hrp).map(|(_, amount)| amount);
+        assert_eq!(amount("lnbc"), Ok(None));
+        assert_eq!(amount("lnbc1"), Ok(Some(Amount { value: 1, multiplier: None })));
+        assert_eq!(amount("lnbc2500u"), Ok(Some(Amount { value: 2500, multiplier: Some(Micro) })));
+        assert_eq!(amount("lnbcrt10n"), Ok(Some(Amount { value: 10, multiplier: Some(Nano) })));
+        assert_eq!(amount("lnbc10p"), Ok(Some(Amount { value: 10, multiplier: Some(Pico) })));
+        assert_eq!(parse_hrp("lnbcrt10n").unwrap().0, "bcrt");
+
+        assert_eq!(amount("lnbc11p"), Err(DecodeError::InvalidAmount));
+        assert_eq!(amount("lnbc01m"), Err(DecodeError::InvalidAmount));
+        assert_eq!(amount("lnbc1x"), Err(DecodeError::InvalidAmount));
+        assert_eq!(amount("lnbc99999999999999999999"), Err(DecodeError::InvalidAmount));
+        assert_eq!(amount("ln1m"), Err(DecodeError::MissingCurrency));
+        assert_eq!(amount("lnb-c1m"), Err(DecodeError::InvalidCurrency));
+        assert_eq!(amount("bc1m"), Err(DecodeError::MissingPrefix));
+    }
+
+    #[test]
+    fn decode_errors() {
+        let s = crate::encode::<Bech32>(Hrp::parse("lnbc").unwrap(), &[0; 10]).unwrap();
+        assert!(matches!(Invoice::from_str(&s), Err(DecodeError::TooShort(_))));
+
+        let mut donation = DONATION.to_owned();
+        donation.pop();
+        donation.push('q');
+        let err = Invoice::from_str(&donation).unwrap_err();
+        assert!(matches!(err, DecodeError::Checked(_)));
+
+        let ctx = err.correction_context::<Bolt11>().expect("checksum error");
+        let mut iter = ctx.bch_errors().expect("correctable error");
+        assert_eq!(iter.next(), Some((0, Fe32::W)));
+        assert_eq!(iter.next(), None);
+    }
+}
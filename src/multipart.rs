@@ -0,0 +1,635 @@
+// SPDX-License-Identifier: MIT
+
+//! Multi-part API - enables splitting payloads that are too long for a single bech32 string.
+//!
+//! Large payloads such as PSBTs or descriptor backups do not fit in one string within the
+//! checksum code length. This module splits a byte payload into numbered parts, each of them a
+//! valid bech32m string, and reassembles the parts in any order.
+//!
+//! The data part of every string starts with an 8 character header:
+//!
+//! - 4 characters: a 20-bit payload identifier shared by all parts of a payload.
+//! - 2 characters: the index of this part, starting at 0.
+//! - 2 characters: the total number of parts minus one.
+//!
+//! Before splitting, a 15 character [`Codex32Long`] checksum over the HRP and the whole payload is
+//! appended to the payload. This integrity checksum is verified after reassembly, catching parts
+//! from different payloads that happen to share an identifier. The identifier is taken from the
+//! first 4 characters of the integrity checksum, so splitting the same payload twice gives the
+//! same identifier.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::multipart::{self, Reassembler};
+//! use bech32::Hrp;
+//!
+//! let hrp = Hrp::parse("psbt").expect("valid hrp");
+//! let payload = [0xab; 200];
+//!
+//! // Split into strings of at most 90 characters.
+//! let parts = multipart::split(hrp, &payload, 90).expect("valid part length");
+//! assert_eq!(parts.len(), 5);
+//! assert!(parts.iter().all(|s| s.len() <= 90));
+//!
+//! // Parts can be added in any order, e.g. as QR codes are scanned.
+//! let mut reassembler = Reassembler::new();
+//! for s in parts.iter().rev() {
+//!     reassembler.add(s).expect("valid part");
+//! }
+//! assert!(reassembler.is_complete());
+//!
+//! let (got_hrp, got) = reassembler.finish().expect("complete and intact");
+//! assert_eq!(got_hrp, hrp);
+//! assert_eq!(got, payload);
+//! ```
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::error::write_err;
+use crate::primitives::checksum::{Engine, PackedFe32};
+use crate::primitives::correction::CorrectableError;
+use crate::primitives::decode::{CheckedHrpstring, CheckedHrpstringError, InvalidResidueError};
+use crate::primitives::hrp::Hrp;
+use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::{Bech32m, Checksum, Codex32Long, Fe32};
+
+/// The maximum number of parts a payload can be split into.
+pub const MAX_PARTS: usize = 1024;
+
+/// The number of characters at the start of each data part used for the part header.
+pub const HEADER_LENGTH: usize = 8;
+
+/// The number of characters of the integrity checksum appended to the payload.
+const INTEGRITY_LENGTH: usize = Codex32Long::CHECKSUM_LENGTH;
+
+/// Splits `payload` into bech32m strings of at most `max_part_length` characters.
+///
+/// The payload is spread as evenly as possible across the fewest parts that fit, so all parts
+/// have about the same length.
+///
+/// # Errors
+///
+/// Errors if `max_part_length` leaves no room for data after the HRP, header and checksum, if it
+/// exceeds the bech32m code length, or if more than [`MAX_PARTS`] parts would be needed.
+pub fn split(hrp: Hrp, payload: &[u8], max_part_length: usize) -> Result<Vec<String>, SplitError> {
+    if max_part_length > Bech32m::CODE_LENGTH {
+        return Err(SplitError::PartTooLong(max_part_length));
+    }
+    let overhead = hrp.len() + 1 + HEADER_LENGTH + Bech32m::CHECKSUM_LENGTH;
+    let capacity = match max_part_length.checked_sub(overhead) {
+        Some(capacity) if capacity > 0 => capacity,
+        _ => return Err(SplitError::PartTooShort(max_part_length)),
+    };
+
+    let mut stream = payload.iter().copied().bytes_to_fes().collect::<Vec<Fe32>>();
+    let integrity = integrity_checksum(hrp, &stream);
+    stream.extend_from_slice(&integrity);
+
+    let parts = (stream.len() + capacity - 1) / capacity;
+    if parts > MAX_PARTS {
+        return Err(SplitError::TooManyParts(parts));
+    }
+    let payload_id = payload_id(&integrity);
+
+    // The first `stream.len() % parts` parts carry one extra field element.
+    let (base, extra) = (stream.len() / parts, stream.len() % parts);
+    let mut rest = &stream[..];
+    let strings = (0..parts)
+        .map(|index| {
+            let (chunk, tail) = rest.split_at(base + usize::from(index < extra));
+            rest = tail;
+            header(payload_id, index, parts)
+                .iter()
+                .copied()
+                .chain(chunk.iter().copied())
+                .with_checksum::<Bech32m>(&hrp)
+                .chars()
+                .collect()
+        })
+        .collect();
+    Ok(strings)
+}
+
+/// Reassembles a payload from all of its parts, given in any order.
+///
+/// # Errors
+///
+/// Errors if any part is invalid or does not belong with the others, if a part is duplicated or
+/// missing, or if the reassembled payload fails its integrity check.
+pub fn join<'s, I: IntoIterator<Item = &'s str>>(parts: I) -> Result<(Hrp, Vec<u8>), JoinError> {
+    let mut reassembler = Reassembler::new();
+    for s in parts {
+        reassembler.add(s)?;
+    }
+    reassembler.finish()
+}
+
+/// Returns the header field elements for part `index` of `total`.
+fn header(payload_id: u32, index: usize, total: usize) -> [Fe32; HEADER_LENGTH] {
+    let fields = [(payload_id as usize, 4), (index, 2), (total - 1, 2)];
+    let mut header = [Fe32::Q; HEADER_LENGTH];
+    let mut pos = 0;
+    for &(value, len) in &fields {
+        for i in (0..len).rev() {
+            header[pos] = Fe32(((value >> (5 * i)) & 0x1f) as u8);
+            pos += 1;
+        }
+    }
+    header
+}
+
+/// Returns the payload identifier for the integrity checksum `integrity`.
+fn payload_id(integrity: &[Fe32]) -> u32 {
+    integrity[..4].iter().fold(0, |id, fe| (id << 5) | u32::from(fe.to_u8()))
+}
+
+/// Computes the integrity checksum of the payload field elements `fes`.
+fn integrity_checksum(hrp: Hrp, fes: &[Fe32]) -> [Fe32; INTEGRITY_LENGTH] {
+    let mut engine = integrity_engine(hrp, fes);
+    engine.input_target_residue();
+    let mut checksum = [Fe32::Q; INTEGRITY_LENGTH];
+    for (i, fe) in checksum.iter_mut().enumerate() {
+        *fe = Fe32(engine.residue().unpack(INTEGRITY_LENGTH - 1 - i));
+    }
+    checksum
+}
+
+/// Returns the integrity checksum engine after inputting `hrp` and `fes`.
+fn integrity_engine(hrp: Hrp, fes: &[Fe32]) -> Engine<Codex32Long> {
+    let mut engine = Engine::new();
+    engine.input_hrp(hrp);
+    engine.input_fes(fes.iter().copied());
+    engine
+}
+
+/// A single part of a multi-part payload, with a valid checksum and header.
+#[derive(Debug)]
+pub struct Part<'s> {
+    inner: CheckedHrpstring<'s>,
+    payload_id: u32,
+    index: usize,
+    total: usize,
+}
+
+impl<'s> Part<'s> {
+    /// Parses and validates a single part.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `s` is not a valid bech32m string, or if its header is missing or invalid.
+    #[inline]
+    pub fn parse(s: &'s str) -> Result<Self, PartError> {
+        let inner = CheckedHrpstring::new::<Bech32m>(s)?;
+        if inner.data_part_ascii_no_checksum().len() <= HEADER_LENGTH {
+            return Err(PartError::MissingHeader);
+        }
+
+        let mut fes = inner.fe32_iter(0);
+        let mut read = |len: usize| {
+            fes.by_ref().take(len).fold(0, |acc, fe| (acc << 5) | usize::from(fe.to_u8()))
+        };
+        let payload_id = read(4) as u32;
+        let index = read(2);
+        let total = read(2) + 1;
+
+        if index >= total {
+            return Err(PartError::InvalidIndex { index, total });
+        }
+        Ok(Part { inner, payload_id, index, total })
+    }
+
+    /// Returns the human-readable part.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.inner.hrp() }
+
+    /// Returns the identifier shared by all parts of the payload.
+    #[inline]
+    pub fn payload_id(&self) -> u32 { self.payload_id }
+
+    /// Returns the index of this part, starting at 0.
+    #[inline]
+    pub fn index(&self) -> usize { self.index }
+
+    /// Returns the total number of parts of the payload.
+    #[inline]
+    pub fn total(&self) -> usize { self.total }
+
+    /// Returns an iterator over the field elements this part carries, after the header.
+    fn fe32_iter(&self) -> impl Iterator<Item = Fe32> + '_ {
+        self.inner.fe32_iter(0).skip(HEADER_LENGTH)
+    }
+}
+
+/// Collects the parts of a multi-part payload as they arrive.
+///
+/// The first part added fixes the HRP, payload identifier and number of parts, later parts must
+/// match them.
+#[derive(Debug, Clone, Default)]
+pub struct Reassembler {
+    /// The HRP and payload identifier, set by the first part.
+    header: Option<(Hrp, u32)>,
+    /// The field elements of each part, indexed by part index.
+    parts: Vec<Option<Vec<Fe32>>>,
+    /// The number of parts received so far.
+    received: usize,
+}
+
+impl Reassembler {
+    /// Constructs a new, empty reassembler.
+    #[inline]
+    pub fn new() -> Self { Self::default() }
+
+    /// Parses and adds a part, returning its index.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the part is invalid, does not belong with the parts added so far, or has
+    /// already been added. The reassembler is unchanged on error.
+    #[inline]
+    pub fn add(&mut self, s: &str) -> Result<usize, PartError> {
+        let part = Part::parse(s)?;
+        self.add_part(&part)
+    }
+
+    /// Adds an already parsed part, returning its index.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the part does not belong with the parts added so far, or has already been
+    /// added. The reassembler is unchanged on error.
+    pub fn add_part(&mut self, part: &Part) -> Result<usize, PartError> {
+        match self.header {
+            None => {
+                self.header = Some((part.hrp(), part.payload_id()));
+                self.parts.resize(part.total(), None);
+            }
+            Some((hrp, payload_id)) => {
+                if part.hrp() != hrp {
+                    return Err(PartError::MismatchedHrp);
+                }
+                if part.payload_id() != payload_id {
+                    return Err(PartError::MismatchedPayloadId);
+                }
+                if part.total() != self.parts.len() {
+                    return Err(PartError::MismatchedTotal);
+                }
+            }
+        }
+
+        let slot = &mut self.parts[part.index()];
+        if slot.is_some() {
+            return Err(PartError::DuplicateIndex(part.index()));
+        }
+        *slot = Some(part.fe32_iter().collect());
+        self.received += 1;
+        Ok(part.index())
+    }
+
+    /// Returns the total number of parts, if any part has been added yet.
+    #[inline]
+    pub fn total(&self) -> Option<usize> { self.header.map(|_| self.parts.len()) }
+
+    /// Returns the number of parts added so far.
+    #[inline]
+    pub fn received(&self) -> usize { self.received }
+
+    /// Returns true if at least one part has been added and no parts are missing.
+    #[inline]
+    pub fn is_complete(&self) -> bool { self.header.is_some() && self.received == self.parts.len() }
+
+    /// Returns an iterator over the indices of the parts not added yet.
+    ///
+    /// Yields nothing before the first part is added, since the total is not yet known.
+    #[inline]
+    pub fn missing(&self) -> impl Iterator<Item = usize> + '_ {
+        self.parts.iter().enumerate().filter(|(_, part)| part.is_none()).map(|(i, _)| i)
+    }
+
+    /// Reassembles the payload and verifies its integrity checksum.
+    ///
+    /// # Errors
+    ///
+    /// Errors if no parts were added, if any part is missing, or if the reassembled payload fails
+    /// its integrity check.
+    pub fn finish(self) -> Result<(Hrp, Vec<u8>), JoinError> {
+        let (hrp, payload_id) = self.header.ok_or(JoinError::NoParts)?;
+        let total = self.parts.len();
+        if self.received != total {
+            return Err(JoinError::MissingParts { missing: total - self.received, total });
+        }
+
+        let stream = self.parts.into_iter().flatten().flatten().collect::<Vec<Fe32>>();
+        let data_len = match stream.len().checked_sub(INTEGRITY_LENGTH) {
+            Some(len) => len,
+            None => return Err(JoinError::Integrity),
+        };
+        let (data, integrity) = stream.split_at(data_len);
+
+        let residue = *integrity_engine(hrp, &stream).residue();
+        if residue != Codex32Long::TARGET_RESIDUE || self::payload_id(integrity) != payload_id {
+            return Err(JoinError::Integrity);
+        }
+
+        let bytes = data.iter().copied().fes_to_bytes().collect::<Vec<u8>>();
+        if !bytes.iter().copied().bytes_to_fes().eq(data.iter().copied()) {
+            return Err(JoinError::Padding);
+        }
+        Ok((hrp, bytes))
+    }
+}
+
+/// An error while splitting a payload into parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SplitError {
+    /// The maximum part length leaves no room for data after the HRP, header and checksum.
+    PartTooShort(usize),
+    /// The maximum part length exceeds the bech32m code length.
+    PartTooLong(usize),
+    /// The payload needs more than [`MAX_PARTS`] parts.
+    TooManyParts(usize),
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SplitError::*;
+
+        match *self {
+            PartTooShort(len) => write!(f, "part length {} leaves no room for data", len),
+            PartTooLong(len) =>
+                write!(f, "part length {} exceeds the code length {}", len, Bech32m::CODE_LENGTH),
+            TooManyParts(n) =>
+                write!(f, "payload needs {} parts, at most {} are supported", n, MAX_PARTS),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SplitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use SplitError::*;
+
+        match *self {
+            PartTooShort(_) | PartTooLong(_) | TooManyParts(_) => None,
+        }
+    }
+}
+
+/// An error while parsing a part or adding it to a [`Reassembler`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PartError {
+    /// Error while parsing the bech32m string.
+    Checked(CheckedHrpstringError),
+    /// The data part is too short to hold a header and any data.
+    MissingHeader,
+    /// The part index is not less than the total number of parts.
+    InvalidIndex {
+        /// The index of the part.
+        index: usize,
+        /// The total number of parts.
+        total: usize,
+    },
+    /// The part has a different HRP than the parts added before it.
+    MismatchedHrp,
+    /// The part has a different payload identifier than the parts added before it.
+    MismatchedPayloadId,
+    /// The part has a different total number of parts than the parts added before it.
+    MismatchedTotal,
+    /// A part with this index has already been added.
+    DuplicateIndex(usize),
+}
+
+impl fmt::Display for PartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use PartError::*;
+
+        match *self {
+            Checked(ref e) => write_err!(f, "invalid bech32m string"; e),
+            MissingHeader => write!(f, "data part too short for the part header"),
+            InvalidIndex { index, total } =>
+                write!(f, "part index {} out of range for {} parts", index, total),
+            MismatchedHrp => write!(f, "part has a different human-readable part"),
+            MismatchedPayloadId => write!(f, "part has a different payload identifier"),
+            MismatchedTotal => write!(f, "part has a different total number of parts"),
+            DuplicateIndex(index) => write!(f, "duplicate part index {}", index),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PartError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use PartError::*;
+
+        match *self {
+            Checked(ref e) => Some(e),
+            MissingHeader
+            | InvalidIndex { .. }
+            | MismatchedHrp
+            | MismatchedPayloadId
+            | MismatchedTotal
+            | DuplicateIndex(_) => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for PartError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Checked(e) }
+}
+
+impl CorrectableError for PartError {
+    fn residue_error(&self) -> Option<&InvalidResidueError> {
+        match self {
+            PartError::Checked(ref e) => e.residue_error(),
+            _ => None,
+        }
+    }
+}
+
+/// An error while reassembling a payload from its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JoinError {
+    /// A part is invalid or does not belong with the others.
+    Part(PartError),
+    /// No parts were provided.
+    NoParts,
+    /// Some parts are missing.
+    MissingParts {
+        /// The number of missing parts.
+        missing: usize,
+        /// The total number of parts.
+        total: usize,
+    },
+    /// The reassembled payload does not match its integrity checksum.
+    Integrity,
+    /// The reassembled payload has invalid padding.
+    Padding,
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use JoinError::*;
+
+        match *self {
+            Part(ref e) => write_err!(f, "invalid part"; e),
+            NoParts => write!(f, "no parts provided"),
+            MissingParts { missing, total } =>
+                write!(f, "{} of {} parts are missing", missing, total),
+            Integrity => write!(f, "reassembled payload fails its integrity check"),
+            Padding => write!(f, "reassembled payload has invalid padding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for JoinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use JoinError::*;
+
+        match *self {
+            Part(ref e) => Some(e),
+            NoParts | MissingParts { .. } | Integrity | Padding => None,
+        }
+    }
+}
+
+impl From<PartError> for JoinError {
+    #[inline]
+    fn from(e: PartError) -> Self { Self::Part(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hrp() -> Hrp { Hrp::parse("psbt").expect("valid hrp") }
+
+    #[test]
+    fn roundtrip() {
+        let payload = (0..600_u16).map(|i| (i * 7) as u8).collect::<Vec<u8>>();
+        for len in [0, 1, 2, 3, 4, 5, 31, 32, 33, 100, 600] {
+            for &max in &[30, 90, 200, 1023] {
+                let parts = split(hrp(), &payload[..len], max).expect("valid part length");
+                assert!(parts.iter().all(|s| s.len() <= max));
+
+                // Parts are about the same length.
+                let lens = parts.iter().map(String::len);
+                assert!(lens.clone().max().unwrap() - lens.min().unwrap() <= 1);
+
+                let (got_hrp, got) = join(parts.iter().rev().map(String::as_str)).expect("valid");
+                assert_eq!(got_hrp, hrp());
+                assert_eq!(got, &payload[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn part_header() {
+        let parts = split(hrp(), &[0xab; 200], 90).unwrap();
+        let first = Part::parse(&parts[0]).expect("valid part");
+        for (i, s) in parts.iter().enumerate() {
+            let part = Part::parse(s).expect("valid part");
+            assert_eq!(part.hrp(), hrp());
+            assert_eq!(part.payload_id(), first.payload_id());
+            assert_eq!(part.index(), i);
+            assert_eq!(part.total(), parts.len());
+        }
+
+        // Same payload, same identifier.
+        let again = split(hrp(), &[0xab; 200], 60).unwrap();
+        assert_eq!(Part::parse(&again[0]).unwrap().payload_id(), first.payload_id());
+        let other = split(hrp(), &[0xac; 200], 90).unwrap();
+        assert_ne!(Part::parse(&other[0]).unwrap().payload_id(), first.payload_id());
+    }
+
+    #[test]
+    fn missing_and_duplicate_parts() {
+        let parts = split(hrp(), &[0xab; 200], 90).unwrap();
+        assert_eq!(parts.len(), 5);
+
+        let mut reassembler = Reassembler::new();
+        assert_eq!(reassembler.total(), None);
+        assert_eq!(reassembler.add(&parts[3]), Ok(3));
+        assert_eq!(reassembler.add(&parts[1]), Ok(1));
+        assert_eq!(reassembler.add(&parts[3]), Err(PartError::DuplicateIndex(3)));
+        assert_eq!(reassembler.total(), Some(5));
+        assert_eq!(reassembler.received(), 2);
+        assert!(!reassembler.is_complete());
+        assert_eq!(reassembler.missing().collect::<Vec<_>>(), [0, 2, 4]);
+        assert_eq!(reassembler.finish(), Err(JoinError::MissingParts { missing: 3, total: 5 }));
+
+        let err = join(parts.iter().chain(&parts[..1]).map(String::as_str)).unwrap_err();
+        assert_eq!(err, JoinError::Part(PartError::DuplicateIndex(0)));
+        assert_eq!(join(core::iter::empty()), Err(JoinError::NoParts));
+    }
+
+    #[test]
+    fn mismatched_parts() {
+        let parts = split(hrp(), &[0xab; 200], 90).unwrap();
+        let other = split(hrp(), &[0xac; 200], 90).unwrap();
+        let other_hrp = split(Hrp::parse("abcd").unwrap(), &[0xab; 200], 90).unwrap();
+        let other_total = split(hrp(), &[0xab; 200], 60).unwrap();
+
+        let mut reassembler = Reassembler::new();
+        reassembler.add(&parts[0]).unwrap();
+        assert_eq!(reassembler.add(&other[1]), Err(PartError::MismatchedPayloadId));
+        assert_eq!(reassembler.add(&other_hrp[1]), Err(PartError::MismatchedHrp));
+        assert_eq!(reassembler.add(&other_total[1]), Err(PartError::MismatchedTotal));
+        assert_eq!(reassembler.received(), 1);
+    }
+
+    #[test]
+    fn integrity_check() {
+        // Two payloads with the same identifier, built by hand with a forged header.
+        let parts = split(hrp(), &[0xab; 200], 90).unwrap();
+        let other = split(hrp(), &[0xac; 200], 90).unwrap();
+        let id = Part::parse(&parts[0]).unwrap().payload_id();
+
+        let forged = {
+            let part = Part::parse(&other[1]).unwrap();
+            header(id, 1, parts.len())
+                .iter()
+                .copied()
+                .chain(part.fe32_iter())
+                .with_checksum::<Bech32m>(&hrp())
+                .chars()
+                .collect::<String>()
+        };
+        let mut mixed = parts.clone();
+        mixed[1] = forged;
+        assert_eq!(join(mixed.iter().map(String::as_str)), Err(JoinError::Integrity));
+    }
+
+    #[test]
+    fn invalid_parts() {
+        assert!(matches!(Part::parse("psbt1qqqq"), Err(PartError::Checked(_))));
+
+        let hrp = hrp();
+        let short = [Fe32::Q; 8].iter().copied().with_checksum::<Bech32m>(&hrp);
+        let short = short.chars().collect::<String>();
+        assert_eq!(Part::parse(&short).unwrap_err(), PartError::MissingHeader);
+
+        let header = header(0, 2, 2);
+        let bad_index = header.iter().copied().chain(Some(Fe32::Q)).with_checksum::<Bech32m>(&hrp);
+        let bad_index = bad_index.chars().collect::<String>();
+        assert_eq!(
+            Part::parse(&bad_index).unwrap_err(),
+            PartError::InvalidIndex { index: 2, total: 2 }
+        );
+    }
+
+    #[test]
+    fn split_errors() {
+        // "psbt" + '1' + header + checksum = 19 characters.
+        assert_eq!(split(hrp(), &[0; 10], 19), Err(SplitError::PartTooShort(19)));
+        assert!(split(hrp(), &[0; 10], 20).is_ok());
+        assert_eq!(split(hrp(), &[0; 10], 1024), Err(SplitError::PartTooLong(1024)));
+        assert_eq!(split(hrp(), &[0; 1000], 20), Err(SplitError::TooManyParts(1615)));
+    }
+}
@@ -0,0 +1,462 @@
+// SPDX-License-Identifier: MIT
+
+//! Nostr API - enables encoding and decoding [NIP-19] bech32 entities.
+//!
+//! NIP-19 entities are bech32 strings, the human-readable part identifies the kind of entity. The
+//! bare keys and ids (`npub`, `nsec` and `note`) hold exactly 32 bytes, the shareable identifiers
+//! (`nprofile`, `nevent` and `naddr`) hold a TLV stream with a one byte type and a one byte length.
+//! Unknown TLV records are ignored when decoding, as required by NIP-19.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::nip19::{self, Nip19};
+//!
+//! let s = "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg";
+//! let entity = nip19::decode(s).expect("valid npub");
+//!
+//! match entity {
+//!     Nip19::Pubkey(ref key) => assert_eq!(key[0], 0x7e),
+//!     _ => panic!("expected a public key"),
+//! }
+//! assert_eq!(nip19::encode(&entity).unwrap(), s);
+//! ```
+//!
+//! [NIP-19]: <https://github.com/nostr-protocol/nips/blob/master/19.md>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::convert::TryInto;
+use core::fmt;
+
+use crate::error::write_err;
+use crate::primitives::correction::CorrectableError;
+use crate::primitives::decode::{
+    CheckedHrpstring, CheckedHrpstringError, InvalidResidueError, PaddingError,
+};
+use crate::primitives::hrp::Hrp;
+use crate::Bech32;
+
+/// TLV type of the entity specific `special` record.
+const TLV_SPECIAL: u8 = 0;
+/// TLV type of a relay record.
+const TLV_RELAY: u8 = 1;
+/// TLV type of the author record.
+const TLV_AUTHOR: u8 = 2;
+/// TLV type of the kind record.
+const TLV_KIND: u8 = 3;
+
+/// The length of keys and event ids.
+const KEY_LENGTH: usize = 32;
+
+/// A decoded NIP-19 entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nip19 {
+    /// `npub`: a public key.
+    Pubkey([u8; 32]),
+    /// `nsec`: a private key.
+    SecretKey([u8; 32]),
+    /// `note`: an event id.
+    Note([u8; 32]),
+    /// `nprofile`: a public key with relay hints.
+    Profile(Profile),
+    /// `nevent`: an event id with optional hints.
+    Event(Event),
+    /// `naddr`: a reference to an addressable event.
+    Address(Address),
+}
+
+impl Nip19 {
+    /// Returns the human-readable part used to encode this entity.
+    pub fn hrp(&self) -> Hrp {
+        let hrp = match *self {
+            Nip19::Pubkey(_) => "npub",
+            Nip19::SecretKey(_) => "nsec",
+            Nip19::Note(_) => "note",
+            Nip19::Profile(_) => "nprofile",
+            Nip19::Event(_) => "nevent",
+            Nip19::Address(_) => "naddr",
+        };
+        Hrp::parse_unchecked(hrp)
+    }
+}
+
+/// The `nprofile` entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// The public key of the profile.
+    pub pubkey: [u8; 32],
+    /// Relays on which the profile is likely to be found.
+    pub relays: Vec<String>,
+}
+
+/// The `nevent` entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The event id.
+    pub id: [u8; 32],
+    /// Relays on which the event is likely to be found.
+    pub relays: Vec<String>,
+    /// The public key of the event author, if known.
+    pub author: Option<[u8; 32]>,
+    /// The event kind, if known.
+    pub kind: Option<u32>,
+}
+
+/// The `naddr` entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    /// The `d` tag of the event.
+    pub identifier: String,
+    /// Relays on which the event is likely to be found.
+    pub relays: Vec<String>,
+    /// The public key of the event author.
+    pub author: [u8; 32],
+    /// The event kind.
+    pub kind: u32,
+}
+
+/// Decodes a NIP-19 entity.
+pub fn decode(s: &str) -> Result<Nip19, DecodeError> {
+    use DecodeError::*;
+
+    let checked = CheckedHrpstring::new::<Bech32>(s)?;
+    checked.validate_segwit_padding()?;
+    let hrp = checked.hrp();
+    let data = checked.byte_iter().collect::<Vec<u8>>();
+
+    let entity = match hrp.to_lowercase().as_str() {
+        "npub" => Nip19::Pubkey(key(&data)?),
+        "nsec" => Nip19::SecretKey(key(&data)?),
+        "note" => Nip19::Note(key(&data)?),
+        "nprofile" => {
+            let tlv = Tlv::parse(&data)?;
+            Nip19::Profile(Profile {
+                pubkey: key(tlv.special.ok_or(MissingField(TLV_SPECIAL))?)?,
+                relays: tlv.relays,
+            })
+        }
+        "nevent" => {
+            let tlv = Tlv::parse(&data)?;
+            Nip19::Event(Event {
+                id: key(tlv.special.ok_or(MissingField(TLV_SPECIAL))?)?,
+                relays: tlv.relays,
+                author: tlv.author,
+                kind: tlv.kind,
+            })
+        }
+        "naddr" => {
+            let tlv = Tlv::parse(&data)?;
+            let identifier = tlv.special.ok_or(MissingField(TLV_SPECIAL))?;
+            Nip19::Address(Address {
+                identifier: String::from_utf8(identifier.to_vec()).map_err(|_| InvalidUtf8)?,
+                relays: tlv.relays,
+                author: tlv.author.ok_or(MissingField(TLV_AUTHOR))?,
+                kind: tlv.kind.ok_or(MissingField(TLV_KIND))?,
+            })
+        }
+        _ => return Err(UnknownHrp(hrp)),
+    };
+    Ok(entity)
+}
+
+/// Encodes a NIP-19 entity as a lowercase bech32 string.
+pub fn encode(entity: &Nip19) -> Result<String, EncodeError> {
+    let mut data = Vec::new();
+    match *entity {
+        Nip19::Pubkey(ref key) | Nip19::SecretKey(ref key) | Nip19::Note(ref key) =>
+            data.extend_from_slice(key),
+        Nip19::Profile(ref profile) => {
+            push_tlv(&mut data, TLV_SPECIAL, &profile.pubkey)?;
+            push_relays(&mut data, &profile.relays)?;
+        }
+        Nip19::Event(ref event) => {
+            push_tlv(&mut data, TLV_SPECIAL, &event.id)?;
+            push_relays(&mut data, &event.relays)?;
+            if let Some(ref author) = event.author {
+                push_tlv(&mut data, TLV_AUTHOR, author)?;
+            }
+            if let Some(kind) = event.kind {
+                push_tlv(&mut data, TLV_KIND, &kind.to_be_bytes())?;
+            }
+        }
+        Nip19::Address(ref address) => {
+            push_tlv(&mut data, TLV_SPECIAL, address.identifier.as_bytes())?;
+            push_relays(&mut data, &address.relays)?;
+            push_tlv(&mut data, TLV_AUTHOR, &address.author)?;
+            push_tlv(&mut data, TLV_KIND, &address.kind.to_be_bytes())?;
+        }
+    }
+    Ok(crate::encode_lower::<Bech32>(entity.hrp(), &data)?)
+}
+
+/// The known records of a TLV stream.
+struct Tlv<'a> {
+    special: Option<&'a [u8]>,
+    relays: Vec<String>,
+    author: Option<[u8; 32]>,
+    kind: Option<u32>,
+}
+
+impl<'a> Tlv<'a> {
+    /// Parses a TLV stream, only the first `special`, `author` and `kind` records are used.
+    fn parse(mut data: &'a [u8]) -> Result<Self, DecodeError> {
+        use DecodeError::*;
+
+        let mut tlv = Tlv { special: None, relays: Vec::new(), author: None, kind: None };
+        while !data.is_empty() {
+            if data.len() < 2 {
+                return Err(TruncatedTlv);
+            }
+            let (tlv_type, len) = (data[0], usize::from(data[1]));
+            if data.len() < 2 + len {
+                return Err(TruncatedTlv);
+            }
+            let value = &data[2..2 + len];
+            data = &data[2 + len..];
+
+            match tlv_type {
+                TLV_SPECIAL if tlv.special.is_none() => tlv.special = Some(value),
+                TLV_RELAY =>
+                    tlv.relays.push(String::from_utf8(value.to_vec()).map_err(|_| InvalidUtf8)?),
+                TLV_AUTHOR if tlv.author.is_none() => tlv.author = Some(key(value)?),
+                TLV_KIND if tlv.kind.is_none() => {
+                    let kind = value.try_into().map_err(|_| InvalidLength(value.len()))?;
+                    tlv.kind = Some(u32::from_be_bytes(kind));
+                }
+                _ => {} // Unknown and repeated records are ignored.
+            }
+        }
+        Ok(tlv)
+    }
+}
+
+/// Converts `data` to a 32-byte key or event id.
+fn key(data: &[u8]) -> Result<[u8; KEY_LENGTH], DecodeError> {
+    data.try_into().map_err(|_| DecodeError::InvalidLength(data.len()))
+}
+
+/// Appends a TLV record to `data`.
+fn push_tlv(data: &mut Vec<u8>, tlv_type: u8, value: &[u8]) -> Result<(), EncodeError> {
+    if value.len() > usize::from(u8::MAX) {
+        return Err(EncodeError::ValueTooLong(value.len()));
+    }
+    data.push(tlv_type);
+    data.push(value.len() as u8);
+    data.extend_from_slice(value);
+    Ok(())
+}
+
+/// Appends a relay TLV record to `data` for each relay.
+fn push_relays(data: &mut Vec<u8>, relays: &[String]) -> Result<(), EncodeError> {
+    relays.iter().try_for_each(|relay| push_tlv(data, TLV_RELAY, relay.as_bytes()))
+}
+
+/// An error while decoding a NIP-19 entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// Error while parsing the bech32 string.
+    Checked(CheckedHrpstringError),
+    /// The data part has invalid padding.
+    Padding(PaddingError),
+    /// The human-readable part is not a known NIP-19 prefix.
+    UnknownHrp(Hrp),
+    /// A key, event id or kind has an invalid length.
+    InvalidLength(usize),
+    /// A TLV record extends past the end of the data.
+    TruncatedTlv,
+    /// A required TLV record is missing.
+    MissingField(u8),
+    /// A relay or identifier is not valid UTF-8.
+    InvalidUtf8,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DecodeError::*;
+
+        match *self {
+            Checked(ref e) => write_err!(f, "invalid bech32 string"; e),
+            Padding(ref e) => write_err!(f, "invalid padding"; e),
+            UnknownHrp(ref hrp) => write!(f, "unknown human-readable part: {}", hrp),
+            InvalidLength(len) => write!(f, "invalid field length: {} bytes", len),
+            TruncatedTlv => write!(f, "TLV record extends past the end of the data"),
+            MissingField(t) => write!(f, "missing required TLV record of type {}", t),
+            InvalidUtf8 => write!(f, "TLV record is not valid UTF-8"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use DecodeError::*;
+
+        match *self {
+            Checked(ref e) => Some(e),
+            Padding(ref e) => Some(e),
+            UnknownHrp(_) | InvalidLength(_) | TruncatedTlv | MissingField(_) | InvalidUtf8 => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for DecodeError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Checked(e) }
+}
+
+impl From<PaddingError> for DecodeError {
+    #[inline]
+    fn from(e: PaddingError) -> Self { Self::Padding(e) }
+}
+
+impl CorrectableError for DecodeError {
+    fn residue_error(&self) -> Option<&InvalidResidueError> {
+        match self {
+            DecodeError::Checked(ref e) => e.residue_error(),
+            _ => None,
+        }
+    }
+}
+
+/// An error while encoding a NIP-19 entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// A TLV value is longer than 255 bytes.
+    ValueTooLong(usize),
+    /// Error while encoding the bech32 string.
+    Encode(crate::EncodeError),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use EncodeError::*;
+
+        match *self {
+            ValueTooLong(len) => write!(f, "TLV value too long: {} bytes", len),
+            Encode(ref e) => write_err!(f, "bech32 encoding failed"; e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use EncodeError::*;
+
+        match *self {
+            ValueTooLong(_) => None,
+            Encode(ref e) => Some(e),
+        }
+    }
+}
+
+impl From<crate::EncodeError> for EncodeError {
+    #[inline]
+    fn from(e: crate::EncodeError) -> Self { Self::Encode(e) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from NIP-19.
+    const NPUB: &str = "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg";
+    const NSEC: &str = "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5";
+    const NPROFILE: &str = "nprofile1qqsrhuxx8l9ex335q7he0f09aej04zpazpl0ne2cgukyawd24mayt8gpp4mhxue69uhhytnc9e3k7mgpz4mhxue69uhkg6nzv9ejuumpv34kytnrdaksjlyr9p";
+
+    const PUBKEY: [u8; 32] = [
+        0x3b, 0xf0, 0xc6, 0x3f, 0xcb, 0x93, 0x46, 0x34, 0x07, 0xaf, 0x97, 0xa5, 0xe5, 0xee, 0x64,
+        0xfa, 0x88, 0x3d, 0x10, 0x7e, 0xf9, 0xe5, 0x58, 0x47, 0x2c, 0x4e, 0xb9, 0xaa, 0xae, 0xfa,
+        0x45, 0x9d,
+    ];
+
+    #[test]
+    fn decode_keys() {
+        match decode(NPUB).unwrap() {
+            Nip19::Pubkey(key) => assert_eq!(&key[..4], &[0x7e, 0x7e, 0x9c, 0x42]),
+            e => panic!("unexpected entity: {:?}", e),
+        }
+        match decode(NSEC).unwrap() {
+            Nip19::SecretKey(key) => assert_eq!(&key[..4], &[0x67, 0xde, 0xa2, 0xed]),
+            e => panic!("unexpected entity: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn decode_nprofile() {
+        let expected = Nip19::Profile(Profile {
+            pubkey: PUBKEY,
+            relays: vec!["wss://r.x.com".into(), "wss://djbas.sadkb.com".into()],
+        });
+        assert_eq!(decode(NPROFILE).unwrap(), expected);
+    }
+
+    #[test]
+    fn roundtrip() {
+        for s in [NPUB, NSEC, NPROFILE].iter() {
+            assert_eq!(encode(&decode(s).unwrap()).unwrap(), *s);
+        }
+
+        let entities = [
+            Nip19::Note([0x11; 32]),
+            Nip19::Event(Event { id: [0x22; 32], relays: vec![], author: None, kind: None }),
+            Nip19::Event(Event {
+                id: [0x22; 32],
+                relays: vec!["wss://relay.example.com".into()],
+                author: Some(PUBKEY),
+                kind: Some(1),
+            }),
+            Nip19::Address(Address {
+                identifier: "my-article".into(),
+                relays: vec!["wss://relay.example.com".into()],
+                author: PUBKEY,
+                kind: 30023,
+            }),
+        ];
+        for entity in entities.iter() {
+            let s = encode(entity).unwrap();
+            assert!(s.starts_with(entity.hrp().as_str()));
+            assert_eq!(decode(&s).unwrap(), *entity);
+        }
+    }
+
+    #[test]
+    fn unknown_tlv_is_ignored() {
+        let mut data = vec![];
+        push_tlv(&mut data, TLV_SPECIAL, &PUBKEY).unwrap();
+        push_tlv(&mut data, 42, b"future").unwrap();
+        let s = crate::encode::<Bech32>(Hrp::parse_unchecked("nprofile"), &data).unwrap();
+        assert_eq!(decode(&s).unwrap(), Nip19::Profile(Profile { pubkey: PUBKEY, relays: vec![] }));
+    }
+
+    #[test]
+    fn decode_errors() {
+        let encode = |hrp: &str, data: &[u8]| {
+            crate::encode::<Bech32>(Hrp::parse_unchecked(hrp), data).unwrap()
+        };
+
+        assert_eq!(decode(&encode("npub", &[0; 31])), Err(DecodeError::InvalidLength(31)));
+        assert_eq!(decode(&encode("note", &[0; 33])), Err(DecodeError::InvalidLength(33)));
+        assert!(matches!(decode(&encode("nfoo", &[0; 32])), Err(DecodeError::UnknownHrp(_))));
+
+        assert_eq!(decode(&encode("nevent", &[0, 32, 0])), Err(DecodeError::TruncatedTlv));
+        assert_eq!(decode(&encode("nevent", &[1, 0])), Err(DecodeError::MissingField(0)));
+        assert_eq!(decode(&encode("nevent", &[0, 1, 0])), Err(DecodeError::InvalidLength(1)));
+        assert_eq!(decode(&encode("naddr", &[0, 0])), Err(DecodeError::MissingField(2)));
+        assert_eq!(decode(&encode("nprofile", &[1, 1, 0xff])), Err(DecodeError::InvalidUtf8));
+
+        let mut kind = vec![0, 32];
+        kind.extend_from_slice(&[0; 32]);
+        kind.extend_from_slice(&[3, 2, 0, 1]);
+        assert_eq!(decode(&encode("nevent", &kind)), Err(DecodeError::InvalidLength(2)));
+    }
+
+    #[test]
+    fn encode_errors() {
+        let profile = Nip19::Profile(Profile { pubkey: PUBKEY, relays: vec!["x".repeat(256)] });
+        assert_eq!(encode(&profile), Err(EncodeError::ValueTooLong(256)));
+    }
+}
@@ -10,6 +10,7 @@ use core::marker::PhantomData;
 use core::{fmt, mem, ops};
 
 use super::Polynomial;
+use crate::primitives::correction::NO_ALLOC_MAX_LENGTH;
 use crate::primitives::hrp::Hrp;
 use crate::{Fe1024, Fe32};
 
@@ -32,8 +33,9 @@ pub trait Checksum {
     ///
     /// The smallest type possible should be used, for efficiency reasons, but the
     /// only operations we do on these types are bitwise xor and shifts, so it should
-    /// be pretty efficient no matter what.
-    type MidstateRepr: PackedFe32;
+    /// be pretty efficient no matter what. Checksums longer than 25 characters can
+    /// use [`PackedWide`].
+    type MidstateRepr: PackedFe32 + 'static;
 
     /// The extension field in which error correction happens.
     type CorrectionField: super::ExtensionField<BaseField = Fe32>;
@@ -73,6 +75,16 @@ pub trait Checksum {
     /// The residue, modulo the generator polynomial, that a valid codeword will have.
     const TARGET_RESIDUE: Self::MidstateRepr;
 
+    /// An optional lookup table which lets [`Engine`] reduce the residue with a single xor
+    /// per character, and process two characters per step in [`Engine::input_fes`].
+    ///
+    /// Entry `(a << 5) | b` is the reduction of `a·x^(n+1) + b·x^n` modulo the generator
+    /// polynomial, where `n` is [`Self::CHECKSUM_LENGTH`]. For checksums of at least two
+    /// characters whose midstate is a primitive integer, it can be computed at compile time
+    /// using [`pair_table_u32`], [`pair_table_u64`] or [`pair_table_u128`]. The residues are
+    /// the same with or without the table; [`Self::sanity_check`] verifies this.
+    const PAIR_TABLE: Option<&'static [Self::MidstateRepr; 1024]> = None;
+
     /// Sanity checks that the various constants of the trait are set in a way that they
     /// are consistent with each other.
     ///
@@ -98,6 +110,24 @@ pub trait Checksum {
                 );
             }
         }
+
+        // Check that the lookup table, if any, agrees with reducing using the generator shifts.
+        if let Some(table) = Self::PAIR_TABLE {
+            assert!(Self::CHECKSUM_LENGTH >= 2, "a lookup table requires at least two characters");
+            let len = Self::CHECKSUM_LENGTH;
+            for (idx, entry) in table.iter().enumerate() {
+                let mut residue =
+                    Self::MidstateRepr::pack([(idx >> 5) as u8, idx as u8 & 0x1f].iter().copied());
+                for _ in 0..len {
+                    input_fe(&mut residue, len, &Self::GENERATOR_SH, Fe32::Q);
+                }
+                assert!(
+                    *entry == residue,
+                    "Entry {} of the lookup table was incorrectly computed",
+                    idx
+                );
+            }
+        }
     }
 }
 
@@ -165,11 +195,13 @@ pub trait Checksum {
 /// between implementations (and between specifications) of your BCH code.
 pub struct PrintImpl<'a, ExtField = Fe1024> {
     name: &'a str,
-    generator: Polynomial<Fe32>,
+    generator: Polynomial<Fe32, NO_ALLOC_MAX_LENGTH>,
+    generator_coeffs: &'a [Fe32],
     target: &'a [Fe32],
     bit_len: usize,
     hex_width: usize,
     midstate_repr: &'static str,
+    wide_words: usize,
     phantom: PhantomData<ExtField>,
 }
 
@@ -204,27 +236,71 @@ impl<'a, ExtField> PrintImpl<'a, ExtField> {
             );
         }
 
+        // End sanity checks.
+
         let bit_len = 5 * target.len();
-        let (hex_width, midstate_repr) = if bit_len <= 32 {
-            (8, "u32")
+        let (hex_width, midstate_repr, wide_words) = if bit_len <= 32 {
+            (8, "u32", 0)
         } else if bit_len <= 64 {
-            (16, "u64")
+            (16, "u64", 0)
         } else if bit_len <= 128 {
-            (32, "u128")
+            (32, "u128", 0)
         } else {
-            panic!("Generator length {} cannot exceed 25, as we cannot represent it by packing bits into a Rust numeric type", generator.len());
+            let fes_per_word = PackedWide::<1>::WIDTH;
+            (15, "PackedWide", (target.len() + fes_per_word - 1) / fes_per_word)
         };
-        // End sanity checks.
         PrintImpl {
             name,
             generator: Polynomial::with_monic_leading_term(generator),
+            generator_coeffs: generator,
             target,
             bit_len,
             hex_width,
             midstate_repr,
+            wide_words,
             phantom: PhantomData,
         }
     }
+
+    /// Writes the name of the `MidstateRepr` type.
+    fn write_midstate_repr(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.wide_words == 0 {
+            f.write_str(self.midstate_repr)
+        } else {
+            write!(f, "{}<{}>", self.midstate_repr, self.wide_words)
+        }
+    }
+
+    /// Writes a packed polynomial, given in the same order as the arguments to [`Self::new`],
+    /// as a literal of the `MidstateRepr` type.
+    fn write_packed<I>(&self, f: &mut fmt::Formatter, fes: I) -> fmt::Result
+    where
+        I: Iterator<Item = Fe32> + Clone,
+    {
+        if self.wide_words == 0 {
+            let packed = u128::pack(fes.map(From::from));
+            return write!(f, "0x{:0width$x}", packed, width = self.hex_width);
+        }
+
+        // The first element yielded is the coefficient of highest degree, see `PackedFe32::pack`.
+        let fes_per_word = PackedWide::<1>::WIDTH;
+        let len = fes.clone().count();
+        write!(f, "{}([", self.midstate_repr)?;
+        for word_idx in 0..self.wide_words {
+            let mut word = 0u64;
+            for (i, fe) in fes.clone().enumerate() {
+                let n = len - 1 - i;
+                if n / fes_per_word == word_idx {
+                    word |= u64::from(fe.to_u8()) << (5 * (n % fes_per_word));
+                }
+            }
+            if word_idx > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "0x{:0width$x}", word, width = self.hex_width)?;
+        }
+        f.write_str("])")
+    }
 }
 
 impl<'a, ExtField> fmt::Display for PrintImpl<'a, ExtField>
@@ -245,11 +321,9 @@ where
         }
         f.write_str("\n")?;
         writeln!(f, "impl Checksum for {} {{", self.name)?;
-        writeln!(
-            f,
-            "    type MidstateRepr = {}; // checksum packs into {} bits",
-            self.midstate_repr, self.bit_len
-        )?;
+        f.write_str("    type MidstateRepr = ")?;
+        self.write_midstate_repr(f)?;
+        writeln!(f, "; // checksum packs into {} bits", self.bit_len)?;
         f.write_str("\n")?;
         writeln!(f, "    type CorrectionField = {};", core::any::type_name::<ExtField>())?;
         f.write_str("    const ROOT_GENERATOR: Self::CorrectionField = ")?;
@@ -264,22 +338,22 @@ where
         f.write_str("\n")?;
         writeln!(f, "    const CODE_LENGTH: usize = {};", length)?;
         writeln!(f, "    const CHECKSUM_LENGTH: usize = {};", self.generator.degree())?;
-        writeln!(f, "    const GENERATOR_SH: [{}; 5] = [", self.midstate_repr)?;
-        let mut gen5 = self.generator.clone().into_inner();
+        f.write_str("    const GENERATOR_SH: [")?;
+        self.write_midstate_repr(f)?;
+        f.write_str("; 5] = [\n")?;
+        let mut shift = Fe32::P;
         for _ in 0..5 {
-            let gen_packed = u128::pack(gen5.iter().copied().map(From::from));
-            writeln!(f, "        0x{:0width$x},", gen_packed, width = self.hex_width)?;
-            gen5.iter_mut().for_each(|x| *x *= Fe32::Z);
+            f.write_str("        ")?;
+            self.write_packed(f, self.generator_coeffs.iter().map(|fe| *fe * shift))?;
+            f.write_str(",\n")?;
+            shift *= Fe32::Z;
         }
         writeln!(f, "    ];")?;
-        writeln!(
-            f,
-            "    const TARGET_RESIDUE: {} = 0x{:0width$x};",
-            self.midstate_repr,
-            u128::pack(self.target.iter().copied().map(From::from)),
-            width = self.hex_width,
-        )?;
-        f.write_str("}")
+        f.write_str("    const TARGET_RESIDUE: ")?;
+        self.write_midstate_repr(f)?;
+        f.write_str(" = ")?;
+        self.write_packed(f, self.target.iter().copied())?;
+        f.write_str(";\n}")
     }
 }
 
@@ -287,11 +361,26 @@ where
 ///
 /// Use this to verify a checksum, feed it the data to be checksummed using
 /// the `Self::input_*` methods.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug)]
 pub struct Engine<Ck: Checksum> {
     residue: Ck::MidstateRepr,
 }
 
+// Implemented manually since deriving would needlessly require `Ck` itself to implement these.
+impl<Ck: Checksum> Clone for Engine<Ck> {
+    #[inline]
+    fn clone(&self) -> Self { *self }
+}
+
+impl<Ck: Checksum> Copy for Engine<Ck> {}
+
+impl<Ck: Checksum> PartialEq for Engine<Ck> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.residue == other.residue }
+}
+
+impl<Ck: Checksum> Eq for Engine<Ck> {}
+
 impl<Ck: Checksum> Default for Engine<Ck> {
     fn default() -> Self { Self::new() }
 }
@@ -303,22 +392,46 @@ impl<Ck: Checksum> Engine<Ck> {
 
     /// Feeds `hrp` into the checksum engine.
     #[inline]
-    pub fn input_hrp(&mut self, hrp: Hrp) {
-        for fe in HrpFe32Iter::new(&hrp) {
-            self.input_fe(fe)
-        }
-    }
+    pub fn input_hrp(&mut self, hrp: Hrp) { self.input_fes(HrpFe32Iter::new(&hrp)) }
 
     /// Adds a single gf32 element to the checksum engine.
     ///
     /// This is where the actual checksum computation magic happens.
     #[inline]
     pub fn input_fe(&mut self, e: Fe32) {
-        let xn = self.residue.mul_by_x_then_add(Ck::CHECKSUM_LENGTH, e.into());
-        for i in 0..5 {
-            if xn & (1 << i) != 0 {
-                self.residue = self.residue ^ Ck::GENERATOR_SH[i];
+        match Ck::PAIR_TABLE {
+            Some(table) => {
+                let xn = self.residue.mul_by_x_then_add(Ck::CHECKSUM_LENGTH, e.into());
+                self.residue = self.residue ^ table[usize::from(xn)];
             }
+            None => input_fe(&mut self.residue, Ck::CHECKSUM_LENGTH, &Ck::GENERATOR_SH, e),
+        }
+    }
+
+    /// Adds a sequence of gf32 elements to the checksum engine.
+    ///
+    /// This is equivalent to calling [`Self::input_fe`] for each element, but if the checksum
+    /// has a [`Checksum::PAIR_TABLE`] the elements are processed two at a time.
+    #[inline]
+    pub fn input_fes<I: IntoIterator<Item = Fe32>>(&mut self, fes: I) {
+        let table = match Ck::PAIR_TABLE {
+            Some(table) if Ck::CHECKSUM_LENGTH >= 2 => table,
+            _ => return fes.into_iter().for_each(|fe| self.input_fe(fe)),
+        };
+
+        let mut iter = fes.into_iter();
+        while let Some(hi) = iter.next() {
+            // Shift both elements in without reducing, then reduce both dropped coefficients
+            // with a single lookup.
+            let a = self.residue.mul_by_x_then_add(Ck::CHECKSUM_LENGTH, hi.into());
+            let b = match iter.next() {
+                Some(lo) => self.residue.mul_by_x_then_add(Ck::CHECKSUM_LENGTH, lo.into()),
+                None => {
+                    self.residue = self.residue ^ table[usize::from(a)];
+                    return;
+                }
+            };
+            self.residue = self.residue ^ table[usize::from(a) << 5 | usize::from(b)];
         }
     }
 
@@ -335,15 +448,249 @@ impl<Ck: Checksum> Engine<Ck> {
         }
     }
 
+    /// Shifts the residue as though `n` zero field elements (`q` characters) had been input.
+    ///
+    /// For large `n` this takes time logarithmic in `n`, rather than linear.
+    pub fn shift(&mut self, n: usize) { self.residue = shift::<Ck>(self.residue, n) }
+
+    /// Combines this engine with the engine of the data which follows it.
+    ///
+    /// `other` must have been created with [`Self::new`] and fed exactly `len` field elements.
+    /// Afterwards, the residue of `self` is the same as if those elements had been input into
+    /// `self` directly. Because BCH residues are linear, this lets a long input be split into
+    /// chunks which are checksummed independently, for example in parallel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32::primitives::checksum::Engine;
+    /// use bech32::{Bech32m, Fe32};
+    ///
+    /// let data = [Fe32::Q, Fe32::P, Fe32::Z, Fe32::R, Fe32::Y, Fe32::_9, Fe32::X];
+    ///
+    /// let mut whole = Engine::<Bech32m>::new();
+    /// whole.input_fes(data.iter().copied());
+    ///
+    /// let (head, tail) = data.split_at(3);
+    /// let mut engine = Engine::<Bech32m>::new();
+    /// engine.input_fes(head.iter().copied());
+    /// let mut tail_engine = Engine::<Bech32m>::new();
+    /// tail_engine.input_fes(tail.iter().copied());
+    /// engine.combine(&tail_engine, tail.len());
+    ///
+    /// assert_eq!(engine.residue(), whole.residue());
+    /// ```
+    pub fn combine(&mut self, other: &Self, len: usize) {
+        // `other` started from the residue 1 rather than 0, which contributes x^len to its
+        // residue; this cancels against the shifted initial 1 of `self`.
+        self.residue = shift::<Ck>(self.residue ^ Ck::MidstateRepr::ONE, len) ^ other.residue;
+    }
+
+    /// Updates the residue to reflect that the field element which was input `n` positions before
+    /// the end of the data (so `0` is the last element input) was `new` rather than `old`.
+    ///
+    /// This allows re-checking a string after changing a single character without recomputing
+    /// the whole checksum.
+    pub fn replace_fe(&mut self, n: usize, old: Fe32, new: Fe32) {
+        let x_n = shift::<Ck>(Ck::MidstateRepr::ONE, n);
+        self.residue = self.residue ^ scale(x_n, Ck::CHECKSUM_LENGTH, old + new);
+    }
+
     /// Returns for the current checksum residue.
     #[inline]
     pub fn residue(&self) -> &Ck::MidstateRepr { &self.residue }
 }
 
+/// A human-readable part together with the checksum midstate after inputting it.
+///
+/// Every string with a given HRP starts its checksum computation the same way, so when encoding
+/// or decoding many strings with the same HRP this lets the HRP be expanded and fed into the
+/// checksum [`Engine`] once rather than once per string.
+///
+/// # Examples
+///
+/// ```
+/// use bech32::primitives::checksum::PreparedHrp;
+/// use bech32::primitives::decode::CheckedHrpstring;
+/// use bech32::{hrp, Bech32m};
+///
+/// let prepared = PreparedHrp::<Bech32m>::new(hrp::BC);
+/// let addrs = [
+///     "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+///     "BC1SW50QGDZ25J",
+///     "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+/// ];
+/// for s in &addrs {
+///     let checked = CheckedHrpstring::new_with_prepared_hrp(s, &prepared).unwrap();
+///     assert_eq!(checked.hrp(), hrp::BC);
+/// }
+/// ```
+pub struct PreparedHrp<Ck: Checksum> {
+    hrp: Hrp,
+    engine: Engine<Ck>,
+}
+
+impl<Ck: Checksum> fmt::Debug for PreparedHrp<Ck> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PreparedHrp").field("hrp", &self.hrp).finish_non_exhaustive()
+    }
+}
+
+impl<Ck: Checksum> Clone for PreparedHrp<Ck> {
+    #[inline]
+    fn clone(&self) -> Self { *self }
+}
+
+impl<Ck: Checksum> Copy for PreparedHrp<Ck> {}
+
+impl<Ck: Checksum> PreparedHrp<Ck> {
+    /// Inputs `hrp` into a new checksum engine.
+    #[inline]
+    pub fn new(hrp: Hrp) -> Self {
+        let mut engine = Engine::new();
+        engine.input_hrp(hrp);
+        PreparedHrp { hrp, engine }
+    }
+
+    /// Returns the human-readable part.
+    #[inline]
+    pub fn hrp(&self) -> &Hrp { &self.hrp }
+
+    /// Returns a checksum engine which has had the human-readable part input into it.
+    #[inline]
+    pub fn engine(&self) -> Engine<Ck> { self.engine }
+
+    /// Returns the engine primed with `hrp`, using the cached midstate if it is for the same HRP.
+    #[inline]
+    pub(crate) fn engine_for(prepared: Option<&Self>, hrp: Hrp) -> Engine<Ck> {
+        match prepared {
+            Some(prepared) if prepared.hrp == hrp => prepared.engine,
+            _ => PreparedHrp::new(hrp).engine,
+        }
+    }
+
+    /// Reuses the midstate for a checksum with the same generator polynomial.
+    ///
+    /// The HRP midstate does not depend on the target residue, so e.g. it is the same for bech32
+    /// and bech32m.
+    #[inline]
+    pub(crate) fn with_checksum<Other>(&self) -> PreparedHrp<Other>
+    where
+        Other: Checksum<MidstateRepr = Ck::MidstateRepr>,
+    {
+        debug_assert!(Ck::CHECKSUM_LENGTH == Other::CHECKSUM_LENGTH);
+        debug_assert!(Ck::GENERATOR_SH == Other::GENERATOR_SH);
+        PreparedHrp { hrp: self.hrp, engine: Engine { residue: self.engine.residue } }
+    }
+}
+
+/// Adds a single gf32 element to `residue`, reducing modulo the generator given by its shifts.
+///
+/// This is the computation done by [`Engine::input_fe`], for callers that only know the
+/// checksum parameters at runtime.
+#[inline]
+pub(crate) fn input_fe<R: PackedFe32>(
+    residue: &mut R,
+    checksum_length: usize,
+    generator_sh: &[R; 5],
+    e: Fe32,
+) {
+    let xn = residue.mul_by_x_then_add(checksum_length, e.into());
+    for (i, sh) in generator_sh.iter().enumerate() {
+        if xn & (1 << i) != 0 {
+            *residue = *residue ^ *sh;
+        }
+    }
+}
+
+macro_rules! impl_pair_table {
+    ($name:ident, $ty:ident) => {
+        #[doc = concat!("Computes a [`Checksum::PAIR_TABLE`] for a checksum with a `", stringify!($ty), "` midstate,")]
+        /// given its [`Checksum::CHECKSUM_LENGTH`] and [`Checksum::GENERATOR_SH`].
+        ///
+        /// This is a `const fn` so that the table can be computed at compile time. The checksum
+        /// length must be at least 2, and no more than the number of field elements which fit
+        /// into the midstate.
+        pub const fn $name(checksum_length: usize, generator_sh: &[$ty; 5]) -> [$ty; 1024] {
+            // The reduction of b·x^n, for every b.
+            let mut single = [0; 32];
+            let mut b = 0;
+            while b < 32 {
+                let mut i = 0;
+                while i < 5 {
+                    if b & (1 << i) != 0 {
+                        single[b] ^= generator_sh[i];
+                    }
+                    i += 1;
+                }
+                b += 1;
+            }
+
+            let top_shift = 5 * (checksum_length - 1);
+            let mut ret = [0; 1024];
+            let mut a = 0;
+            while a < 32 {
+                // a·x^(n+1) is x times the reduction of a·x^n, which must be reduced again.
+                let top = (single[a] >> top_shift) as usize & 0x1f;
+                let ax = ((single[a] & !(0x1f << top_shift)) << 5) ^ single[top];
+                let mut b = 0;
+                while b < 32 {
+                    ret[a << 5 | b] = ax ^ single[b];
+                    b += 1;
+                }
+                a += 1;
+            }
+            ret
+        }
+    };
+}
+impl_pair_table!(pair_table_u32, u32);
+impl_pair_table!(pair_table_u64, u64);
+impl_pair_table!(pair_table_u128, u128);
+
+/// Multiplies the first `len` coefficients of a packed polynomial by a field element.
+fn scale<R: PackedFe32>(r: R, len: usize, e: Fe32) -> R {
+    R::pack((0..len).rev().map(|i| (Fe32(r.unpack(i)) * e).to_u8()))
+}
+
+/// Multiplies two residues modulo the generator polynomial of `Ck`.
+fn mul_mod<Ck: Checksum>(a: Ck::MidstateRepr, b: Ck::MidstateRepr) -> Ck::MidstateRepr {
+    let len = Ck::CHECKSUM_LENGTH;
+    let mut ret = Ck::MidstateRepr::pack(core::iter::empty());
+    for i in (0..len).rev() {
+        input_fe(&mut ret, len, &Ck::GENERATOR_SH, Fe32::Q);
+        ret = ret ^ scale(a, len, Fe32(b.unpack(i)));
+    }
+    ret
+}
+
+/// Multiplies a residue by x^n modulo the generator polynomial of `Ck`.
+fn shift<Ck: Checksum>(mut residue: Ck::MidstateRepr, n: usize) -> Ck::MidstateRepr {
+    let len = Ck::CHECKSUM_LENGTH;
+    // Each multiplication costs about as much as inputting `len` elements, so only compute
+    // x^n by repeated squaring when that is cheaper.
+    if n <= 2 * len * (usize::BITS - n.leading_zeros()) as usize {
+        for _ in 0..n {
+            input_fe(&mut residue, len, &Ck::GENERATOR_SH, Fe32::Q);
+        }
+        return residue;
+    }
+
+    let mut x_n = Ck::MidstateRepr::ONE;
+    for bit in (0..usize::BITS - n.leading_zeros()).rev() {
+        x_n = mul_mod::<Ck>(x_n, x_n);
+        if (n >> bit) & 1 == 1 {
+            input_fe(&mut x_n, len, &Ck::GENERATOR_SH, Fe32::Q);
+        }
+    }
+    mul_mod::<Ck>(residue, x_n)
+}
+
 /// Trait describing an integer type which can be used as a "packed" sequence of Fe32s.
 ///
 /// This is implemented for u32, u64 and u128, as a way to treat these primitive types as
-/// packed coefficients of polynomials over GF32 (up to some maximal degree, of course).
+/// packed coefficients of polynomials over GF32 (up to some maximal degree, of course). For
+/// polynomials of degree greater than 25 use [`PackedWide`].
 ///
 /// This is useful because then multiplication by x reduces to simply left-shifting by 5,
 /// and addition of entire polynomials can be done by xor.
@@ -446,6 +793,86 @@ impl_packed_fe32!(u32);
 impl_packed_fe32!(u64);
 impl_packed_fe32!(u128);
 
+/// A packed sequence of Fe32s spread over `N` 64-bit words, for checksums which are too long to
+/// fit into a `u128` (more than 25 characters).
+///
+/// Each word holds 12 field elements in its low 60 bits; the first word holds the coefficients
+/// of lowest degree. So e.g. a 40-character checksum can be represented by `PackedWide<4>`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PackedWide<const N: usize>(pub [u64; N]);
+
+impl<const N: usize> PackedWide<N> {
+    /// The number of field elements stored in each word.
+    const FES_PER_WORD: usize = 12;
+
+    /// Mask for the bits of a word which are used to store field elements.
+    const WORD_MASK: u64 = (1 << (5 * Self::FES_PER_WORD)) - 1;
+
+    /// Multiplies the polynomial by x, dropping its highest coefficient.
+    #[inline]
+    fn shl_fe(&mut self) {
+        for i in (0..N).rev() {
+            let carry = if i > 0 { self.0[i - 1] >> (5 * (Self::FES_PER_WORD - 1)) } else { 0 };
+            self.0[i] = ((self.0[i] << 5) & Self::WORD_MASK) | carry;
+        }
+    }
+}
+
+/// Constructs the words of [`PackedWide::ONE`].
+const fn wide_one<const N: usize>() -> [u64; N] {
+    let mut ret = [0; N];
+    ret[0] = 1;
+    ret
+}
+
+impl<const N: usize> ops::BitXor<PackedWide<N>> for PackedWide<N> {
+    type Output = PackedWide<N>;
+    #[inline]
+    fn bitxor(mut self, other: PackedWide<N>) -> PackedWide<N> {
+        for (word, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *word ^= *other;
+        }
+        self
+    }
+}
+
+impl<const N: usize> PackedFe32 for PackedWide<N> {
+    const ONE: Self = PackedWide(wide_one());
+    const WIDTH: usize = Self::FES_PER_WORD * N;
+
+    #[inline]
+    fn unpack(&self, n: usize) -> u8 {
+        debug_assert!(n < Self::WIDTH);
+        let word = self.0[n / Self::FES_PER_WORD];
+        (word >> (5 * (n % Self::FES_PER_WORD))) as u8 & 0x1f
+    }
+
+    #[inline]
+    fn mul_by_x_then_add(&mut self, degree: usize, add: u8) -> u8 {
+        debug_assert!(degree > 0);
+        debug_assert!(degree <= Self::WIDTH);
+        debug_assert!(add < 32);
+        let ret = self.unpack(degree - 1);
+        let n = degree - 1;
+        self.0[n / Self::FES_PER_WORD] &= !(0x1f << (5 * (n % Self::FES_PER_WORD)));
+        self.shl_fe();
+        self.0[0] |= u64::from(add);
+        ret
+    }
+
+    #[inline]
+    fn pack<I: Iterator<Item = u8>>(iter: I) -> Self {
+        let mut ret = PackedWide([0; N]);
+        for (n, elem) in iter.enumerate() {
+            debug_assert!(elem < 32);
+            debug_assert!(n < Self::WIDTH);
+            ret.shl_fe();
+            ret.0[0] |= u64::from(elem);
+        }
+        ret
+    }
+}
+
 /// Iterator that yields the field elements that are input into a checksum algorithm for an [`Hrp`].
 pub struct HrpFe32Iter<'hrp> {
     /// `None` once the hrp high fes have been yielded.
@@ -531,6 +958,136 @@ mod tests {
         assert_eq!(packed.unpack(3), 1);
     }
 
+    #[test]
+    fn pack_unpack_wide() {
+        let fes = (0..30).map(|i| i as u8 + 1);
+        let packed = PackedWide::<3>::pack(fes.clone());
+        for (i, fe) in fes.enumerate() {
+            assert_eq!(packed.unpack(29 - i), fe);
+        }
+        assert_eq!(packed.unpack(30), 0);
+        assert_eq!(PackedWide::<3>::pack([0, 0, 1].iter().copied()), PackedWide::<3>::ONE);
+
+        // Multiplying by x carries coefficients across words.
+        let mut packed = PackedWide::<2>::pack((0..12).map(|_| 31));
+        assert_eq!(packed.mul_by_x_then_add(24, 7), 0);
+        assert_eq!(packed, PackedWide([0xfff_ffff_ffff_ffe7, 31]));
+        assert_eq!(packed.mul_by_x_then_add(13, 0), 31);
+        assert_eq!(packed, PackedWide([0xfff_ffff_ffff_fce0, 31]));
+
+        // Agrees with the primitive representation.
+        let mut wide = PackedWide::<2>::ONE;
+        let mut narrow = u128::ONE;
+        for i in 0..40 {
+            let fe = (i * 7 % 32) as u8;
+            assert_eq!(wide.mul_by_x_then_add(20, fe), narrow.mul_by_x_then_add(20, fe));
+            assert!((0..20).all(|n| wide.unpack(n) == narrow.unpack(n)));
+        }
+    }
+
+    #[test]
+    fn pair_table_engine() {
+        use crate::primitives::{Bech32, Codex32, DescriptorChecksum};
+
+        // Codex32 with a lookup table, to check the u128 table and a long checksum.
+        enum TabledCodex32 {}
+        impl Checksum for TabledCodex32 {
+            type MidstateRepr = u128;
+            type CorrectionField = <Codex32 as Checksum>::CorrectionField;
+            const ROOT_GENERATOR: Self::CorrectionField = Codex32::ROOT_GENERATOR;
+            const ROOT_EXPONENTS: core::ops::RangeInclusive<usize> = Codex32::ROOT_EXPONENTS;
+            const CODE_LENGTH: usize = Codex32::CODE_LENGTH;
+            const CHECKSUM_LENGTH: usize = Codex32::CHECKSUM_LENGTH;
+            const GENERATOR_SH: [u128; 5] = Codex32::GENERATOR_SH;
+            const TARGET_RESIDUE: u128 = Codex32::TARGET_RESIDUE;
+            const PAIR_TABLE: Option<&'static [u128; 1024]> =
+                Some(&pair_table_u128(Codex32::CHECKSUM_LENGTH, &Codex32::GENERATOR_SH));
+        }
+        TabledCodex32::sanity_check();
+
+        let table =
+            pair_table_u64(DescriptorChecksum::CHECKSUM_LENGTH, &DescriptorChecksum::GENERATOR_SH);
+        for (idx, entry) in table.iter().enumerate() {
+            let mut residue = u64::pack([(idx >> 5) as u8, idx as u8 & 0x1f].iter().copied());
+            for _ in 0..DescriptorChecksum::CHECKSUM_LENGTH {
+                input_fe(&mut residue, 8, &DescriptorChecksum::GENERATOR_SH, Fe32::Q);
+            }
+            assert_eq!(*entry, residue);
+        }
+
+        // The table-driven engine computes the same residues as the bitwise reduction, for both
+        // even and odd numbers of characters, whether they are input one or many at a time.
+        for len in 0..40 {
+            let fes = (0..len).map(|i| Fe32((i * 13 + len * 7) as u8 % 32));
+
+            let mut bitwise = u32::ONE;
+            let mut single = Engine::<Bech32>::new();
+            let mut many = Engine::<Bech32>::new();
+            for fe in fes.clone() {
+                input_fe(&mut bitwise, Bech32::CHECKSUM_LENGTH, &Bech32::GENERATOR_SH, fe);
+                single.input_fe(fe);
+            }
+            many.input_fes(fes.clone());
+            assert_eq!(*single.residue(), bitwise);
+            assert_eq!(*many.residue(), bitwise);
+
+            let mut bitwise = u128::ONE;
+            let mut many = Engine::<TabledCodex32>::new();
+            for fe in fes.clone() {
+                input_fe(&mut bitwise, Codex32::CHECKSUM_LENGTH, &Codex32::GENERATOR_SH, fe);
+            }
+            many.input_fes(fes);
+            assert_eq!(*many.residue(), bitwise);
+        }
+    }
+
+    #[test]
+    fn shift_combine_replace() {
+        use crate::primitives::{Bech32, Codex32};
+
+        fn check<Ck: Checksum + Copy>() {
+            let mut data = [Fe32::Q; 300];
+            for (i, fe) in data.iter_mut().enumerate() {
+                *fe = Fe32((i * 17 % 31) as u8);
+            }
+            let mut whole = Engine::<Ck>::new();
+            whole.input_fes(data.iter().copied());
+
+            // Shifting is the same as inputting zeros, for short and long shifts.
+            for &n in &[0, 1, 7, 50, 299] {
+                let mut shifted = whole;
+                shifted.shift(n);
+                let mut zeros = whole;
+                zeros.input_fes(core::iter::repeat(Fe32::Q).take(n));
+                assert!(shifted.residue() == zeros.residue());
+            }
+
+            // Combining the engines of chunks gives the engine of the whole.
+            for &split in &[0, 1, 13, 150, 299, 300] {
+                let (head, tail) = data.split_at(split);
+                let mut engine = Engine::<Ck>::new();
+                engine.input_fes(head.iter().copied());
+                let mut tail_engine = Engine::<Ck>::new();
+                tail_engine.input_fes(tail.iter().copied());
+                engine.combine(&tail_engine, tail.len());
+                assert!(engine.residue() == whole.residue());
+            }
+
+            // Replacing a single element gives the engine of the modified data.
+            for &pos in &[0, 1, 100, 299] {
+                let mut modified = data;
+                modified[pos] = Fe32::Z;
+                let mut expected = Engine::<Ck>::new();
+                expected.input_fes(modified.iter().copied());
+                let mut engine = whole;
+                engine.replace_fe(data.len() - 1 - pos, data[pos], Fe32::Z);
+                assert!(engine.residue() == expected.residue());
+            }
+        }
+        check::<Bech32>();
+        check::<Codex32>();
+    }
+
     #[test]
     #[cfg(feature = "alloc")]
     fn bech32() {
@@ -599,6 +1156,28 @@ mod tests {
         #[cfg(feature = "std")]
         println!("{}", _s);
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn wide() {
+        // A 28-character BCH code, roots alpha^1 to alpha^14 (and their conjugates) in GF1024.
+        let generator = "qezr95j8ls8u0j3ew7e0hajjvt0v"
+            .chars()
+            .map(|c| Fe32::from_char(c).unwrap())
+            .collect::<Vec<_>>();
+        let mut target = vec![Fe32::Q; 28];
+        target[27] = Fe32::P;
+
+        let s = PrintImpl::<Fe1024>::new("Wide", &generator, &target).to_string();
+        assert!(s.contains("type MidstateRepr = PackedWide<3>; // checksum packs into 140 bits"));
+        assert!(s.contains("const CHECKSUM_LENGTH: usize = 28;"));
+        assert!(
+            s.contains("PackedWide([0x77b2fbf65262dec, 0x2d247fc0fc7ca39, 0x000000000006443]),")
+        );
+        assert!(s.contains(
+            "const TARGET_RESIDUE: PackedWide<3> = PackedWide([0x000000000000001, 0x000000000000000, 0x000000000000000]);"
+        ));
+    }
 }
 
 #[cfg(bench)]
@@ -6,13 +6,24 @@
 //! equation to identify the error values, in a BCH-encoded string.
 //!
 
-use core::convert::TryInto;
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::fmt;
 use core::marker::PhantomData;
+use core::ops::RangeInclusive;
 
+#[cfg(feature = "alloc")]
+use crate::error::write_err;
+use crate::primitives::checksum::PackedFe32;
+#[cfg(feature = "alloc")]
+use crate::primitives::decode::{CharError, UncheckedHrpstringError};
 use crate::primitives::decode::{
     CheckedHrpstringError, ChecksumError, InvalidResidueError, SegwitHrpstringError,
 };
-use crate::primitives::{Field as _, FieldVec, LfsrIter, Polynomial};
+#[cfg(feature = "alloc")]
+use crate::primitives::hrp::Hrp;
+use crate::primitives::{ExtensionField, FieldVec, LfsrIter, Polynomial};
 #[cfg(feature = "alloc")]
 use crate::DecodeError;
 use crate::{Checksum, Fe32};
@@ -24,10 +35,12 @@ use crate::{Checksum, Fe32};
 /// When the feature is disabled, it represents a length beyond which this library
 /// does not support error correction.
 ///
-/// If you need this value to be increased, please file an issue describing your
-/// usecase. Bear in mind that an increased value will increase memory usage for
-/// all users, and the focus of this library is the Bitcoin ecosystem, so we may
-/// not be able to accept your request.
+/// This is the capacity of the correctors returned by [`CorrectableError::correction_context`].
+/// To correct longer checksums without an allocator, choose a larger capacity with
+/// [`UncheckedHrpstring::correction_context`], which does not increase memory usage for
+/// anybody else.
+///
+/// [`UncheckedHrpstring::correction_context`]: crate::primitives::decode::UncheckedHrpstring::correction_context
 // This constant is also used when comparing bech32 residues against the
 // bech32/bech32m targets, which should work with no-alloc. Therefore this
 // constant must be > 6 (the length of the bech32(m) checksum).
@@ -38,11 +51,7 @@ use crate::{Checksum, Fe32};
 // value and the descriptor checksum. To also get codex32 it should be >13,
 // and for "long codex32" >15 ... but consider that no-alloc contexts are
 // likely to be underpowered and will struggle to do correction on these
-// big codes anyway.
-//
-// Perhaps we will want to add a feature gate, off by default, that boosts
-// this to 16, or maybe even higher. But we will wait for implementors who
-// complain.
+// big codes anyway. Users who want them can pick their own capacity.
 pub const NO_ALLOC_MAX_LENGTH: usize = 7;
 
 /// Trait describing an error for which an error correction algorithm is applicable.
@@ -69,8 +78,12 @@ pub trait CorrectableError {
     /// feature is disabled and the checksum is too large. See the documentation
     /// for [`NO_ALLOC_MAX_LENGTH`] for more information.
     ///
-    /// This is the function that users should call.
-    fn correction_context<Ck: Checksum>(&self) -> Option<Corrector<Ck>> {
+    /// This is the function that users should call. To correct larger checksums
+    /// without an allocator use [`UncheckedHrpstring::correction_context`], which
+    /// lets the caller choose the capacity of the corrector.
+    ///
+    /// [`UncheckedHrpstring::correction_context`]: crate::primitives::decode::UncheckedHrpstring::correction_context
+    fn correction_context<Ck: Checksum>(&self) -> Option<Corrector<Ck, NO_ALLOC_MAX_LENGTH>> {
         #[cfg(not(feature = "alloc"))]
         if Ck::CHECKSUM_LENGTH >= NO_ALLOC_MAX_LENGTH {
             return None;
@@ -131,39 +144,43 @@ impl CorrectableError for DecodeError {
 }
 
 /// An error-correction context.
-pub struct Corrector<Ck: Checksum> {
-    erasures: FieldVec<usize>,
-    residue: Polynomial<Fe32>,
+///
+/// Without the **alloc** feature, the corrector stores at most `N` field elements in each of
+/// its internal vectors, which must be more than the checksum length. Correctors obtained from
+/// [`CorrectableError::correction_context`] use [`NO_ALLOC_MAX_LENGTH`].
+pub struct Corrector<Ck: Checksum, const N: usize> {
+    erasures: FieldVec<usize, N>,
+    residue: Polynomial<Fe32, N>,
     phantom: PhantomData<Ck>,
 }
 
-impl<Ck: Checksum> Corrector<Ck> {
+impl<Ck: Checksum, const N: usize> Corrector<Ck, N> {
+    /// Constructs a correction context for a string whose checksum engine ended with `residue`.
+    ///
+    /// Returns `None` if the **alloc** feature is disabled and `N` is too small for the checksum.
+    pub(crate) fn from_residue(residue: Ck::MidstateRepr) -> Option<Self> {
+        #[cfg(not(feature = "alloc"))]
+        if Ck::CHECKSUM_LENGTH >= N {
+            return None;
+        }
+
+        let residue = residue ^ Ck::TARGET_RESIDUE;
+        Some(Corrector {
+            erasures: FieldVec::new(),
+            residue: (0..Ck::CHECKSUM_LENGTH).map(|i| Fe32(residue.unpack(i))).collect(),
+            phantom: PhantomData,
+        })
+    }
+
     /// A bound on the number of errors and erasures (errors with known location)
     /// can be corrected by this corrector.
     ///
     /// Returns N such that, given E errors and X erasures, corection is possible
     /// iff 2E + X <= N.
-    pub fn singleton_bound(&self) -> usize {
-        // d - 1, where d = [number of consecutive roots] + 2
-        Ck::ROOT_EXPONENTS.end() - Ck::ROOT_EXPONENTS.start() + 1
-    }
+    pub fn singleton_bound(&self) -> usize { singleton_bound(&Ck::ROOT_EXPONENTS) }
 
     /// TODO
-    pub fn add_erasures(&mut self, locs: &[usize]) {
-        for loc in locs {
-            // If the user tries to add too many erasures, just ignore them. In
-            // this case error correction is guaranteed to fail anyway, because
-            // they will have exceeded the singleton bound. (Otherwise, the
-            // singleton bound, which is always <= the checksum length, must be
-            // greater than NO_ALLOC_MAX_LENGTH. So the checksum length must be
-            // greater than NO_ALLOC_MAX_LENGTH. Then correction will still fail.)
-            #[cfg(not(feature = "alloc"))]
-            if self.erasures.len() == NO_ALLOC_MAX_LENGTH {
-                break;
-            }
-            self.erasures.push(*loc);
-        }
-    }
+    pub fn add_erasures(&mut self, locs: &[usize]) { add_erasures(&mut self.erasures, locs) }
 
     /// Returns an iterator over the errors in the string.
     ///
@@ -175,52 +192,98 @@ impl<Ck: Checksum> Corrector<Ck> {
     ///
     /// If the input string has sufficiently many errors, this unique closest correct
     /// string may not actually be the intended string.
-    pub fn bch_errors(&self) -> Option<ErrorIterator<Ck>> {
-        // 1. Compute all syndromes by evaluating the residue at each power of the generator.
-        let syndromes: Polynomial<_> = Ck::ROOT_GENERATOR
-            .powers_range(Ck::ROOT_EXPONENTS)
-            .map(|rt| self.residue.evaluate(&rt))
-            .collect();
-
-        // 1a. Compute the "Forney syndrome polynomial" which is the product of the syndrome
-        //     polynomial and the erasure locator. This "erases the erasures" so that B-M
-        //     can find only the errors.
-        let mut erasure_locator = Polynomial::with_monic_leading_term(&[]); // 1
-        for loc in &self.erasures {
-            let factor: Polynomial<_> =
-                [Ck::CorrectionField::ONE, -Ck::ROOT_GENERATOR.powi(*loc as i64)]
-                    .iter()
-                    .cloned()
-                    .collect(); // alpha^-ix - 1
-            erasure_locator = erasure_locator.mul_mod_x_d(&factor, usize::MAX);
-        }
-        let forney_syndromes = erasure_locator.convolution(&syndromes);
-
-        // 2. Use the Berlekamp-Massey algorithm to find the connection polynomial of the
-        //    LFSR that generates these syndromes. For magical reasons this will be equal
-        //    to the error locator polynomial for the syndrome.
-        let lfsr = LfsrIter::berlekamp_massey(&forney_syndromes.as_inner()[..]);
-        let conn = lfsr.coefficient_polynomial();
-
-        // 3. The connection polynomial is the error locator polynomial. Use this to get
-        //    the errors.
-        if erasure_locator.degree() + 2 * conn.degree() <= self.singleton_bound() {
-            // 3a. Compute the "errata locator" which is the product of the error locator
-            //     and the erasure locator. Note that while we used the Forney syndromes
-            //     when calling the BM algorithm, in all other cases we use the ordinary
-            //     unmodified syndromes.
-            let errata_locator = conn.mul_mod_x_d(&erasure_locator, usize::MAX);
-            Some(ErrorIterator {
-                evaluator: errata_locator.mul_mod_x_d(&syndromes, self.singleton_bound()),
-                locator_derivative: errata_locator.formal_derivative(),
-                erasures: &self.erasures[..],
-                errors: conn.find_nonzero_distinct_roots(Ck::ROOT_GENERATOR),
-                a: Ck::ROOT_GENERATOR,
-                c: *Ck::ROOT_EXPONENTS.start(),
-            })
-        } else {
-            None
+    pub fn bch_errors(&self) -> Option<ErrorIterator<'_, Ck, N>> {
+        bch_errors(&self.residue, &self.erasures, Ck::ROOT_GENERATOR, Ck::ROOT_EXPONENTS)
+            .map(|inner| ErrorIterator { inner, phantom: PhantomData })
+    }
+}
+
+/// Returns the singleton bound of a code whose generator has the roots `exponents`.
+pub(crate) fn singleton_bound(exponents: &RangeInclusive<usize>) -> usize {
+    // d - 1, where d = [number of consecutive roots] + 2
+    exponents.end() - exponents.start() + 1
+}
+
+/// Adds the erasure locations `locs` to `erasures`.
+pub(crate) fn add_erasures<const N: usize>(erasures: &mut FieldVec<usize, N>, locs: &[usize]) {
+    for loc in locs {
+        // If the user tries to add too many erasures, just ignore them. In
+        // this case error correction is guaranteed to fail anyway, because
+        // they will have exceeded the singleton bound. (Otherwise, the
+        // singleton bound, which is always <= the checksum length, must be
+        // greater than N. So the checksum length must be greater than N. Then
+        // correction will still fail.)
+        #[cfg(not(feature = "alloc"))]
+        if erasures.len() == N {
+            break;
         }
+        erasures.push(*loc);
+    }
+}
+
+/// Locates the errors in a string with the given `residue`, for the code whose generator has
+/// roots `root_generator` raised to each of `root_exponents`.
+///
+/// This is the implementation of [`Corrector::bch_errors`], parameterized by values rather than
+/// by a [`Checksum`] so that it can also be used for checksums defined at runtime.
+pub(crate) fn bch_errors<'c, F, const N: usize>(
+    residue: &Polynomial<Fe32, N>,
+    erasures: &'c FieldVec<usize, N>,
+    root_generator: F,
+    root_exponents: RangeInclusive<usize>,
+) -> Option<RawErrorIterator<'c, F, N>>
+where
+    F: ExtensionField<BaseField = Fe32>,
+{
+    let singleton_bound = singleton_bound(&root_exponents);
+    let first_exponent = *root_exponents.start();
+    if erasures.len() > singleton_bound {
+        return None;
+    }
+
+    // 1. Compute all syndromes by evaluating the residue at each power of the generator.
+    let syndromes: Polynomial<_, N> = root_generator
+        .clone()
+        .powers_range(root_exponents)
+        .map(|rt| residue.evaluate(&rt))
+        .collect();
+
+    // 1a. Compute the "Forney syndrome polynomial" which is the product of the syndrome
+    //     polynomial and the erasure locator. This "erases the erasures" so that B-M
+    //     can find only the errors.
+    let mut erasure_locator = Polynomial::with_monic_leading_term(&[]); // 1
+    for loc in erasures {
+        let factor: Polynomial<_, N> =
+            [F::ONE, -root_generator.powi(*loc as i64)].iter().cloned().collect(); // alpha^-ix - 1
+        erasure_locator = erasure_locator.mul_mod_x_d(&factor, usize::MAX);
+    }
+    let forney_syndromes = erasure_locator.convolution(&syndromes);
+
+    // 2. Use the Berlekamp-Massey algorithm to find the connection polynomial of the
+    //    LFSR that generates these syndromes. For magical reasons this will be equal
+    //    to the error locator polynomial for the syndrome.
+    let lfsr = LfsrIter::<_, N>::berlekamp_massey(&forney_syndromes.as_inner()[..]);
+    let conn = lfsr.coefficient_polynomial();
+
+    // 3. The connection polynomial is the error locator polynomial. Use this to get
+    //    the errors.
+    if erasure_locator.degree() + 2 * conn.degree() <= singleton_bound {
+        // 3a. Compute the "errata locator" which is the product of the error locator
+        //     and the erasure locator. Note that while we used the Forney syndromes
+        //     when calling the BM algorithm, in all other cases we use the ordinary
+        //     unmodified syndromes.
+        let errata_locator = conn.mul_mod_x_d(&erasure_locator, usize::MAX);
+        Some(RawErrorIterator {
+            evaluator: errata_locator.mul_mod_x_d(&syndromes, singleton_bound),
+            locator_derivative: errata_locator.formal_derivative(),
+            erasures: &erasures[..],
+            errors: conn.find_nonzero_distinct_roots(root_generator.clone()),
+            a: root_generator,
+            c: first_exponent,
+            done: false,
+        })
+    } else {
+        None
     }
 }
 
@@ -247,31 +310,57 @@ impl<Ck: Checksum> Corrector<Ck> {
 /// most checksums.) However, it is easy to construct adversarial inputs that will
 /// exhibit this behavior, so you must take it into account.
 ///
+/// Similarly, if the string has more errors than the checksum can correct, the
+/// iterator may stop early without having yielded every error. Corrected strings
+/// should always be re-validated before use.
+///
 /// Out-of-bound error locations may occur naturally in the case of a string with a
 /// corrupted HRP, because for checksumming purposes the HRP is treated as twice as
 /// many field elements as characters, plus one. If the correct HRP is known, the
-/// caller should fix this before attempting error correction. If it is unknown,
+/// caller should fix this before attempting error correction, for example using
+/// [`crate::correct_with_hrp`]. If it is unknown,
 /// the caller cannot assume anything about the intended checksum, and should not
 /// attempt error correction.
-pub struct ErrorIterator<'c, Ck: Checksum> {
-    evaluator: Polynomial<Ck::CorrectionField>,
-    locator_derivative: Polynomial<Ck::CorrectionField>,
+pub struct ErrorIterator<'c, Ck: Checksum, const N: usize> {
+    inner: RawErrorIterator<'c, Ck::CorrectionField, N>,
+    phantom: PhantomData<Ck>,
+}
+
+impl<'c, Ck: Checksum, const N: usize> Iterator for ErrorIterator<'c, Ck, N> {
+    type Item = (usize, Fe32);
+
+    fn next(&mut self) -> Option<Self::Item> { self.inner.next() }
+}
+
+/// An iterator over the errors in a string, parameterized by the correction field.
+///
+/// See [`ErrorIterator`] for the meaning of the yielded values.
+pub(crate) struct RawErrorIterator<'c, F, const N: usize> {
+    evaluator: Polynomial<F, N>,
+    locator_derivative: Polynomial<F, N>,
     erasures: &'c [usize],
-    errors: super::polynomial::RootIter<Ck::CorrectionField>,
-    a: Ck::CorrectionField,
+    errors: super::polynomial::RootIter<F, N>,
+    a: F,
     c: usize,
+    done: bool,
 }
 
-impl<'c, Ck: Checksum> Iterator for ErrorIterator<'c, Ck> {
+impl<'c, F, const N: usize> Iterator for RawErrorIterator<'c, F, N>
+where
+    F: ExtensionField<BaseField = Fe32>,
+{
     type Item = (usize, Fe32);
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
         // Compute -i, which is the location we will return to the user.
         let neg_i = if self.erasures.is_empty() {
             match self.errors.next() {
                 None => return None,
                 Some(0) => 0,
-                Some(x) => Ck::ROOT_GENERATOR.multiplicative_order() - x,
+                Some(x) => self.a.multiplicative_order() - x,
             }
         } else {
             let pop = self.erasures[0];
@@ -287,9 +376,9 @@ impl<'c, Ck: Checksum> Iterator for ErrorIterator<'c, Ck> {
         //     e_k = - -----------------------------------------
         //              (a^i)^(c - 1)) locator_derivative(a^-i)
         //
-        // where here a is `Ck::ROOT_GENERATOR`, c is the first element of the range
-        // `Ck::ROOT_EXPONENTS`, and both evalutor and locator_derivative are polynomials
-        // which are computed when constructing the ErrorIterator.
+        // where here a is the root generator, c is the first element of the range of
+        // root exponents, and both evalutor and locator_derivative are polynomials
+        // which are computed when constructing the iterator.
 
         let a_i = self.a.powi(neg_i as i64);
         let a_neg_i = a_i.clone().multiplicative_inverse();
@@ -299,16 +388,369 @@ impl<'c, Ck: Checksum> Iterator for ErrorIterator<'c, Ck> {
         let ret = -num / den;
         match ret.try_into() {
             Ok(ret) => Some((neg_i, ret)),
-            Err(_) => unreachable!("error guaranteed to  lie in base field"),
+            // Only possible when there are more errors than the code can correct.
+            Err(_) => {
+                self.done = true;
+                None
+            }
         }
     }
 }
 
+/// A character changed by error correction: its position in the string, the original character
+/// and the corrected character.
+#[cfg(feature = "alloc")]
+pub type CharCorrection = (usize, char, char);
+
+/// Applies the corrections found by `corrector` to the checksummed string `s`.
+///
+/// `s` must have been successfully parsed as an [`UncheckedHrpstring`] (or, if `erasure_marker`
+/// is given, as an [`ErasedHrpstring`]) and `corrector` must have been created from it for the
+/// `Ck` checksum. The returned string is not revalidated.
+///
+/// [`UncheckedHrpstring`]: crate::primitives::decode::UncheckedHrpstring
+/// [`ErasedHrpstring`]: crate::primitives::decode::ErasedHrpstring
+#[cfg(feature = "alloc")]
+pub(crate) fn correct_string<Ck: Checksum, const N: usize>(
+    s: &str,
+    corrector: &Corrector<Ck, N>,
+    erasure_marker: Option<char>,
+) -> Result<(String, Vec<CharCorrection>), CorrectionError> {
+    let errors = corrector.bch_errors().ok_or(CorrectionError::TooManyErrors)?;
+    apply_errors(s, errors, erasure_marker)
+}
+
+/// Applies `errors`, given as `(location, value)` pairs like those yielded by an
+/// [`ErrorIterator`], to the checksummed string `s`.
+///
+/// See [`correct_string`] for the requirements on `s`.
+#[cfg(feature = "alloc")]
+pub(crate) fn apply_errors<I: IntoIterator<Item = (usize, Fe32)>>(
+    s: &str,
+    errors: I,
+    erasure_marker: Option<char>,
+) -> Result<(String, Vec<CharCorrection>), CorrectionError> {
+    // The separator is the last '1' in the string, everything after it is the data part.
+    let data_len = s.len() - 1 - s.rfind('1').expect("parsed strings contain a separator");
+    let uppercase = s.bytes().any(|b| b.is_ascii_uppercase());
+
+    let mut corrected = s.to_owned().into_bytes();
+    let mut corrections = Vec::new();
+    for (neg_i, fe) in errors {
+        if neg_i >= data_len {
+            return Err(CorrectionError::OutOfRange(ErrorLocationError {
+                location: neg_i,
+                data_length: data_len,
+            }));
+        }
+        let pos = s.len() - 1 - neg_i;
+        let old = char::from(corrected[pos]);
+        let old_fe = if Some(old) == erasure_marker {
+            Fe32::Q
+        } else {
+            Fe32::from_char(old).expect("parsed strings contain valid characters")
+        };
+        let new = (old_fe + fe).to_char();
+        let new = if uppercase { new.to_ascii_uppercase() } else { new };
+
+        corrected[pos] = new as u8;
+        corrections.push((pos, old, new));
+    }
+    corrections.sort_unstable();
+
+    let corrected = String::from_utf8(corrected).expect("only ASCII characters were replaced");
+    Ok((corrected, corrections))
+}
+
+/// An error while correcting a checksummed string.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CorrectionError {
+    /// The string could not be parsed.
+    Parse(UncheckedHrpstringError),
+    /// The checksum is invalid in a way that error correction cannot fix.
+    Checksum(ChecksumError),
+    /// The segwit address is invalid in a way that error correction cannot fix.
+    Segwit(SegwitHrpstringError),
+    /// There are too many errors to correct.
+    TooManyErrors,
+    /// An error was located outside of the data part.
+    OutOfRange(ErrorLocationError),
+    /// The corrected string failed validation.
+    Revalidation(CheckedHrpstringError),
+    /// The corrected segwit address failed validation.
+    SegwitRevalidation(SegwitHrpstringError),
+    /// No expected HRP was given to substitute into the string.
+    NoExpectedHrp,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for CorrectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use CorrectionError::*;
+
+        match *self {
+            Parse(ref e) => write_err!(f, "parsing failed"; e),
+            Checksum(ref e) => write_err!(f, "uncorrectable checksum error"; e),
+            Segwit(ref e) => write_err!(f, "uncorrectable segwit address"; e),
+            TooManyErrors => f.write_str("too many errors to correct"),
+            OutOfRange(ref e) => write_err!(f, "invalid error location"; e),
+            Revalidation(ref e) => write_err!(f, "corrected string is invalid"; e),
+            SegwitRevalidation(ref e) => write_err!(f, "corrected segwit address is invalid"; e),
+            NoExpectedHrp => f.write_str("no expected human-readable part was given"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CorrectionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use CorrectionError::*;
+
+        match *self {
+            Parse(ref e) => Some(e),
+            Checksum(ref e) => Some(e),
+            Segwit(ref e) => Some(e),
+            OutOfRange(ref e) => Some(e),
+            Revalidation(ref e) => Some(e),
+            SegwitRevalidation(ref e) => Some(e),
+            TooManyErrors | NoExpectedHrp => None,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<UncheckedHrpstringError> for CorrectionError {
+    #[inline]
+    fn from(e: UncheckedHrpstringError) -> Self { Self::Parse(e) }
+}
+
+/// A human-readable part replaced by one of the expected HRPs during error correction.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HrpEdit {
+    /// The human-readable part of the input string.
+    pub original: String,
+    /// The expected human-readable part it was replaced with.
+    pub corrected: Hrp,
+    /// The edit distance between the two, ignoring case.
+    pub distance: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for HrpEdit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "replaced human-readable part {} with {}", self.original, self.corrected)
+    }
+}
+
+/// Substitutes the closest of the `expected` HRPs into `s` and then corrects the data part.
+///
+/// Each expected HRP is substituted in order of edit distance, starting with the HRP of `s` itself
+/// if it is expected, until `correct` succeeds. On failure, the error for the closest HRP is
+/// returned.
+#[cfg(feature = "alloc")]
+pub(crate) fn correct_hrp<T, F>(
+    s: &str,
+    expected: &[Hrp],
+    correct: F,
+) -> Result<(T, Option<HrpEdit>), CorrectionError>
+where
+    F: Fn(&str) -> Result<T, CorrectionError>,
+{
+    let sep = s.rfind('1').ok_or(CorrectionError::Parse(UncheckedHrpstringError::Char(
+        CharError::MissingSeparator,
+    )))?;
+    let (original, data) = (&s[..sep], &s[sep..]);
+    let uppercase = data.bytes().any(|b| b.is_ascii_uppercase());
+
+    let original_lower = original.chars().map(|c| c.to_ascii_lowercase()).collect::<Vec<_>>();
+    let mut ranked = expected
+        .iter()
+        .map(|hrp| (edit_distance(&original_lower, hrp.lowercase_char_iter()), hrp))
+        .collect::<Vec<_>>();
+    ranked.sort_by_key(|&(distance, _)| distance);
+
+    let mut first_err = None;
+    for (distance, hrp) in ranked {
+        let hrp_str =
+            if uppercase { hrp.to_lowercase().to_ascii_uppercase() } else { hrp.to_lowercase() };
+        let substituted = hrp_str + data;
+        match correct(&substituted) {
+            Ok(ret) => {
+                let edit = if distance == 0 {
+                    None
+                } else {
+                    Some(HrpEdit { original: original.to_owned(), corrected: *hrp, distance })
+                };
+                return Ok((ret, edit));
+            }
+            Err(e) => {
+                first_err.get_or_insert(e);
+            }
+        }
+    }
+    Err(first_err.unwrap_or(CorrectionError::NoExpectedHrp))
+}
+
+/// The Levenshtein distance between two strings, given as sequences of characters.
+#[cfg(feature = "alloc")]
+fn edit_distance<I: Iterator<Item = char>>(a: &[char], b: I) -> usize {
+    let mut row = (0..=a.len()).collect::<Vec<_>>();
+    for (j, cb) in b.enumerate() {
+        let mut diag = row[0];
+        row[0] = j + 1;
+        for (i, &ca) in a.iter().enumerate() {
+            let next = if ca == cb {
+                diag
+            } else {
+                1 + core::cmp::min(diag, core::cmp::min(row[i], row[i + 1]))
+            };
+            diag = row[i + 1];
+            row[i + 1] = next;
+        }
+    }
+    row[a.len()]
+}
+
+/// Error correction located an error outside of the data part of a string.
+///
+/// This happens if the HRP is corrupted, or if the string has too many errors.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ErrorLocationError {
+    /// The location of the error, counted backwards from the last character of the string.
+    pub location: usize,
+    /// The length of the data part (including the checksum).
+    pub data_length: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for ErrorLocationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "error location {} exceeds the data part length {}",
+            self.location, self.data_length
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErrorLocationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { None }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::primitives::decode::SegwitHrpstring;
     use crate::Bech32;
+    #[cfg(feature = "alloc")]
+    use crate::{
+        primitives::checksum::PackedWide, primitives::decode::CheckedHrpstring, ByteIterExt,
+        Fe1024, Fe32IterExt, Hrp,
+    };
+
+    /// A 28-character checksum which can correct up to 7 errors.
+    #[cfg(feature = "alloc")]
+    enum Wide {}
+
+    #[cfg(feature = "alloc")]
+    impl Checksum for Wide {
+        type MidstateRepr = PackedWide<3>;
+
+        type CorrectionField = Fe1024;
+        const ROOT_GENERATOR: Self::CorrectionField = Fe1024::new([Fe32::W, Fe32::A]);
+        const ROOT_EXPONENTS: core::ops::RangeInclusive<usize> = 1009..=1022;
+
+        const CODE_LENGTH: usize = 1023;
+        const CHECKSUM_LENGTH: usize = 28;
+        const GENERATOR_SH: [PackedWide<3>; 5] = [
+            PackedWide([0x77b2fbf65262dec, 0x2d247fc0fc7ca39, 0x000000000006443]),
+            PackedWide([0xe577e3cdadc5bd8, 0x505aeba5d1f357b, 0x000000000006c86]),
+            PackedWide([0x88ff573f5ac96b9, 0xa0b5c3cb8baeadf, 0x000000000007d0c]),
+            PackedWide([0x59ae3e7bbdda87b, 0x093b1736361f4b7, 0x000000000005e18]),
+            PackedWide([0xb30e68d673fd0df, 0x1226be696534d47, 0x000000000001d39]),
+        ];
+        const TARGET_RESIDUE: PackedWide<3> = PackedWide([1, 0, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn wide() {
+        Wide::sanity_check();
+
+        let hrp = Hrp::parse("wide").unwrap();
+        let data = [0xab; 60];
+        let s = data
+            .iter()
+            .copied()
+            .bytes_to_fes()
+            .with_checksum::<Wide>(&hrp)
+            .chars()
+            .collect::<String>();
+        let checked = CheckedHrpstring::new::<Wide>(&s).unwrap();
+        assert!(checked.byte_iter().eq(data.iter().copied()));
+
+        // Corrupt 7 characters, including some in the checksum.
+        let errors = [0, 5, 27, 28, 40, 71, 100];
+        let mut corrupted = s.clone().into_bytes();
+        for &neg_i in &errors {
+            let idx = corrupted.len() - 1 - neg_i;
+            corrupted[idx] = if corrupted[idx] == b'q' { b'p' } else { b'q' };
+        }
+        let corrupted = String::from_utf8(corrupted).unwrap();
+
+        let e = CheckedHrpstring::new::<Wide>(&corrupted).unwrap_err();
+        let ctx = e.correction_context::<Wide>().unwrap();
+        assert_eq!(ctx.singleton_bound(), 14);
+        let mut fixed = corrupted.clone().into_bytes();
+        let mut found = 0;
+        for (neg_i, fe) in ctx.bch_errors().unwrap() {
+            assert!(errors.contains(&neg_i));
+            let idx = fixed.len() - 1 - neg_i;
+            fixed[idx] = (Fe32::from_char(fixed[idx].into()).unwrap() + fe).to_char() as u8;
+            found += 1;
+        }
+        assert_eq!(found, errors.len());
+        assert_eq!(String::from_utf8(fixed).unwrap(), s);
+    }
+
+    #[test]
+    fn inline_capacity() {
+        use crate::primitives::decode::UncheckedHrpstring;
+        use crate::primitives::Codex32;
+
+        // Codex32 has a 13-character checksum, too long for the default capacity without alloc.
+        let s = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+        let mut corrupted = [0; 48];
+        corrupted.copy_from_slice(s.as_bytes());
+        for &idx in &[5, 20, 40, 47] {
+            corrupted[idx] = if corrupted[idx] == b'q' { b'p' } else { b'q' };
+        }
+        let corrupted = core::str::from_utf8(&corrupted).unwrap();
+        let unchecked = UncheckedHrpstring::new(corrupted).unwrap();
+
+        let small = unchecked.correction_context::<Codex32, NO_ALLOC_MAX_LENGTH>();
+        assert_eq!(small.is_some(), cfg!(feature = "alloc"));
+
+        let ctx = unchecked.correction_context::<Codex32, 16>().unwrap();
+        assert_eq!(ctx.singleton_bound(), 8);
+        let mut fixed = [0; 48];
+        fixed.copy_from_slice(corrupted.as_bytes());
+        for (neg_i, fe) in ctx.bch_errors().unwrap() {
+            let idx = fixed.len() - 1 - neg_i;
+            fixed[idx] = (Fe32::from_char(fixed[idx].into()).unwrap() + fe).to_char() as u8;
+        }
+        assert_eq!(core::str::from_utf8(&fixed).unwrap(), s);
+
+        // A valid string has nothing to correct.
+        assert!(UncheckedHrpstring::new(s).unwrap().correction_context::<Codex32, 16>().is_none());
+    }
 
     #[test]
     fn bech32() {
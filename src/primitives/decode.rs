@@ -73,10 +73,15 @@
 //! [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>
 //! [BIP-350]: <https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki>
 
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::string::String;
 use core::{fmt, iter, slice, str};
 
 use crate::error::write_err;
-use crate::primitives::checksum::{self, Checksum, PackedFe32};
+use crate::primitives::checksum::{self, Checksum, PackedFe32, PreparedHrp};
+use crate::primitives::correction::{Corrector, NO_ALLOC_MAX_LENGTH};
+#[cfg(feature = "alloc")]
+use crate::primitives::dyn_checksum::DynChecksum;
 use crate::primitives::gf32::Fe32;
 use crate::primitives::hrp::{self, Hrp};
 use crate::primitives::iter::{Fe32IterExt, FesToBytes};
@@ -135,7 +140,7 @@ impl<'s> UncheckedHrpstring<'s> {
 
         let ret = UncheckedHrpstring {
             hrp: Hrp::parse(hrp)?,
-            data_part_ascii: rest[1..].as_bytes(), // Skip the separator.
+            data_part_ascii: &rest.as_bytes()[1..], // Skip the separator.
             hrpstring_length: s.len(),
         };
 
@@ -237,6 +242,17 @@ impl<'s> UncheckedHrpstring<'s> {
         Ok(self.remove_checksum::<Ck>())
     }
 
+    /// Validates that data has a valid checksum for the runtime-defined checksum `ck` and returns
+    /// a [`CheckedHrpstring`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn validate_and_remove_dyn_checksum(
+        self,
+        ck: &DynChecksum,
+    ) -> Result<CheckedHrpstring<'s>, ChecksumError> {
+        self.detect_checksum(slice::from_ref(ck.candidate())).map(|(_, checked)| checked)
+    }
+
     /// Validates that data has a valid checksum for the `Ck` algorithm (this may mean an empty
     /// checksum if `NoChecksum` is used).
     ///
@@ -252,6 +268,26 @@ impl<'s> UncheckedHrpstring<'s> {
     /// checksum if `NoChecksum` is used).
     #[inline]
     pub fn validate_checksum<Ck: Checksum>(&self) -> Result<(), ChecksumError> {
+        self.validate_checksum_from::<Ck>(None)
+    }
+
+    /// Validates that data has a valid checksum for the `Ck` algorithm, starting from the cached
+    /// checksum midstate of `prepared` if it is for the same HRP as this string.
+    ///
+    /// This gives the same result as [`Self::validate_checksum`].
+    #[inline]
+    pub fn validate_checksum_with_prepared_hrp<Ck: Checksum>(
+        &self,
+        prepared: &PreparedHrp<Ck>,
+    ) -> Result<(), ChecksumError> {
+        self.validate_checksum_from(Some(prepared))
+    }
+
+    /// Validates the checksum, using the HRP midstate of `prepared` if it is for our HRP.
+    fn validate_checksum_from<Ck: Checksum>(
+        &self,
+        prepared: Option<&PreparedHrp<Ck>>,
+    ) -> Result<(), ChecksumError> {
         use ChecksumError::*;
 
         if self.hrpstring_length > Ck::CODE_LENGTH {
@@ -270,15 +306,7 @@ impl<'s> UncheckedHrpstring<'s> {
             return Err(InvalidLength);
         }
 
-        let mut checksum_eng = checksum::Engine::<Ck>::new();
-        checksum_eng.input_hrp(self.hrp());
-
-        // Unwrap ok since we checked all characters in our constructor.
-        for fe in self.data_part_ascii.iter().map(|&b| Fe32::from_char_unchecked(b)) {
-            checksum_eng.input_fe(fe);
-        }
-
-        let residue = *checksum_eng.residue();
+        let residue = self.residue_from(PreparedHrp::engine_for(prepared, self.hrp));
         if residue != Ck::TARGET_RESIDUE {
             return Err(InvalidResidue(InvalidResidueError::new(residue, Ck::TARGET_RESIDUE)));
         }
@@ -286,6 +314,62 @@ impl<'s> UncheckedHrpstring<'s> {
         Ok(())
     }
 
+    /// Computes the checksum residue of the string for the `Ck` algorithm.
+    fn residue<Ck: Checksum>(&self) -> Ck::MidstateRepr {
+        self.residue_from(PreparedHrp::<Ck>::engine_for(None, self.hrp))
+    }
+
+    /// Computes the checksum residue of the data part, given an engine primed with our HRP.
+    fn residue_from<Ck: Checksum>(
+        &self,
+        mut checksum_eng: checksum::Engine<Ck>,
+    ) -> Ck::MidstateRepr {
+        // Unwrap ok since we checked all characters in our constructor.
+        checksum_eng.input_fes(self.data_part_ascii.iter().map(|&b| Fe32::from_char_unchecked(b)));
+        *checksum_eng.residue()
+    }
+
+    /// Returns an error correction context for the `Ck` algorithm, which stores up to `N` field
+    /// elements inline.
+    ///
+    /// This is like calling [`correction_context`] on the error returned by
+    /// [`Self::validate_checksum`], except that the capacity of the corrector can be chosen.
+    /// Without the **alloc** feature it must be greater than the checksum length, so this allows
+    /// correcting checksums longer than [`NO_ALLOC_MAX_LENGTH`] allows, such as codex32, without
+    /// increasing the size of the error types for everyone.
+    ///
+    /// Returns `None` if the checksum is valid, if the string has an invalid length for the
+    /// checksum, or if the **alloc** feature is disabled and `N` is too small.
+    ///
+    /// [`correction_context`]: crate::primitives::correction::CorrectableError::correction_context
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32::primitives::decode::UncheckedHrpstring;
+    /// use bech32::Bech32;
+    ///
+    /// // The last character should be a 'q'.
+    /// let unchecked = UncheckedHrpstring::new("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdx").unwrap();
+    /// let ctx = unchecked.correction_context::<Bech32, 7>().expect("invalid checksum");
+    /// let errors = ctx.bch_errors().expect("correctable").collect::<Vec<_>>();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].0, 0);
+    /// ```
+    pub fn correction_context<Ck: Checksum, const N: usize>(&self) -> Option<Corrector<Ck, N>> {
+        if self.hrpstring_length > Ck::CODE_LENGTH
+            || Ck::CHECKSUM_LENGTH == 0
+            || self.data_part_ascii.len() < Ck::CHECKSUM_LENGTH
+        {
+            return None;
+        }
+        let residue = self.residue::<Ck>();
+        if residue == Ck::TARGET_RESIDUE {
+            return None;
+        }
+        Corrector::from_residue(residue)
+    }
+
     /// Removes the checksum for the `Ck` algorithm and returns an [`CheckedHrpstring`].
     ///
     /// Data must be valid (ie, first call `has_valid_checksum` or `validate_checksum()`). This
@@ -305,6 +389,301 @@ impl<'s> UncheckedHrpstring<'s> {
             hrpstring_length: self.hrpstring_length,
         }
     }
+
+    /// Validates the checksum against each of the `candidates` in turn and returns the index of
+    /// the first one that matches, along with the resulting [`CheckedHrpstring`].
+    ///
+    /// The residue of the string is computed once and reused for consecutive candidates with the
+    /// same generator polynomial, so candidates that only differ in their target residue (e.g.
+    /// [`Bech32`] and [`Bech32m`]) are checked with a single pass over the string.
+    ///
+    /// If no candidate matches, returns the error for the first candidate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bech32::{Bech32, Bech32m};
+    /// use bech32::primitives::decode::{ChecksumCandidate, UncheckedHrpstring};
+    ///
+    /// let candidates = [ChecksumCandidate::new::<Bech32>(), ChecksumCandidate::new::<Bech32m>()];
+    ///
+    /// let s = "bc1pdp43hj65vxw49rts6kcw35u6r6tgzguyr03vvveeewjqpn05efzq7un9w0";
+    /// let unchecked = UncheckedHrpstring::new(s).unwrap();
+    /// let (index, _checked) = unchecked.detect_checksum(&candidates).unwrap();
+    /// assert_eq!(index, 1); // Bech32m.
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `candidates` is empty.
+    pub fn detect_checksum<R: PackedFe32>(
+        self,
+        candidates: &[ChecksumCandidate<R>],
+    ) -> Result<(usize, CheckedHrpstring<'s>), ChecksumError> {
+        assert!(!candidates.is_empty(), "at least one checksum candidate is required");
+
+        let mut first_err = None;
+        let mut cached = None;
+        for (i, candidate) in candidates.iter().enumerate() {
+            match self.validate_candidate(candidate, &mut cached) {
+                Ok(()) => {
+                    let end = self.data_part_ascii.len() - candidate.checksum_length;
+                    let checked = CheckedHrpstring {
+                        hrp: self.hrp(),
+                        ascii: &self.data_part_ascii[..end],
+                        hrpstring_length: self.hrpstring_length,
+                    };
+                    return Ok((i, checked));
+                }
+                Err(e) =>
+                    if first_err.is_none() {
+                        first_err = Some(e);
+                    },
+            }
+        }
+        Err(first_err.expect("candidates is non-empty"))
+    }
+
+    /// Validates the checksum for a single candidate, `cached` holds the last computed residue
+    /// along with the candidate that it was computed for.
+    fn validate_candidate<'c, R: PackedFe32>(
+        &self,
+        candidate: &'c ChecksumCandidate<R>,
+        cached: &mut Option<(&'c ChecksumCandidate<R>, R)>,
+    ) -> Result<(), ChecksumError> {
+        use ChecksumError::*;
+
+        if self.hrpstring_length > candidate.code_length {
+            return Err(CodeLength(CodeLengthError {
+                encoded_length: self.hrpstring_length,
+                code_length: candidate.code_length,
+            }));
+        }
+
+        if candidate.checksum_length == 0 {
+            return Ok(());
+        }
+
+        if self.data_part_ascii.len() < candidate.checksum_length {
+            return Err(InvalidLength);
+        }
+
+        let residue = match *cached {
+            Some((c, residue)) if c.has_same_generator(candidate) => residue,
+            _ => {
+                let mut residue = R::ONE;
+                let fes = self.data_part_ascii.iter().map(|&b| Fe32::from_char_unchecked(b));
+                for fe in checksum::HrpFe32Iter::new(&self.hrp).chain(fes) {
+                    checksum::input_fe(
+                        &mut residue,
+                        candidate.checksum_length,
+                        &candidate.generator_sh,
+                        fe,
+                    );
+                }
+                *cached = Some((candidate, residue));
+                residue
+            }
+        };
+        if residue != candidate.target_residue {
+            return Err(InvalidResidue(InvalidResidueError::new(
+                residue,
+                candidate.target_residue,
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// A checksum algorithm to try when detecting which checksum a string uses.
+///
+/// See [`UncheckedHrpstring::detect_checksum`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ChecksumCandidate<R> {
+    generator_sh: [R; 5],
+    target_residue: R,
+    checksum_length: usize,
+    code_length: usize,
+}
+
+impl<R: PackedFe32> ChecksumCandidate<R> {
+    /// Constructs a candidate for the `Ck` algorithm.
+    #[inline]
+    pub fn new<Ck: Checksum<MidstateRepr = R>>() -> Self {
+        ChecksumCandidate {
+            generator_sh: Ck::GENERATOR_SH,
+            target_residue: Ck::TARGET_RESIDUE,
+            checksum_length: Ck::CHECKSUM_LENGTH,
+            code_length: Ck::CODE_LENGTH,
+        }
+    }
+
+    /// Constructs a candidate from checksum parameters only known at runtime.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_parts(
+        generator_sh: [R; 5],
+        target_residue: R,
+        checksum_length: usize,
+        code_length: usize,
+    ) -> Self {
+        ChecksumCandidate { generator_sh, target_residue, checksum_length, code_length }
+    }
+
+    /// Returns the generator polynomial and its shifts.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn generator_sh(&self) -> &[R; 5] { &self.generator_sh }
+
+    /// Returns the length of the code.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn code_length(&self) -> usize { self.code_length }
+
+    /// Returns true if a residue computed for `self` is also the residue for `other`.
+    fn has_same_generator(&self, other: &Self) -> bool {
+        self.checksum_length == other.checksum_length && self.generator_sh == other.generator_sh
+    }
+}
+
+/// An HRP string whose data part may contain erasures, characters which are known to be wrong.
+///
+/// Erasures are marked in the data part with a placeholder character, e.g. `?`, for example when
+/// restoring a damaged backup on which some characters are illegible. Knowing the locations of
+/// errors doubles the number of them that a checksum can correct: a code that can correct `n`
+/// errors at unknown locations can correct `2n` erasures.
+///
+/// # Examples
+///
+/// ```
+/// use bech32::{Bech32, Fe32};
+/// use bech32::primitives::correction::NO_ALLOC_MAX_LENGTH;
+/// use bech32::primitives::decode::ErasedHrpstring;
+///
+/// // Three illegible characters, a bech32 checksum can only correct one unknown error.
+/// let erased = ErasedHrpstring::new("bc1qar0srrr7xf?vy5l643lydnw9re59gtzzwf5?d?", '?').unwrap();
+/// assert_eq!(erased.erasure_count(), 3);
+///
+/// // Erased characters are treated as `q`, so the errors are the original characters.
+/// let ctx = erased.correction_context::<Bech32, NO_ALLOC_MAX_LENGTH>().unwrap();
+/// let mut errors = ctx.bch_errors().unwrap();
+/// assert_eq!(errors.next(), Some((0, Fe32::Q)));
+/// assert_eq!(errors.next(), Some((2, Fe32::M)));
+/// assert_eq!(errors.next(), Some((27, Fe32::K)));
+/// assert_eq!(errors.next(), None);
+/// ```
+#[derive(Debug)]
+pub struct ErasedHrpstring<'s> {
+    /// The human-readable part, guaranteed to be ASCII characters and not mixed-case.
+    hrp: Hrp,
+    /// The data part, guaranteed to be valid bech32 characters or the erasure marker.
+    data_part_ascii: &'s [u8],
+    /// The erasure marker, guaranteed to be ASCII.
+    marker: u8,
+    /// The length of the parsed hrpstring.
+    hrpstring_length: usize,
+}
+
+impl<'s> ErasedHrpstring<'s> {
+    /// Parses an HRP string whose data part may contain the erasure `marker` character.
+    ///
+    /// The marker must be an ASCII character which is neither a bech32 character (in either case)
+    /// nor the separator.
+    #[inline]
+    pub fn new(s: &'s str, marker: char) -> Result<Self, UncheckedHrpstringError> {
+        if !marker.is_ascii() || marker == SEP || Fe32::from_char(marker).is_ok() {
+            return Err(CharError::InvalidErasureMarker(marker).into());
+        }
+
+        let sep_pos = check_characters_with_erasures(s, Some(marker))?;
+        let (hrp, rest) = s.split_at(sep_pos);
+
+        Ok(ErasedHrpstring {
+            hrp: Hrp::parse(hrp)?,
+            data_part_ascii: &rest.as_bytes()[1..], // Skip the separator.
+            marker: marker as u8,
+            hrpstring_length: s.len(),
+        })
+    }
+
+    /// Returns the human-readable part.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.hrp }
+
+    /// Returns the number of erased characters.
+    #[inline]
+    pub fn erasure_count(&self) -> usize { self.erasures().count() }
+
+    /// Returns an iterator over the locations of the erasures.
+    ///
+    /// Locations are counted backwards from the end of the string, as used by
+    /// [`Corrector::add_erasures`], so 0 is the last character.
+    ///
+    /// [`Corrector::add_erasures`]: crate::primitives::correction::Corrector::add_erasures
+    #[inline]
+    pub fn erasures(&self) -> impl Iterator<Item = usize> + '_ {
+        let marker = self.marker;
+        self.data_part_ascii
+            .iter()
+            .rev()
+            .enumerate()
+            .filter(move |(_, &b)| b == marker)
+            .map(|(i, _)| i)
+    }
+
+    /// Validates the checksum for the `Ck` algorithm, treating erased characters as `q`.
+    ///
+    /// If the string contains any erasures this always returns an [`InvalidResidueError`], even if
+    /// the residue happens to match, so that the error can be used for correction.
+    pub fn validate_checksum<Ck: Checksum>(&self) -> Result<(), ChecksumError> {
+        use ChecksumError::*;
+
+        if self.hrpstring_length > Ck::CODE_LENGTH {
+            return Err(CodeLength(CodeLengthError {
+                encoded_length: self.hrpstring_length,
+                code_length: Ck::CODE_LENGTH,
+            }));
+        }
+        if self.data_part_ascii.len() < Ck::CHECKSUM_LENGTH {
+            return Err(InvalidLength);
+        }
+
+        let residue = self.residue::<Ck>();
+        if residue != Ck::TARGET_RESIDUE || self.erasures().next().is_some() {
+            return Err(InvalidResidue(InvalidResidueError::new(residue, Ck::TARGET_RESIDUE)));
+        }
+        Ok(())
+    }
+
+    /// Computes the checksum residue of the string for the `Ck` algorithm, with erasures as `q`.
+    fn residue<Ck: Checksum>(&self) -> Ck::MidstateRepr {
+        let mut checksum_eng = checksum::Engine::<Ck>::new();
+        checksum_eng.input_hrp(self.hrp());
+        checksum_eng.input_fes(self.data_part_ascii.iter().map(|&b| {
+            if b == self.marker {
+                Fe32::Q
+            } else {
+                Fe32::from_char_unchecked(b)
+            }
+        }));
+        *checksum_eng.residue()
+    }
+
+    /// Returns an error correction context for the `Ck` algorithm, with the erasures added.
+    ///
+    /// The corrector stores up to `N` field elements inline, see
+    /// [`UncheckedHrpstring::correction_context`].
+    ///
+    /// Returns `None` if there is nothing to correct, or if the string cannot be corrected.
+    pub fn correction_context<Ck: Checksum, const N: usize>(&self) -> Option<Corrector<Ck, N>> {
+        match self.validate_checksum::<Ck>() {
+            Err(ChecksumError::InvalidResidue(_)) => {}
+            _ => return None,
+        }
+        let mut ctx = Corrector::from_residue(self.residue::<Ck>())?;
+        for loc in self.erasures() {
+            ctx.add_erasures(&[loc]);
+        }
+        Some(ctx)
+    }
 }
 
 /// An HRP string that has been parsed and had the checksum validated.
@@ -355,6 +734,32 @@ impl<'s> CheckedHrpstring<'s> {
         Ok(checked)
     }
 
+    /// Parses and validates an HRP string, starting the checksum computation from the cached
+    /// midstate of `prepared` if the string has the same HRP.
+    ///
+    /// This gives the same result as [`Self::new`], but avoids re-computing the checksum of the
+    /// HRP for each string when parsing many strings with the same HRP.
+    #[inline]
+    pub fn new_with_prepared_hrp<Ck: Checksum>(
+        s: &'s str,
+        prepared: &PreparedHrp<Ck>,
+    ) -> Result<Self, CheckedHrpstringError> {
+        let unchecked = UncheckedHrpstring::new(s)?;
+        unchecked.validate_checksum_with_prepared_hrp(prepared)?;
+        Ok(unchecked.remove_checksum::<Ck>())
+    }
+
+    /// Parses and validates an HRP string using the runtime-defined checksum `ck`.
+    ///
+    /// This is equivalent to `UncheckedHrpstring::new().validate_and_remove_dyn_checksum(ck)`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn new_dyn(s: &'s str, ck: &DynChecksum) -> Result<Self, CheckedHrpstringError> {
+        let unchecked = UncheckedHrpstring::new(s)?;
+        let checked = unchecked.validate_and_remove_dyn_checksum(ck)?;
+        Ok(checked)
+    }
+
     /// Returns the human-readable part.
     #[inline]
     pub fn hrp(&self) -> Hrp { self.hrp }
@@ -405,6 +810,20 @@ impl<'s> CheckedHrpstring<'s> {
         })
     }
 
+    /// Removes the first `n` characters of the data part and returns them as ASCII bytes.
+    ///
+    /// Used by formats that carry a fixed-length header in front of their payload.
+    ///
+    /// # Panics
+    ///
+    /// If the data part is shorter than `n` characters.
+    #[inline]
+    pub(crate) fn remove_prefix(&mut self, n: usize) -> &'s [u8] {
+        let (prefix, rest) = self.ascii.split_at(n);
+        self.ascii = rest;
+        prefix
+    }
+
     /// Returns the segwit witness version if there is one.
     ///
     /// Attempts to convert the first character of the data part to a witness version. If this
@@ -444,7 +863,7 @@ impl<'s> CheckedHrpstring<'s> {
     ///
     /// Converts the ASCII bytes representing field elements to the respective field elements.
     #[inline]
-    pub fn fe32_iter(&self, _: u8) -> AsciiToFe32Iter {
+    pub fn fe32_iter(&self, _: u8) -> AsciiToFe32Iter<'_> {
         AsciiToFe32Iter { iter: self.ascii.iter().copied() }
     }
 
@@ -453,7 +872,7 @@ impl<'s> CheckedHrpstring<'s> {
     /// Converts the ASCII bytes representing field elements to the respective field elements, then
     /// converts the stream of field elements to a stream of bytes.
     #[inline]
-    pub fn byte_iter(&self) -> ByteIter {
+    pub fn byte_iter(&self) -> ByteIter<'_> {
         ByteIter { iter: AsciiToFe32Iter { iter: self.ascii.iter().copied() }.fes_to_bytes() }
     }
 
@@ -565,7 +984,27 @@ impl<'s> SegwitHrpstring<'s> {
     /// NOTE: We do not enforce any restrictions on the HRP, use [`SegwitHrpstring::has_valid_hrp`]
     /// to get strict BIP conformance (also [`Hrp::is_valid_on_mainnet`] and friends).
     #[inline]
-    pub fn new(s: &'s str) -> Result<Self, SegwitHrpstringError> {
+    pub fn new(s: &'s str) -> Result<Self, SegwitHrpstringError> { Self::new_from(s, None) }
+
+    /// Parses an HRP string, treating the first data character as a witness version, starting
+    /// the checksum computation from the cached midstate of `prepared` if the string has the same
+    /// HRP.
+    ///
+    /// This gives the same result as [`Self::new`]. The HRP midstate is the same for bech32 and
+    /// bech32m, so `prepared` is used for all witness versions.
+    #[inline]
+    pub fn new_with_prepared_hrp(
+        s: &'s str,
+        prepared: &PreparedHrp<Bech32m>,
+    ) -> Result<Self, SegwitHrpstringError> {
+        Self::new_from(s, Some(prepared))
+    }
+
+    /// Parses a segwit HRP string, using the HRP midstate of `prepared` if it is for its HRP.
+    fn new_from(
+        s: &'s str,
+        prepared: Option<&PreparedHrp<Bech32m>>,
+    ) -> Result<Self, SegwitHrpstringError> {
         let len = s.len();
         if len > segwit::MAX_STRING_LENGTH {
             return Err(SegwitHrpstringError::TooLong(len));
@@ -586,8 +1025,15 @@ impl<'s> SegwitHrpstring<'s> {
         }
 
         let checked: CheckedHrpstring<'s> = match witness_version {
-            VERSION_0 => unchecked.validate_and_remove_checksum::<Bech32>()?,
-            _ => unchecked.validate_and_remove_checksum::<Bech32m>()?,
+            VERSION_0 => {
+                let prepared = prepared.map(PreparedHrp::with_checksum::<Bech32>);
+                unchecked.validate_checksum_from(prepared.as_ref())?;
+                unchecked.remove_checksum::<Bech32>()
+            }
+            _ => {
+                unchecked.validate_checksum_from(prepared)?;
+                unchecked.remove_checksum::<Bech32m>()
+            }
         };
 
         checked.validate_segwit()
@@ -663,18 +1109,253 @@ impl<'s> SegwitHrpstring<'s> {
     ///
     /// Use `self.witness_version()` to get the witness version.
     #[inline]
-    pub fn byte_iter(&self) -> ByteIter {
+    pub fn byte_iter(&self) -> ByteIter<'_> {
         ByteIter { iter: AsciiToFe32Iter { iter: self.ascii.iter().copied() }.fes_to_bytes() }
     }
 }
 
+/// An owned [`UncheckedHrpstring`].
+///
+/// Holds the parsed string so it can be stored in types that outlive the input. Use
+/// [`Self::as_unchecked`] to access the data.
+///
+/// Equality and hashing ignore case, as for [`Hrp`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct UncheckedHrpstringBuf {
+    /// The parsed string, guaranteed to be a valid [`UncheckedHrpstring`].
+    s: String,
+}
+
+#[cfg(feature = "alloc")]
+impl UncheckedHrpstringBuf {
+    /// Parses a bech32 encoded string and constructs a [`UncheckedHrpstringBuf`] object.
+    ///
+    /// Checks for valid ASCII values, does not validate the checksum.
+    #[inline]
+    pub fn new(s: &str) -> Result<Self, UncheckedHrpstringError> {
+        let _ = UncheckedHrpstring::new(s)?;
+        Ok(UncheckedHrpstringBuf { s: s.into() })
+    }
+
+    /// Returns a borrowed [`UncheckedHrpstring`] of this string.
+    #[inline]
+    pub fn as_unchecked(&self) -> UncheckedHrpstring<'_> {
+        UncheckedHrpstring::new(&self.s).expect("validated on construction")
+    }
+
+    /// Returns the human-readable part.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.as_unchecked().hrp() }
+
+    /// Returns the string as it was parsed.
+    #[inline]
+    pub fn as_str(&self) -> &str { &self.s }
+
+    /// Converts this type into the string it was parsed from.
+    #[inline]
+    pub fn into_string(self) -> String { self.s }
+}
+
+/// An owned [`CheckedHrpstring`], with a valid checksum for the `Ck` algorithm.
+///
+/// Holds the parsed string so it can be stored in types that outlive the input. Use
+/// [`Self::as_checked`] to access the data.
+///
+/// Equality and hashing ignore case, as for [`Hrp`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32::primitives::decode::CheckedHrpstringBuf;
+/// use bech32::Bech32m;
+///
+/// struct Backup {
+///     share: CheckedHrpstringBuf<Bech32m>,
+/// }
+///
+/// let s = String::from("abcd14g08d6qejxtdg4y5r3zarvary0c5xw7knqc5r8");
+/// let backup = Backup { share: s.parse().expect("valid bech32m string") };
+/// drop(s);
+///
+/// assert_eq!(backup.share.as_checked().byte_iter().len(), 20);
+/// ```
+#[cfg(feature = "alloc")]
+pub struct CheckedHrpstringBuf<Ck: Checksum> {
+    /// The parsed string, guaranteed to have a valid `Ck` checksum.
+    s: String,
+    /// The checksum algorithm.
+    marker: core::marker::PhantomData<Ck>,
+}
+
+#[cfg(feature = "alloc")]
+impl<Ck: Checksum> CheckedHrpstringBuf<Ck> {
+    /// Parses and validates an HRP string, without treating the first data character specially.
+    #[inline]
+    pub fn new(s: &str) -> Result<Self, CheckedHrpstringError> {
+        let _ = CheckedHrpstring::new::<Ck>(s)?;
+        Ok(CheckedHrpstringBuf { s: s.into(), marker: core::marker::PhantomData })
+    }
+
+    /// Returns a borrowed [`CheckedHrpstring`] of this string.
+    ///
+    /// Does not validate the checksum again.
+    #[inline]
+    pub fn as_checked(&self) -> CheckedHrpstring<'_> {
+        UncheckedHrpstring::new(&self.s).expect("validated on construction").remove_checksum::<Ck>()
+    }
+
+    /// Returns the human-readable part.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.as_checked().hrp() }
+
+    /// Returns the string as it was parsed.
+    #[inline]
+    pub fn as_str(&self) -> &str { &self.s }
+
+    /// Converts this type into the string it was parsed from.
+    #[inline]
+    pub fn into_string(self) -> String { self.s }
+}
+
+#[cfg(feature = "alloc")]
+impl<Ck: Checksum> Clone for CheckedHrpstringBuf<Ck> {
+    #[inline]
+    fn clone(&self) -> Self { CheckedHrpstringBuf { s: self.s.clone(), marker: self.marker } }
+}
+
+#[cfg(feature = "alloc")]
+impl<Ck: Checksum> fmt::Debug for CheckedHrpstringBuf<Ck> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CheckedHrpstringBuf").field("s", &self.s).finish()
+    }
+}
+
+/// An owned [`SegwitHrpstring`].
+///
+/// Holds the parsed string so it can be stored in types that outlive the input. Use
+/// [`Self::as_segwit`] to access the data.
+///
+/// Equality and hashing ignore case, as for [`Hrp`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct SegwitHrpstringBuf {
+    /// The parsed string, guaranteed to be a valid [`SegwitHrpstring`].
+    s: String,
+}
+
+#[cfg(feature = "alloc")]
+impl SegwitHrpstringBuf {
+    /// Parses an HRP string, treating the first data character as a witness version.
+    #[inline]
+    pub fn new(s: &str) -> Result<Self, SegwitHrpstringError> {
+        let _ = SegwitHrpstring::new(s)?;
+        Ok(SegwitHrpstringBuf { s: s.into() })
+    }
+
+    /// Returns a borrowed [`SegwitHrpstring`] of this string.
+    ///
+    /// Does not validate the checksum again.
+    #[inline]
+    pub fn as_segwit(&self) -> SegwitHrpstring<'_> {
+        let unchecked = UncheckedHrpstring::new(&self.s).expect("validated on construction");
+        // Bech32 and bech32m checksums have the same length.
+        let checked = unchecked.remove_checksum::<Bech32m>();
+        SegwitHrpstring {
+            hrp: checked.hrp,
+            witness_version: Fe32::from_char_unchecked(checked.ascii[0]),
+            ascii: &checked.ascii[1..],
+        }
+    }
+
+    /// Returns the human-readable part.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.as_segwit().hrp() }
+
+    /// Returns the witness version.
+    #[inline]
+    pub fn witness_version(&self) -> Fe32 { self.as_segwit().witness_version() }
+
+    /// Returns the string as it was parsed.
+    #[inline]
+    pub fn as_str(&self) -> &str { &self.s }
+
+    /// Converts this type into the string it was parsed from.
+    #[inline]
+    pub fn into_string(self) -> String { self.s }
+}
+
+/// Implements the traits common to the owned hrpstring types.
+#[cfg(feature = "alloc")]
+macro_rules! impl_hrpstring_buf {
+    ($ty:ty, $err:ty $(, $gen:ident)?) => {
+        impl$(<$gen: Checksum>)? fmt::Display for $ty {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(&self.s) }
+        }
+
+        impl$(<$gen: Checksum>)? str::FromStr for $ty {
+            type Err = $err;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> { Self::new(s) }
+        }
+
+        impl$(<$gen: Checksum>)? AsRef<str> for $ty {
+            #[inline]
+            fn as_ref(&self) -> &str { &self.s }
+        }
+
+        /// Case insensitive comparison.
+        impl$(<$gen: Checksum>)? PartialEq for $ty {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool { self.s.eq_ignore_ascii_case(&other.s) }
+        }
+
+        impl$(<$gen: Checksum>)? Eq for $ty {}
+
+        impl$(<$gen: Checksum>)? core::hash::Hash for $ty {
+            #[inline]
+            fn hash<H: core::hash::Hasher>(&self, h: &mut H) {
+                self.s.bytes().map(|b| b.to_ascii_lowercase()).for_each(|b| h.write_u8(b))
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl$(<$gen: Checksum>)? serde::Serialize for $ty {
+            #[inline]
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                s.collect_str(&crate::serde_utils::Lowercase(&self.s))
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de $(, $gen: Checksum)?> serde::Deserialize<'de> for $ty {
+            #[inline]
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_str(crate::serde_utils::FromStrVisitor::new("a bech32 string"))
+            }
+        }
+    };
+}
+#[cfg(feature = "alloc")]
+impl_hrpstring_buf!(UncheckedHrpstringBuf, UncheckedHrpstringError);
+#[cfg(feature = "alloc")]
+impl_hrpstring_buf!(CheckedHrpstringBuf<Ck>, CheckedHrpstringError, Ck);
+#[cfg(feature = "alloc")]
+impl_hrpstring_buf!(SegwitHrpstringBuf, SegwitHrpstringError);
+
 /// Checks whether a given HRP string has data part characters in the bech32 alphabet (incl.
 /// checksum characters), and that the whole string has consistent casing (hrp and data part).
 ///
 /// # Returns
 ///
 /// The byte-index into the string where the '1' separator occurs, or an error if it does not.
-fn check_characters(s: &str) -> Result<usize, CharError> {
+fn check_characters(s: &str) -> Result<usize, CharError> { check_characters_with_erasures(s, None) }
+
+/// Checks the characters of a HRP string as [`check_characters`] does, additionally allowing the
+/// `erasure` character in the data part.
+fn check_characters_with_erasures(s: &str, erasure: Option<char>) -> Result<usize, CharError> {
     use CharError::*;
 
     let mut has_upper = false;
@@ -686,7 +1367,7 @@ fn check_characters(s: &str) -> Result<usize, CharError> {
             req_bech32 = false;
             sep_pos = Some(n);
         }
-        if req_bech32 {
+        if req_bech32 && Some(ch) != erasure {
             Fe32::from_char(ch).map_err(|_| InvalidChar(ch))?;
         }
         if ch.is_ascii_uppercase() {
@@ -921,6 +1602,8 @@ pub enum CharError {
     InvalidChar(char),
     /// The whole string must be of one case.
     MixedCase,
+    /// The erasure marker is not an ASCII character, or is a bech32 character or the separator.
+    InvalidErasureMarker(char),
 }
 
 impl fmt::Display for CharError {
@@ -932,6 +1615,7 @@ impl fmt::Display for CharError {
             NothingAfterSeparator => write!(f, "invalid data - no characters after the separator"),
             InvalidChar(n) => write!(f, "invalid character (code={})", n),
             MixedCase => write!(f, "mixed-case strings not allowed"),
+            InvalidErasureMarker(c) => write!(f, "invalid erasure marker: {:?}", c),
         }
     }
 }
@@ -942,7 +1626,11 @@ impl std::error::Error for CharError {
         use CharError::*;
 
         match *self {
-            MissingSeparator | NothingAfterSeparator | InvalidChar(_) | MixedCase => None,
+            MissingSeparator
+            | NothingAfterSeparator
+            | InvalidChar(_)
+            | MixedCase
+            | InvalidErasureMarker(_) => None,
         }
     }
 }
@@ -987,8 +1675,8 @@ impl std::error::Error for ChecksumError {
 /// Residue mismatch validating the checksum. That is, "the checksum failed".
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InvalidResidueError {
-    actual: Polynomial<Fe32>,
-    target: Polynomial<Fe32>,
+    actual: Polynomial<Fe32, NO_ALLOC_MAX_LENGTH>,
+    target: Polynomial<Fe32, NO_ALLOC_MAX_LENGTH>,
 }
 
 impl fmt::Display for InvalidResidueError {
@@ -1003,7 +1691,7 @@ impl fmt::Display for InvalidResidueError {
 
 impl InvalidResidueError {
     /// Constructs a new "invalid residue" error.
-    fn new<F: PackedFe32>(residue: F, target_residue: F) -> Self {
+    pub(crate) fn new<F: PackedFe32>(residue: F, target_residue: F) -> Self {
         Self {
             actual: Polynomial::from_residue(residue),
             target: Polynomial::from_residue(target_residue),
@@ -1031,7 +1719,9 @@ impl InvalidResidueError {
     /// Not public because [`Polynomial`] is a private type, and because the
     /// subtraction will panic if this is called without checking has_data
     /// on the FieldVecs.
-    pub(super) fn residue(&self) -> Polynomial<Fe32> { self.actual.clone() - &self.target }
+    pub(super) fn residue(&self) -> Polynomial<Fe32, NO_ALLOC_MAX_LENGTH> {
+        self.actual.clone() - &self.target
+    }
 }
 
 #[cfg(feature = "std")]
@@ -1273,6 +1963,107 @@ mod tests {
         }
     }
 
+    #[test]
+    fn prepared_hrp_matches_unprepared() {
+        let prepared = PreparedHrp::<Bech32m>::new(hrp::BC);
+        let strings = [
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+            "BC1SW50QGDZ25J",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+            "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj1",
+            "23451QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZLKULZK",
+        ];
+        for s in &strings {
+            assert_eq!(
+                format!("{:?}", SegwitHrpstring::new_with_prepared_hrp(s, &prepared)),
+                format!("{:?}", SegwitHrpstring::new(s)),
+            );
+            assert_eq!(
+                format!("{:?}", CheckedHrpstring::new_with_prepared_hrp(s, &prepared)),
+                format!("{:?}", CheckedHrpstring::new::<Bech32m>(s)),
+            );
+        }
+    }
+
+    #[test]
+    fn detect_checksum_bech32_variants() {
+        let candidates = [ChecksumCandidate::new::<Bech32>(), ChecksumCandidate::new::<Bech32m>()];
+
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        let (index, checked) =
+            UncheckedHrpstring::new(s).unwrap().detect_checksum(&candidates).unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(checked.data_part_ascii_no_checksum(), b"qar0srrr7xfkvy5l643lydnw9re59gtzz");
+
+        let s = "bc1pdp43hj65vxw49rts6kcw35u6r6tgzguyr03vvveeewjqpn05efzq7un9w0";
+        let (index, _) = UncheckedHrpstring::new(s).unwrap().detect_checksum(&candidates).unwrap();
+        assert_eq!(index, 1);
+
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdp";
+        let err = UncheckedHrpstring::new(s).unwrap().detect_checksum(&candidates).unwrap_err();
+        assert!(matches!(err, ChecksumError::InvalidResidue(_)));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")] // Residue errors for long checksums need an allocator.
+    fn detect_checksum_distinct_generators() {
+        use crate::primitives::{Codex32, Codex32Long};
+
+        let candidates =
+            [ChecksumCandidate::new::<Codex32Long>(), ChecksumCandidate::new::<Codex32>()];
+        let s = "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM";
+        let (index, checked) =
+            UncheckedHrpstring::new(s).unwrap().detect_checksum(&candidates).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(checked.data_part_ascii_no_checksum(), b"2NAMEA320ZYXWVUTSRQPNMLKJHGFEDCA");
+
+        // The first candidate's error is reported when none match.
+        let candidates =
+            [ChecksumCandidate::new::<Codex32>(), ChecksumCandidate::new::<Codex32Long>()];
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        let err = UncheckedHrpstring::new(s).unwrap().detect_checksum(&candidates).unwrap_err();
+        let want = CheckedHrpstring::new::<Codex32>(s).map(|_| ()).unwrap_err();
+        assert_eq!(CheckedHrpstringError::Checksum(err), want);
+    }
+
+    #[test]
+    fn erased_hrpstring() {
+        use CharError::*;
+
+        let erased =
+            ErasedHrpstring::new("BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5*DQ", '*').unwrap();
+        assert_eq!(erased.hrp(), Hrp::parse_unchecked("bc"));
+        assert!(erased.erasures().eq([2].iter().copied()));
+        assert!(matches!(
+            erased.validate_checksum::<Bech32>(),
+            Err(ChecksumError::InvalidResidue(_))
+        ));
+        let ctx = erased.correction_context::<Bech32, NO_ALLOC_MAX_LENGTH>().unwrap();
+        let mut errors = ctx.bch_errors().unwrap();
+        assert_eq!(errors.next(), Some((2, Fe32::M)));
+        assert_eq!(errors.next(), None);
+
+        // Without erasures this is an ordinary checked string.
+        let erased =
+            ErasedHrpstring::new("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq", '?').unwrap();
+        assert_eq!(erased.erasure_count(), 0);
+        assert_eq!(erased.validate_checksum::<Bech32>(), Ok(()));
+        assert!(erased.correction_context::<Bech32, NO_ALLOC_MAX_LENGTH>().is_none());
+
+        assert_eq!(
+            ErasedHrpstring::new("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5?d*", '?').unwrap_err(),
+            UncheckedHrpstringError::Char(InvalidChar('*')),
+        );
+        for &marker in ['q', 'Q', '1', '\u{e9}'].iter() {
+            assert_eq!(
+                ErasedHrpstring::new("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq", marker)
+                    .unwrap_err(),
+                UncheckedHrpstringError::Char(InvalidErasureMarker(marker)),
+            );
+        }
+    }
+
     #[test]
     #[allow(clippy::assertions_on_constants)]
     fn constant_sanity() {
@@ -1303,4 +2094,52 @@ mod tests {
         invalid_segwit_address_4, "missing data", "bc1qwf5mdq";
         invalid_segwit_address_5, "invalid program length", "bc14r0srrr7xfkvy5l643lydnw9rewf5mdq";
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn owned_hrpstrings() {
+        let s = "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4";
+
+        let unchecked = UncheckedHrpstringBuf::new(s).expect("valid");
+        assert_eq!(unchecked.as_str(), s);
+        assert_eq!(unchecked.hrp(), crate::hrp::BC);
+        assert!(unchecked.as_unchecked().has_valid_checksum::<Bech32>());
+
+        let checked = s.parse::<CheckedHrpstringBuf<Bech32>>().expect("valid bech32");
+        let borrowed = CheckedHrpstring::new::<Bech32>(s).unwrap();
+        assert!(checked.as_checked().byte_iter().eq(borrowed.byte_iter()));
+        assert_eq!(checked.to_string(), s);
+        assert_eq!(checked, CheckedHrpstringBuf::new(&s.to_lowercase()).unwrap());
+        assert!(s.parse::<CheckedHrpstringBuf<Bech32m>>().is_err());
+
+        let segwit = SegwitHrpstringBuf::new(s).expect("valid segwit");
+        let borrowed = SegwitHrpstring::new(s).unwrap();
+        assert_eq!(segwit.witness_version(), borrowed.witness_version());
+        assert!(segwit.as_segwit().byte_iter().eq(borrowed.byte_iter()));
+        assert_eq!(segwit.into_string(), s);
+
+        assert!(SegwitHrpstringBuf::new("bc1qwf5mdq").is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "alloc", feature = "serde"))]
+    fn owned_hrpstrings_serde() {
+        use serde_test::{assert_de_tokens_error, assert_ser_tokens, assert_tokens, Token};
+
+        let s = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+        assert_tokens(&SegwitHrpstringBuf::new(s).unwrap(), &[Token::Str(s)]);
+        assert_tokens(&CheckedHrpstringBuf::<Bech32m>::new(s).unwrap(), &[Token::Str(s)]);
+        assert_tokens(&UncheckedHrpstringBuf::new(s).unwrap(), &[Token::Str(s)]);
+
+        // Uppercase strings serialize in lowercase.
+        let upper = s.to_uppercase();
+        assert_ser_tokens(&SegwitHrpstringBuf::new(&upper).unwrap(), &[Token::Str(s)]);
+        assert_ser_tokens(&CheckedHrpstringBuf::<Bech32m>::new(&upper).unwrap(), &[Token::Str(s)]);
+        assert_ser_tokens(&UncheckedHrpstringBuf::new(&upper).unwrap(), &[Token::Str(s)]);
+
+        // Deserializing validates the checksum.
+        let s = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj1";
+        let err = CheckedHrpstringBuf::<Bech32m>::new(s).unwrap_err().to_string();
+        assert_de_tokens_error::<CheckedHrpstringBuf<Bech32m>>(&[Token::Str(s)], &err);
+    }
 }
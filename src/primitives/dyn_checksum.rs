@@ -0,0 +1,591 @@
+// SPDX-License-Identifier: MIT
+
+//! Checksums defined at runtime.
+//!
+//! The [`Checksum`] trait describes a checksum at compile time, which is the most efficient way to
+//! use this library. However, sometimes the generator polynomial of a code is only known at
+//! runtime (e.g. it is read from a configuration file, or is one of many experimental codes). For
+//! these cases [`DynChecksum`] computes the same data as the [`PrintImpl`] type prints, and can be
+//! used with the checksum engine, the checked HRP string parser, the encoder and the error
+//! corrector.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::{Fe32, Fe32IterExt, Hrp};
+//! use bech32::primitives::decode::CheckedHrpstring;
+//! use bech32::primitives::dyn_checksum::DynChecksum;
+//!
+//! // The bech32 generator polynomial and target residue, as passed to `PrintImpl::new`.
+//! let generator = [Fe32::A, Fe32::K, Fe32::_5, Fe32::_4, Fe32::A, Fe32::J];
+//! let target = [Fe32::Q, Fe32::Q, Fe32::Q, Fe32::Q, Fe32::Q, Fe32::P];
+//! let ck = DynChecksum::new(&generator, &target).expect("valid BCH code");
+//!
+//! let hrp = Hrp::parse("abc").unwrap();
+//! let s = [Fe32::Q, Fe32::P]
+//!     .iter()
+//!     .copied()
+//!     .with_dyn_checksum(&ck, &hrp)
+//!     .chars()
+//!     .expect("within code length")
+//!     .collect::<String>();
+//!
+//! let checked = CheckedHrpstring::new_dyn(&s, &ck).expect("valid checksum");
+//! assert_eq!(checked.hrp(), hrp);
+//! ```
+//!
+//! [`Checksum`]: crate::Checksum
+//! [`PrintImpl`]: crate::PrintImpl
+
+use core::fmt;
+use core::ops::RangeInclusive;
+
+use crate::primitives::checksum::{self, HrpFe32Iter, PackedFe32};
+use crate::primitives::correction::{
+    self, CorrectableError, RawErrorIterator, NO_ALLOC_MAX_LENGTH,
+};
+use crate::primitives::decode::{ChecksumCandidate, CodeLengthError};
+use crate::primitives::encode::WitnessVersionIter;
+use crate::primitives::hrp::{self, Hrp};
+use crate::primitives::{ExtensionField, FieldVec, Polynomial};
+use crate::{Fe1024, Fe32, Fe32768};
+
+/// A checksum whose generator polynomial and target residue are only known at runtime.
+///
+/// Two checksums compare equal if they have the same generator polynomial and target residue.
+#[derive(Debug, Clone)]
+pub struct DynChecksum {
+    candidate: ChecksumCandidate<u128>,
+    target_residue: u128,
+    checksum_length: usize,
+    root_generator: RootGenerator,
+    root_exponents: RangeInclusive<usize>,
+}
+
+/// The generator of the consecutive roots of the generator polynomial.
+///
+/// This is [`crate::Checksum::CorrectionField`] and [`crate::Checksum::ROOT_GENERATOR`], chosen
+/// at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RootGenerator {
+    Fe1024(Fe1024),
+    Fe32768(Fe32768),
+}
+
+impl DynChecksum {
+    /// Constructs a new checksum from its generator polynomial and target residue.
+    ///
+    /// These take the same form as the arguments to [`crate::PrintImpl::new`]: both should be in
+    /// little-endian order, and the generator polynomial should not include its monic term.
+    ///
+    /// The roots of the generator polynomial are searched for in [`Fe1024`], then in
+    /// [`Fe32768`]. This is slow, so a checksum should be constructed once and then reused.
+    pub fn new(generator: &[Fe32], target: &[Fe32]) -> Result<Self, DynChecksumError> {
+        use DynChecksumError::*;
+
+        if generator.len() < 2 || generator.len() > u128::WIDTH {
+            return Err(InvalidLength(generator.len()));
+        }
+        if generator.len() != target.len() {
+            return Err(LengthMismatch { generator: generator.len(), target: target.len() });
+        }
+
+        let poly = Polynomial::with_monic_leading_term(generator);
+        let (root_generator, code_length, root_exponents) = if splits::<Fe1024>(&poly) {
+            let (gen, len, exponents) =
+                poly.try_bch_generator_primitive_element::<Fe1024>().ok_or(NotBch)?;
+            (RootGenerator::Fe1024(gen), len, exponents)
+        } else if splits::<Fe32768>(&poly) {
+            let (gen, len, exponents) =
+                poly.try_bch_generator_primitive_element::<Fe32768>().ok_or(NotBch)?;
+            (RootGenerator::Fe32768(gen), len, exponents)
+        } else {
+            return Err(NotSplit);
+        };
+
+        let mut generator_sh = [0; 5];
+        let mut gen: FieldVec<Fe32, NO_ALLOC_MAX_LENGTH> = generator.iter().copied().collect();
+        for sh in generator_sh.iter_mut() {
+            *sh = u128::pack(gen.iter().copied().map(From::from));
+            gen.iter_mut().for_each(|x| *x *= Fe32::Z);
+        }
+        let target_residue = u128::pack(target.iter().copied().map(From::from));
+        let checksum_length = generator.len();
+
+        Ok(DynChecksum {
+            candidate: ChecksumCandidate::from_parts(
+                generator_sh,
+                target_residue,
+                checksum_length,
+                code_length,
+            ),
+            target_residue,
+            checksum_length,
+            root_generator,
+            root_exponents,
+        })
+    }
+
+    /// The number of characters in the checksum.
+    #[inline]
+    pub fn checksum_length(&self) -> usize { self.checksum_length }
+
+    /// The length of the code, see [`crate::Checksum::CODE_LENGTH`].
+    #[inline]
+    pub fn code_length(&self) -> usize { self.candidate.code_length() }
+
+    /// The consecutive powers of the root generator which are roots of the generator polynomial.
+    #[inline]
+    pub fn root_exponents(&self) -> RangeInclusive<usize> { self.root_exponents.clone() }
+
+    /// Returns this checksum as a candidate for [`UncheckedHrpstring::detect_checksum`].
+    ///
+    /// [`UncheckedHrpstring::detect_checksum`]: crate::primitives::decode::UncheckedHrpstring::detect_checksum
+    #[inline]
+    pub fn candidate(&self) -> &ChecksumCandidate<u128> { &self.candidate }
+
+    /// Constructs a new checksum engine for this checksum.
+    #[inline]
+    pub fn engine(&self) -> DynEngine<'_> { DynEngine { ck: self, residue: u128::ONE } }
+
+    /// Constructs an error correction context for `err`, if it is a correctable error.
+    ///
+    /// Note that nothing ties `err` to this checksum; the caller must ensure that the error was
+    /// produced by validating against `self`.
+    pub fn correction_context<E: CorrectableError>(&self, err: &E) -> Option<DynCorrector<'_>> {
+        err.residue_error().map(|e| DynCorrector {
+            ck: self,
+            erasures: FieldVec::new(),
+            residue: e.residue(),
+        })
+    }
+}
+
+impl PartialEq for DynChecksum {
+    fn eq(&self, other: &Self) -> bool {
+        // The root generator is one of several equally valid choices, so is not compared.
+        self.candidate.generator_sh()[0] == other.candidate.generator_sh()[0]
+            && self.target_residue == other.target_residue
+            && self.checksum_length == other.checksum_length
+    }
+}
+
+impl Eq for DynChecksum {}
+
+/// Returns true if `poly` has as many distinct roots in `E` as its degree.
+fn splits<E: ExtensionField<BaseField = Fe32>>(
+    poly: &Polynomial<Fe32, NO_ALLOC_MAX_LENGTH>,
+) -> bool {
+    let n_roots = poly.find_nonzero_distinct_roots(E::GENERATOR).count();
+    n_roots + usize::from(poly.zero_is_root()) == poly.degree()
+}
+
+/// A checksum engine for a [`DynChecksum`].
+///
+/// This is the runtime equivalent of [`checksum::Engine`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynEngine<'c> {
+    ck: &'c DynChecksum,
+    residue: u128,
+}
+
+impl<'c> DynEngine<'c> {
+    /// Constructs a new checksum engine with no data input.
+    #[inline]
+    pub fn new(ck: &'c DynChecksum) -> Self { ck.engine() }
+
+    /// Feeds `hrp` into the checksum engine.
+    #[inline]
+    pub fn input_hrp(&mut self, hrp: Hrp) {
+        for fe in HrpFe32Iter::new(&hrp) {
+            self.input_fe(fe)
+        }
+    }
+
+    /// Adds a single gf32 element to the checksum engine.
+    #[inline]
+    pub fn input_fe(&mut self, e: Fe32) {
+        let generator_sh = self.ck.candidate.generator_sh();
+        checksum::input_fe(&mut self.residue, self.ck.checksum_length, generator_sh, e)
+    }
+
+    /// Inputs the target residue of the checksum.
+    #[inline]
+    pub fn input_target_residue(&mut self) {
+        let len = self.ck.checksum_length;
+        for i in 0..len {
+            self.input_fe(Fe32(self.ck.target_residue.unpack(len - i - 1)));
+        }
+    }
+
+    /// Returns for the current checksum residue.
+    #[inline]
+    pub fn residue(&self) -> &u128 { &self.residue }
+}
+
+/// An encoder for a [`DynChecksum`], see [`crate::primitives::encode::Encoder`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct DynEncoder<'a, I: Iterator<Item = Fe32>> {
+    data: I,
+    ck: &'a DynChecksum,
+    hrp: &'a Hrp,
+    witness_version: Option<Fe32>,
+}
+
+impl<'a, I: Iterator<Item = Fe32>> DynEncoder<'a, I> {
+    /// Constructs a new encoder for `data` using the checksum `ck`.
+    #[inline]
+    pub fn new(data: I, ck: &'a DynChecksum, hrp: &'a Hrp) -> Self {
+        DynEncoder { data, ck, hrp, witness_version: None }
+    }
+
+    /// Adds `witness_version` to the encoder (as first byte of encoded data).
+    #[inline]
+    pub fn with_witness_version(mut self, witness_version: Fe32) -> Self {
+        self.witness_version = Some(witness_version);
+        self
+    }
+
+    /// Returns an iterator that yields the bech32 encoded address as field ASCII characters.
+    ///
+    /// # Errors
+    ///
+    /// If the encoded string would be longer than the code length of the checksum.
+    #[inline]
+    pub fn chars(self) -> Result<DynCharIter<'a, I>, CodeLengthError>
+    where
+        I: Clone,
+    {
+        let encoded_length = self.hrp.len()
+            + 1
+            + usize::from(self.witness_version.is_some())
+            + self.data.clone().count()
+            + self.ck.checksum_length;
+        if encoded_length > self.ck.code_length() {
+            return Err(CodeLengthError { encoded_length, code_length: self.ck.code_length() });
+        }
+
+        let mut engine = self.ck.engine();
+        engine.input_hrp(*self.hrp);
+        Ok(DynCharIter {
+            hrp_iter: Some(self.hrp.lowercase_char_iter()),
+            data: WitnessVersionIter::new(self.witness_version, self.data),
+            checksum_remaining: self.ck.checksum_length,
+            engine,
+        })
+    }
+}
+
+/// Iterator adaptor which takes a stream of field elements, converts it to characters prefixed by
+/// an HRP (and separator), and suffixed by the checksum of a [`DynChecksum`].
+pub struct DynCharIter<'a, I: Iterator<Item = Fe32>> {
+    hrp_iter: Option<hrp::LowercaseCharIter<'a>>,
+    data: WitnessVersionIter<I>,
+    checksum_remaining: usize,
+    engine: DynEngine<'a>,
+}
+
+impl<'a, I: Iterator<Item = Fe32>> Iterator for DynCharIter<'a, I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        if let Some(ref mut hrp_iter) = self.hrp_iter {
+            match hrp_iter.next() {
+                Some(c) => return Some(c),
+                None => {
+                    self.hrp_iter = None;
+                    return Some('1');
+                }
+            }
+        }
+
+        match self.data.next() {
+            Some(fe) => {
+                self.engine.input_fe(fe);
+                Some(fe.to_char())
+            }
+            None =>
+                if self.checksum_remaining == 0 {
+                    None
+                } else {
+                    if self.checksum_remaining == self.engine.ck.checksum_length {
+                        self.engine.input_target_residue();
+                    }
+                    self.checksum_remaining -= 1;
+                    Some(Fe32(self.engine.residue.unpack(self.checksum_remaining)).to_char())
+                },
+        }
+    }
+}
+
+/// An error-correction context for a [`DynChecksum`].
+///
+/// This is the runtime equivalent of [`correction::Corrector`].
+pub struct DynCorrector<'c> {
+    ck: &'c DynChecksum,
+    erasures: FieldVec<usize, NO_ALLOC_MAX_LENGTH>,
+    residue: Polynomial<Fe32, NO_ALLOC_MAX_LENGTH>,
+}
+
+impl<'c> DynCorrector<'c> {
+    /// A bound on the number of errors and erasures (errors with known location)
+    /// can be corrected by this corrector.
+    ///
+    /// See [`correction::Corrector::singleton_bound`].
+    pub fn singleton_bound(&self) -> usize { correction::singleton_bound(&self.ck.root_exponents) }
+
+    /// Adds the locations of known errors, see [`correction::Corrector::add_erasures`].
+    pub fn add_erasures(&mut self, locs: &[usize]) {
+        correction::add_erasures(&mut self.erasures, locs)
+    }
+
+    /// Returns an iterator over the errors in the string.
+    ///
+    /// See [`correction::Corrector::bch_errors`].
+    pub fn bch_errors(&self) -> Option<DynErrorIterator<'_>> {
+        let exponents = self.ck.root_exponents.clone();
+        let inner = match self.ck.root_generator {
+            RootGenerator::Fe1024(gen) => ErrorIteratorInner::Fe1024(correction::bch_errors(
+                &self.residue,
+                &self.erasures,
+                gen,
+                exponents,
+            )?),
+            RootGenerator::Fe32768(gen) => ErrorIteratorInner::Fe32768(correction::bch_errors(
+                &self.residue,
+                &self.erasures,
+                gen,
+                exponents,
+            )?),
+        };
+        Some(DynErrorIterator { inner })
+    }
+}
+
+/// An iterator over the errors in a string, see [`correction::ErrorIterator`].
+pub struct DynErrorIterator<'c> {
+    inner: ErrorIteratorInner<'c>,
+}
+
+enum ErrorIteratorInner<'c> {
+    Fe1024(RawErrorIterator<'c, Fe1024, NO_ALLOC_MAX_LENGTH>),
+    Fe32768(RawErrorIterator<'c, Fe32768, NO_ALLOC_MAX_LENGTH>),
+}
+
+impl<'c> Iterator for DynErrorIterator<'c> {
+    type Item = (usize, Fe32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner {
+            ErrorIteratorInner::Fe1024(ref mut iter) => iter.next(),
+            ErrorIteratorInner::Fe32768(ref mut iter) => iter.next(),
+        }
+    }
+}
+
+/// An error while constructing a [`DynChecksum`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DynChecksumError {
+    /// The generator polynomial must have degree between 2 and 25 inclusive.
+    InvalidLength(usize),
+    /// The generator polynomial and target residue have different lengths.
+    LengthMismatch {
+        /// The length of the generator polynomial (excluding the monic term).
+        generator: usize,
+        /// The length of the target residue.
+        target: usize,
+    },
+    /// The generator polynomial does not split into distinct roots in a supported field.
+    NotSplit,
+    /// The roots of the generator polynomial do not contain a series of consecutive powers of
+    /// some element, so it does not generate a BCH code.
+    NotBch,
+}
+
+impl fmt::Display for DynChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DynChecksumError::*;
+
+        match *self {
+            InvalidLength(len) => write!(f, "invalid generator polynomial length: {}", len),
+            LengthMismatch { generator, target } => write!(
+                f,
+                "generator length {} does not match target residue length {}",
+                generator, target
+            ),
+            NotSplit => f.write_str("generator polynomial does not split in GF1024 or GF32768"),
+            NotBch => f.write_str("generator polynomial does not generate a BCH code"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DynChecksumError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use DynChecksumError::*;
+
+        match *self {
+            InvalidLength(_) | LengthMismatch { .. } | NotSplit | NotBch => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::{CheckedHrpstring, SegwitHrpstring};
+    use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+    use crate::{Bech32, Bech32m, Checksum};
+
+    fn from_checksum<Ck: Checksum<MidstateRepr = u32>>() -> DynChecksum {
+        let unpack =
+            |x: u32| (0..Ck::CHECKSUM_LENGTH).rev().map(|i| Fe32(x.unpack(i))).collect::<Vec<_>>();
+        DynChecksum::new(&unpack(Ck::GENERATOR_SH[0]), &unpack(Ck::TARGET_RESIDUE)).unwrap()
+    }
+
+    #[test]
+    fn matches_static_checksum() {
+        let ck = from_checksum::<Bech32m>();
+        assert_eq!(ck.checksum_length(), Bech32m::CHECKSUM_LENGTH);
+        assert_eq!(ck.code_length(), Bech32m::CODE_LENGTH);
+
+        let hrp = Hrp::parse("bc").unwrap();
+        let mut engine = ck.engine();
+        let mut expected = checksum::Engine::<Bech32m>::new();
+        engine.input_hrp(hrp);
+        expected.input_hrp(hrp);
+        for fe in [Fe32::Q, Fe32::P, Fe32::Z].iter() {
+            engine.input_fe(*fe);
+            expected.input_fe(*fe);
+        }
+        engine.input_target_residue();
+        expected.input_target_residue();
+        assert_eq!(*engine.residue(), u128::from(*expected.residue()));
+    }
+
+    #[test]
+    fn encode_and_parse() {
+        let ck = from_checksum::<Bech32>();
+        let hrp = Hrp::parse("bc").unwrap();
+        let data = [0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94];
+
+        let expected = data
+            .iter()
+            .copied()
+            .bytes_to_fes()
+            .with_checksum::<Bech32>(&hrp)
+            .with_witness_version(Fe32::Q)
+            .chars()
+            .collect::<String>();
+        let s = data
+            .iter()
+            .copied()
+            .bytes_to_fes()
+            .with_dyn_checksum(&ck, &hrp)
+            .with_witness_version(Fe32::Q)
+            .chars()
+            .unwrap()
+            .collect::<String>();
+        assert_eq!(s, expected);
+
+        let mut checked = CheckedHrpstring::new_dyn(&s, &ck).unwrap();
+        assert_eq!(checked.hrp(), hrp);
+        assert_eq!(checked.remove_witness_version(), Some(Fe32::Q));
+        assert!(checked.byte_iter().eq(data.iter().copied()));
+
+        assert!(CheckedHrpstring::new_dyn(&s, &from_checksum::<Bech32m>()).is_err());
+    }
+
+    #[test]
+    fn correction() {
+        let ck = from_checksum::<Bech32>();
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdx";
+
+        let e = CheckedHrpstring::new_dyn(s, &ck).unwrap_err();
+        let ctx = ck.correction_context(&e).unwrap();
+        let mut iter = ctx.bch_errors().unwrap();
+        assert_eq!(iter.next(), Some((0, Fe32::X)));
+        assert_eq!(iter.next(), None);
+
+        let e = SegwitHrpstring::new(s).unwrap_err();
+        let expected = e.correction_context::<Bech32>().unwrap();
+        let ctx = ck.correction_context(&e).unwrap();
+        assert!(ctx.bch_errors().unwrap().eq(expected.bch_errors().unwrap()));
+        assert_eq!(ctx.singleton_bound(), 3);
+    }
+
+    #[test]
+    fn invalid_checksums() {
+        assert_eq!(
+            DynChecksum::new(&[Fe32::P], &[Fe32::P]),
+            Err(DynChecksumError::InvalidLength(1))
+        );
+        assert_eq!(
+            DynChecksum::new(&[Fe32::P; 26], &[Fe32::P; 26]),
+            Err(DynChecksumError::InvalidLength(26))
+        );
+        assert_eq!(
+            DynChecksum::new(&[Fe32::P; 3], &[Fe32::P; 2]),
+            Err(DynChecksumError::LengthMismatch { generator: 3, target: 2 })
+        );
+        // x^2 + 1 = (x + 1)^2 has a repeated root.
+        assert_eq!(
+            DynChecksum::new(&[Fe32::P, Fe32::Q], &[Fe32::P, Fe32::Q]),
+            Err(DynChecksumError::NotSplit)
+        );
+        // The two roots of x^2 + zx + y are not consecutive powers of their ratio.
+        assert_eq!(
+            DynChecksum::new(&[Fe32::Y, Fe32::Z], &[Fe32::P, Fe32::Q]),
+            Err(DynChecksumError::NotBch)
+        );
+    }
+
+    #[test]
+    fn all_degree_two_generators() {
+        let mut n_ok = 0;
+        // Checking all 1024 generators is slow, so only check two of their linear terms.
+        for i in 0..64u8 {
+            let generator = [Fe32(i & 31), if i < 32 { Fe32::P } else { Fe32::Z }];
+            match DynChecksum::new(&generator, &[Fe32::P, Fe32::Q]) {
+                Ok(ck) => {
+                    assert!(ck.code_length() > ck.checksum_length());
+                    n_ok += 1;
+                }
+                Err(DynChecksumError::NotSplit) | Err(DynChecksumError::NotBch) => {}
+                Err(e) => panic!("unexpected error {:?}", e),
+            }
+        }
+        assert!(n_ok > 0);
+    }
+
+    #[test]
+    fn equality() {
+        let ck = from_checksum::<Bech32>();
+        assert_eq!(ck, from_checksum::<Bech32>());
+        assert_eq!(ck, ck.clone());
+        assert_ne!(ck, from_checksum::<Bech32m>());
+    }
+
+    #[test]
+    fn encode_too_long() {
+        let ck = from_checksum::<Bech32>();
+        let hrp = Hrp::parse("bc").unwrap();
+
+        // The HRP, separator and checksum take up 9 characters.
+        let data = vec![Fe32::Q; ck.code_length() - 8];
+        let res = data.iter().copied().with_dyn_checksum(&ck, &hrp).chars();
+        assert_eq!(
+            res.err(),
+            Some(CodeLengthError {
+                encoded_length: ck.code_length() + 1,
+                code_length: ck.code_length()
+            })
+        );
+
+        let data = vec![Fe32::Q; ck.code_length() - 9];
+        let s = data.iter().copied().with_dyn_checksum(&ck, &hrp).chars().unwrap();
+        assert_eq!(s.count(), ck.code_length());
+    }
+}
@@ -42,7 +42,7 @@
 
 use core::marker::PhantomData;
 
-use crate::primitives::checksum::HrpFe32Iter;
+use crate::primitives::checksum::{Engine, HrpFe32Iter, PreparedHrp};
 use crate::primitives::hrp::{self, Hrp};
 use crate::primitives::iter::Checksummed;
 use crate::{Checksum, Fe32};
@@ -81,6 +81,8 @@ where
     hrp: &'hrp Hrp,
     /// The witness version, if present.
     witness_version: Option<Fe32>,
+    /// The checksum engine with the HRP already input, if it was prepared in advance.
+    engine: Option<Engine<Ck>>,
     /// Checksum marker.
     marker: PhantomData<Ck>,
 }
@@ -93,7 +95,20 @@ where
     /// Constructs a new bech32 encoder.
     #[inline]
     pub fn new(data: I, hrp: &'hrp Hrp) -> Self {
-        Self { data, hrp, witness_version: None, marker: PhantomData::<Ck> }
+        Self { data, hrp, witness_version: None, engine: None, marker: PhantomData::<Ck> }
+    }
+
+    /// Constructs a new bech32 encoder which starts the checksum computation from the cached
+    /// HRP midstate of `prepared`.
+    #[inline]
+    pub fn with_prepared_hrp(data: I, prepared: &'hrp PreparedHrp<Ck>) -> Self {
+        Self {
+            data,
+            hrp: prepared.hrp(),
+            witness_version: None,
+            engine: Some(prepared.engine()),
+            marker: PhantomData::<Ck>,
+        }
     }
 
     /// Adds `witness_version` to the encoder (as first byte of encoded data).
@@ -109,7 +124,7 @@ where
     #[inline]
     pub fn chars(self) -> CharIter<'hrp, I, Ck> {
         let witver_iter = WitnessVersionIter::new(self.witness_version, self.data);
-        CharIter::new(self.hrp, witver_iter)
+        CharIter::new_engine(self.hrp, self.engine, witver_iter)
     }
 
     /// Returns an iterator that yields the bech32 encoded address as field ASCII characters, as
@@ -126,7 +141,23 @@ where
     #[inline]
     pub fn fes(self) -> Fe32Iter<'hrp, I, Ck> {
         let witver_iter = WitnessVersionIter::new(self.witness_version, self.data);
-        Fe32Iter::new(self.hrp, witver_iter)
+        Fe32Iter::new_engine(self.hrp, self.engine, witver_iter)
+    }
+}
+
+/// Creates the checksummed data iterator, inputting `hrp` unless `engine` already has it.
+fn checksummed<I, Ck>(
+    hrp: &Hrp,
+    engine: Option<Engine<Ck>>,
+    data: WitnessVersionIter<I>,
+) -> Checksummed<WitnessVersionIter<I>, Ck>
+where
+    I: Iterator<Item = Fe32>,
+    Ck: Checksum,
+{
+    match engine {
+        Some(engine) => Checksummed::new_engine(engine, data),
+        None => Checksummed::new_hrp(*hrp, data),
     }
 }
 
@@ -194,7 +225,13 @@ where
     /// Adapts the `Fe32Iter` iterator to yield characters representing the bech32 encoding.
     #[inline]
     pub fn new(hrp: &'hrp Hrp, data: WitnessVersionIter<I>) -> Self {
-        let checksummed = Checksummed::new_hrp(*hrp, data);
+        Self::new_engine(hrp, None, data)
+    }
+
+    /// Like [`Self::new`], but continues from `engine` if it has already had `hrp` input into it.
+    #[inline]
+    fn new_engine(hrp: &'hrp Hrp, engine: Option<Engine<Ck>>, data: WitnessVersionIter<I>) -> Self {
+        let checksummed = checksummed(hrp, engine, data);
         Self { hrp_iter: Some(hrp.lowercase_char_iter()), checksummed }
     }
 }
@@ -305,8 +342,14 @@ where
     /// Creates a [`Fe32Iter`] which yields all the field elements which go into the checksum algorithm.
     #[inline]
     pub fn new(hrp: &'hrp Hrp, data: WitnessVersionIter<I>) -> Self {
+        Self::new_engine(hrp, None, data)
+    }
+
+    /// Like [`Self::new`], but continues from `engine` if it has already had `hrp` input into it.
+    #[inline]
+    fn new_engine(hrp: &'hrp Hrp, engine: Option<Engine<Ck>>, data: WitnessVersionIter<I>) -> Self {
         let hrp_iter = HrpFe32Iter::new(hrp);
-        let checksummed = Checksummed::new_hrp(*hrp, data);
+        let checksummed = checksummed(hrp, engine, data);
         Self { hrp_iter: Some(hrp_iter), checksummed }
     }
 }
@@ -346,6 +389,7 @@ where
 
 #[cfg(test)]
 mod tests {
+    use crate::primitives::checksum::PreparedHrp;
     use crate::{Bech32, ByteIterExt, Fe32, Fe32IterExt, Hrp};
 
     // Tests below using this data, are based on the test vector (from BIP-173):
@@ -367,6 +411,19 @@ mod tests {
         assert!(iter.eq("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".chars()));
     }
 
+    #[test]
+    fn hrpstring_iter_prepared_hrp() {
+        let prepared = PreparedHrp::<Bech32>::new(Hrp::parse_unchecked("bc"));
+
+        let iter = DATA.iter().copied().bytes_to_fes();
+        let iter = iter.with_prepared_hrp(&prepared).with_witness_version(Fe32::Q).chars();
+        assert!(iter.eq("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".chars()));
+
+        let iter = DATA.iter().copied().bytes_to_fes();
+        let fes = iter.clone().with_prepared_hrp(&prepared).fes();
+        assert!(fes.eq(iter.with_checksum::<Bech32>(prepared.hrp()).fes()));
+    }
+
     #[test]
     #[cfg(feature = "alloc")]
     fn hrpstring_iter_collect() {
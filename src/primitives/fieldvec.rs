@@ -46,7 +46,10 @@
 //! don't want to limit our users artificially. We cannot have arbitrary
 //! sized objects without an allocator, so we split the difference by
 //! using a fixed-size array, and when the user tries to go beyond this,
-//! panicking if an allocator is unavailable.
+//! panicking if an allocator is unavailable. The size of the array is the
+//! const parameter `N`, so that code which needs to work with larger
+//! checksums without an allocator can choose a bigger array, without
+//! increasing the stack usage of everyone else.
 //!
 //! Users of this type should take care not to expose this panic to users.
 //! This shouldn't be too hard, because this type is internal to the library
@@ -62,21 +65,22 @@ use alloc::vec::Vec;
 use core::{fmt, iter, mem, ops, slice};
 
 use super::Field;
-use crate::primitives::correction::NO_ALLOC_MAX_LENGTH;
 
 /// A vector of field elements.
 ///
 /// Parameterized by the field type `F` which can be anything, but for most methods
-/// to be enabled needs `Default` and `Clone`. (Both are implied by `Field`.)
+/// to be enabled needs `Default` and `Clone`. (Both are implied by `Field`.) Up to
+/// `N` elements are stored inline; without the **alloc** feature this is the most
+/// that can be stored.
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
-pub struct FieldVec<F> {
-    inner_a: [F; NO_ALLOC_MAX_LENGTH],
+pub struct FieldVec<F, const N: usize> {
+    inner_a: [F; N],
     len: usize,
     #[cfg(feature = "alloc")]
     inner_v: Vec<F>,
 }
 
-impl<F> FieldVec<F> {
+impl<F, const N: usize> FieldVec<F, N> {
     /// Determines whether the residue is representable, given the current
     /// compilation context.
     ///
@@ -86,7 +90,7 @@ impl<F> FieldVec<F> {
     ///
     /// If you just want to panic when this is false, use `assert_has_data`.
     #[inline]
-    pub fn has_data(&self) -> bool { self.len <= NO_ALLOC_MAX_LENGTH || cfg!(feature = "alloc") }
+    pub fn has_data(&self) -> bool { self.len <= N || cfg!(feature = "alloc") }
 
     /// Panics if [`Self::has_data`] is false, with an informative panic message.
     #[inline]
@@ -95,7 +99,7 @@ impl<F> FieldVec<F> {
             self.has_data(),
             "checksums of {} characters (more than {}) require the `alloc` feature of `bech32` to be enabled",
             self.len,
-            NO_ALLOC_MAX_LENGTH,
+            N,
         );
     }
 
@@ -117,7 +121,7 @@ impl<F> FieldVec<F> {
         }
 
         #[cfg(feature = "alloc")]
-        if self.len > NO_ALLOC_MAX_LENGTH {
+        if self.len > N {
             self.inner_v.reverse();
         } else {
             self.inner_a[..self.len].reverse();
@@ -129,8 +133,8 @@ impl<F> FieldVec<F> {
     /// # Panics
     ///
     /// Panics if [`Self::has_data`] is false.
-    pub fn iter(&self) -> slice::Iter<F> {
-        if self.len > NO_ALLOC_MAX_LENGTH {
+    pub fn iter(&self) -> slice::Iter<'_, F> {
+        if self.len > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return self.inner_v[..self.len].iter();
@@ -143,8 +147,8 @@ impl<F> FieldVec<F> {
     /// # Panics
     ///
     /// Panics if [`Self::has_data`] is false.
-    pub fn iter_mut(&mut self) -> slice::IterMut<F> {
-        if self.len > NO_ALLOC_MAX_LENGTH {
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, F> {
+        if self.len > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return self.inner_v[..self.len].iter_mut();
@@ -153,7 +157,7 @@ impl<F> FieldVec<F> {
     }
 }
 
-impl<F: Field> FieldVec<F> {
+impl<F: Field, const N: usize> FieldVec<F, N> {
     /// Constructor from the powers of an element, from 0 upward.
     ///
     /// If the **alloc** feature is disabled and `n` exceeds the maximum size for
@@ -198,20 +202,20 @@ impl<F: Field> FieldVec<F> {
     /// # Panics
     ///
     /// Panics if [`Self::has_data`] is false.
-    pub fn lift<E: Field + From<F>>(&self) -> FieldVec<E> {
+    pub fn lift<E: Field + From<F>>(&self) -> FieldVec<E, N> {
         self.iter().cloned().map(E::from).collect()
     }
 }
 
-impl<F: Default> Default for FieldVec<F> {
+impl<F: Default, const N: usize> Default for FieldVec<F, N> {
     fn default() -> Self { Self::new() }
 }
 
-impl<F: Default> FieldVec<F> {
+impl<F: Default, const N: usize> FieldVec<F, N> {
     /// Constructs a new empty field vector.
     pub fn new() -> Self {
         FieldVec {
-            inner_a: Default::default(),
+            inner_a: [(); N].map(|_| F::default()),
             len: 0,
             #[cfg(feature = "alloc")]
             inner_v: Vec::new(),
@@ -230,7 +234,7 @@ impl<F: Default> FieldVec<F> {
         }
 
         #[cfg(feature = "alloc")]
-        if cap > NO_ALLOC_MAX_LENGTH {
+        if cap > N {
             let mut ret = Self::new();
             ret.inner_v = Vec::with_capacity(cap);
             ret
@@ -260,11 +264,11 @@ impl<F: Default> FieldVec<F> {
         }
 
         #[cfg(feature = "alloc")]
-        if self.len < NO_ALLOC_MAX_LENGTH + 1 {
+        if self.len < N + 1 {
             self.inner_a[self.len - 1] = item;
         } else {
-            if self.len == NO_ALLOC_MAX_LENGTH + 1 {
-                let inner_a = mem::take(&mut self.inner_a);
+            if self.len == N + 1 {
+                let inner_a = mem::replace(&mut self.inner_a, [(); N].map(|_| F::default()));
                 self.inner_v = inner_a.into();
             }
             self.inner_v.push(item);
@@ -292,7 +296,7 @@ impl<F: Default> FieldVec<F> {
         }
 
         #[cfg(feature = "alloc")]
-        if self.len > NO_ALLOC_MAX_LENGTH + 1 {
+        if self.len > N + 1 {
             self.len -= 1;
             Some(self.inner_v.remove(0))
         } else {
@@ -321,14 +325,14 @@ impl<F: Default> FieldVec<F> {
         }
 
         #[cfg(feature = "alloc")]
-        if self.len < NO_ALLOC_MAX_LENGTH {
+        if self.len < N {
             Some(mem::take(&mut self.inner_a[self.len]))
         } else {
             use core::convert::TryFrom;
 
             let ret = self.inner_v.pop();
             let inner_v = mem::take(&mut self.inner_v);
-            match <[F; NO_ALLOC_MAX_LENGTH]>::try_from(inner_v) {
+            match <[F; N]>::try_from(inner_v) {
                 Ok(arr) => self.inner_a = arr,
                 Err(vec) => self.inner_v = vec,
             }
@@ -337,7 +341,7 @@ impl<F: Default> FieldVec<F> {
     }
 }
 
-impl<F: Clone + Default> iter::FromIterator<F> for FieldVec<F> {
+impl<F: Clone + Default, const N: usize> iter::FromIterator<F> for FieldVec<F, N> {
     /// Constructor from an iterator of elements.
     ///
     /// If the **alloc** feature is disabled and `n` exceeds the maximum size for
@@ -352,9 +356,9 @@ impl<F: Clone + Default> iter::FromIterator<F> for FieldVec<F> {
         // This goofy map construction is needed because we cannot use the
         // `[F::default(); N]` syntax without adding a `Copy` bound to `F`.
         // After Rust 1.63 we will be able to use array::from_fn.
-        let mut inner_a = [(); NO_ALLOC_MAX_LENGTH].map(|_| F::default());
+        let mut inner_a = [(); N].map(|_| F::default());
         let mut len = 0;
-        for elem in iter.by_ref().take(NO_ALLOC_MAX_LENGTH) {
+        for elem in iter.by_ref().take(N) {
             inner_a[len] = elem;
             len += 1;
         }
@@ -387,7 +391,7 @@ impl<F: Clone + Default> iter::FromIterator<F> for FieldVec<F> {
     }
 }
 
-impl<F: fmt::Display> fmt::Display for FieldVec<F> {
+impl<F: fmt::Display, const N: usize> fmt::Display for FieldVec<F, N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for fe in self.iter() {
             fe.fmt(f)?;
@@ -396,24 +400,24 @@ impl<F: fmt::Display> fmt::Display for FieldVec<F> {
     }
 }
 
-impl<'a, F> IntoIterator for &'a FieldVec<F> {
+impl<'a, F, const N: usize> IntoIterator for &'a FieldVec<F, N> {
     type Item = &'a F;
     type IntoIter = slice::Iter<'a, F>;
     #[inline]
     fn into_iter(self) -> Self::IntoIter { self.iter() }
 }
 
-impl<'a, F> IntoIterator for &'a mut FieldVec<F> {
+impl<'a, F, const N: usize> IntoIterator for &'a mut FieldVec<F, N> {
     type Item = &'a mut F;
     type IntoIter = slice::IterMut<'a, F>;
     #[inline]
     fn into_iter(self) -> Self::IntoIter { self.iter_mut() }
 }
 
-impl<F> ops::Index<usize> for FieldVec<F> {
+impl<F, const N: usize> ops::Index<usize> for FieldVec<F, N> {
     type Output = F;
     fn index(&self, index: usize) -> &F {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &self.inner_v[..self.len][index];
@@ -422,10 +426,10 @@ impl<F> ops::Index<usize> for FieldVec<F> {
     }
 }
 
-impl<F> ops::Index<ops::Range<usize>> for FieldVec<F> {
+impl<F, const N: usize> ops::Index<ops::Range<usize>> for FieldVec<F, N> {
     type Output = [F];
     fn index(&self, index: ops::Range<usize>) -> &[F] {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &self.inner_v[..self.len][index];
@@ -434,10 +438,10 @@ impl<F> ops::Index<ops::Range<usize>> for FieldVec<F> {
     }
 }
 
-impl<F> ops::Index<ops::RangeFrom<usize>> for FieldVec<F> {
+impl<F, const N: usize> ops::Index<ops::RangeFrom<usize>> for FieldVec<F, N> {
     type Output = [F];
     fn index(&self, index: ops::RangeFrom<usize>) -> &[F] {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &self.inner_v[..self.len][index];
@@ -446,10 +450,10 @@ impl<F> ops::Index<ops::RangeFrom<usize>> for FieldVec<F> {
     }
 }
 
-impl<F> ops::Index<ops::RangeTo<usize>> for FieldVec<F> {
+impl<F, const N: usize> ops::Index<ops::RangeTo<usize>> for FieldVec<F, N> {
     type Output = [F];
     fn index(&self, index: ops::RangeTo<usize>) -> &[F] {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &self.inner_v[..self.len][index];
@@ -458,10 +462,10 @@ impl<F> ops::Index<ops::RangeTo<usize>> for FieldVec<F> {
     }
 }
 
-impl<F> ops::Index<ops::RangeFull> for FieldVec<F> {
+impl<F, const N: usize> ops::Index<ops::RangeFull> for FieldVec<F, N> {
     type Output = [F];
     fn index(&self, index: ops::RangeFull) -> &[F] {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &self.inner_v[..self.len][index];
@@ -470,9 +474,9 @@ impl<F> ops::Index<ops::RangeFull> for FieldVec<F> {
     }
 }
 
-impl<F> ops::IndexMut<usize> for FieldVec<F> {
+impl<F, const N: usize> ops::IndexMut<usize> for FieldVec<F, N> {
     fn index_mut(&mut self, index: usize) -> &mut F {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &mut self.inner_v[..self.len][index];
@@ -481,9 +485,9 @@ impl<F> ops::IndexMut<usize> for FieldVec<F> {
     }
 }
 
-impl<F> ops::IndexMut<ops::Range<usize>> for FieldVec<F> {
+impl<F, const N: usize> ops::IndexMut<ops::Range<usize>> for FieldVec<F, N> {
     fn index_mut(&mut self, index: ops::Range<usize>) -> &mut [F] {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &mut self.inner_v[..self.len][index];
@@ -492,9 +496,9 @@ impl<F> ops::IndexMut<ops::Range<usize>> for FieldVec<F> {
     }
 }
 
-impl<F> ops::IndexMut<ops::RangeFrom<usize>> for FieldVec<F> {
+impl<F, const N: usize> ops::IndexMut<ops::RangeFrom<usize>> for FieldVec<F, N> {
     fn index_mut(&mut self, index: ops::RangeFrom<usize>) -> &mut [F] {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &mut self.inner_v[..self.len][index];
@@ -503,9 +507,9 @@ impl<F> ops::IndexMut<ops::RangeFrom<usize>> for FieldVec<F> {
     }
 }
 
-impl<F> ops::IndexMut<ops::RangeTo<usize>> for FieldVec<F> {
+impl<F, const N: usize> ops::IndexMut<ops::RangeTo<usize>> for FieldVec<F, N> {
     fn index_mut(&mut self, index: ops::RangeTo<usize>) -> &mut [F] {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &mut self.inner_v[..self.len][index];
@@ -514,9 +518,9 @@ impl<F> ops::IndexMut<ops::RangeTo<usize>> for FieldVec<F> {
     }
 }
 
-impl<F> ops::IndexMut<ops::RangeFull> for FieldVec<F> {
+impl<F, const N: usize> ops::IndexMut<ops::RangeFull> for FieldVec<F, N> {
     fn index_mut(&mut self, index: ops::RangeFull) -> &mut [F] {
-        if self.len() > NO_ALLOC_MAX_LENGTH {
+        if self.len() > N {
             self.assert_has_data();
             #[cfg(feature = "alloc")]
             return &mut self.inner_v[..self.len][index];
@@ -528,8 +532,11 @@ impl<F> ops::IndexMut<ops::RangeFull> for FieldVec<F> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::primitives::correction::NO_ALLOC_MAX_LENGTH;
     use crate::{Fe1024, Fe32};
 
+    type FieldVec<F> = super::FieldVec<F, NO_ALLOC_MAX_LENGTH>;
+
     #[test]
     fn push_pop() {
         let mut x: FieldVec<_> = (0..NO_ALLOC_MAX_LENGTH).collect();
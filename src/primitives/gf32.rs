@@ -316,6 +316,46 @@ impl super::ExtensionField for Fe32 {
     const EXT_ELEM: Self = Fe32::P;
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Fe32 {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_char(self.to_char())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Fe32 {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        use serde::de::{self, Unexpected};
+
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Fe32;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a bech32 character")
+            }
+
+            fn visit_char<E: de::Error>(self, c: char) -> Result<Fe32, E> {
+                Fe32::from_char(c).map_err(E::custom)
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Fe32, E> {
+                let mut chars = v.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => self.visit_char(c),
+                    _ => Err(E::invalid_value(Unexpected::Str(v), &self)),
+                }
+            }
+        }
+
+        d.deserialize_char(Visitor)
+    }
+}
+
 /// A galois field error when converting from a character.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 #[non_exhaustive]
@@ -513,6 +553,20 @@ mod tests {
         assert_eq!(Fe32::default().to_u8(), 0);
         assert_eq!(Fe32::default(), Fe32::Q);
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+        assert_tokens(&Fe32::P, &[Token::Char('p')]);
+        assert_de_tokens(&Fe32::P, &[Token::Str("P")]);
+        assert_de_tokens_error::<Fe32>(&[Token::Char('b')], "invalid char in field element: b");
+        assert_de_tokens_error::<Fe32>(
+            &[Token::Str("pq")],
+            "invalid value: string \"pq\", expected a bech32 character",
+        );
+    }
 }
 
 #[cfg(kani)]
@@ -17,7 +17,7 @@ use core::iter::FusedIterator;
 use core::{slice, str};
 
 /// Maximum length of the human-readable part, as defined by BIP-173.
-const MAX_HRP_LEN: usize = 83;
+pub(crate) const MAX_HRP_LEN: usize = 83;
 
 // Defines HRP constants for the different bitcoin networks.
 // You can also access these at `crate::hrp::BC` etc.
@@ -43,15 +43,31 @@ macro_rules! define_hrp_const {
 }
 define_hrp_const! {
     /// The human-readable part used by the Bitcoin mainnet network.
-    pub const BC 2 [99, 99, 0, 0];
+    pub const BC 2 [98, 99, 0, 0];
 }
 define_hrp_const! {
     /// The human-readable part used by the Bitcoin testnet networks (testnet, signet).
-    pub const TB 2 [116, 99, 0, 0];
+    pub const TB 2 [116, 98, 0, 0];
 }
 define_hrp_const! {
     /// The human-readable part used when running a Bitcoin regtest network.
-    pub const BCRT 4 [99, 99, 114, 116];
+    pub const BCRT 4 [98, 99, 114, 116];
+}
+define_hrp_const! {
+    /// The human-readable part used by codex32 secret shares (BIP-93).
+    pub const MS 2 [109, 115, 0, 0];
+}
+define_hrp_const! {
+    /// The human-readable part used by silent payment addresses on the Bitcoin mainnet network.
+    pub const SP 2 [115, 112, 0, 0];
+}
+define_hrp_const! {
+    /// The human-readable part used by silent payment addresses on the Bitcoin testnet networks.
+    pub const TSP 3 [116, 115, 112, 0];
+}
+define_hrp_const! {
+    /// The human-readable part used by silent payment addresses on a Bitcoin regtest network.
+    pub const SPRT 4 [115, 112, 114, 116];
 }
 
 /// The human-readable part (human readable prefix before the '1' separator).
@@ -237,24 +253,24 @@ impl Hrp {
     /// If an uppercase HRP was parsed during object construction then this iterator will yield
     /// uppercase ASCII `char`s. For lowercase bytes see [`Self::lowercase_byte_iter`]
     #[inline]
-    pub fn byte_iter(&self) -> ByteIter { ByteIter { iter: self.buf[..self.size].iter() } }
+    pub fn byte_iter(&self) -> ByteIter<'_> { ByteIter { iter: self.buf[..self.size].iter() } }
 
     /// Creates a character iterator over the ASCII characters of this HRP.
     ///
     /// If an uppercase HRP was parsed during object construction then this iterator will yield
     /// uppercase ASCII `char`s. For lowercase bytes see [`Self::lowercase_char_iter`].
     #[inline]
-    pub fn char_iter(&self) -> CharIter { CharIter { iter: self.byte_iter() } }
+    pub fn char_iter(&self) -> CharIter<'_> { CharIter { iter: self.byte_iter() } }
 
     /// Creates a lowercase iterator over the byte values (ASCII characters) of this HRP.
     #[inline]
-    pub fn lowercase_byte_iter(&self) -> LowercaseByteIter {
+    pub fn lowercase_byte_iter(&self) -> LowercaseByteIter<'_> {
         LowercaseByteIter { iter: self.byte_iter() }
     }
 
     /// Creates a lowercase character iterator over the ASCII characters of this HRP.
     #[inline]
-    pub fn lowercase_char_iter(&self) -> LowercaseCharIter {
+    pub fn lowercase_char_iter(&self) -> LowercaseCharIter<'_> {
         LowercaseCharIter { iter: self.lowercase_byte_iter() }
     }
 
@@ -305,6 +321,29 @@ impl fmt::Display for Hrp {
     }
 }
 
+impl str::FromStr for Hrp {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::parse(s) }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Hrp {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(&crate::serde_utils::Lowercase(self.as_str()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Hrp {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_str(crate::serde_utils::FromStrVisitor::new("a human-readable part"))
+    }
+}
+
 /// Case insensitive comparison.
 impl Ord for Hrp {
     #[inline]
@@ -595,10 +634,14 @@ mod tests {
     #[cfg(feature = "alloc")]
     #[test]
     fn hrp_consts() {
-        use crate::primitives::hrp::{BC, BCRT, TB};
+        use crate::primitives::hrp::{BC, BCRT, MS, SP, SPRT, TB, TSP};
         assert_eq!(BC, Hrp::parse_unchecked("bc"));
         assert_eq!(TB, Hrp::parse_unchecked("tb"));
         assert_eq!(BCRT, Hrp::parse_unchecked("bcrt"));
+        assert_eq!(MS, Hrp::parse_unchecked("ms"));
+        assert_eq!(SP, Hrp::parse_unchecked("sp"));
+        assert_eq!(TSP, Hrp::parse_unchecked("tsp"));
+        assert_eq!(SPRT, Hrp::parse_unchecked("sprt"));
     }
 
     #[test]
@@ -608,6 +651,13 @@ mod tests {
         assert_eq!(hrp.as_str(), s);
     }
 
+    #[test]
+    fn hrp_consts_as_str() {
+        assert_eq!(BC.as_str(), "bc");
+        assert_eq!(TB.as_str(), "tb");
+        assert_eq!(BCRT.as_str(), "bcrt");
+    }
+
     #[test]
     fn as_bytes() {
         let s = "arbitraryhrp";
@@ -655,4 +705,19 @@ mod tests {
         assert_eq!(Hrp::parse_display(" ❤").unwrap_err(), Error::InvalidAsciiByte(b' '));
         assert_eq!(Hrp::parse_display("_❤").unwrap_err(), Error::NonAsciiChar('❤'));
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        use serde_test::{assert_de_tokens_error, assert_ser_tokens, assert_tokens, Token};
+
+        assert_tokens(&Hrp::parse("bc").unwrap(), &[Token::Str("bc")]);
+        // Uppercase input serializes in lowercase, which deserializes to an equal HRP.
+        assert_ser_tokens(&Hrp::parse("BC").unwrap(), &[Token::Str("bc")]);
+        assert_ser_tokens(&Hrp::parse("BC1-X").unwrap(), &[Token::Str("bc1-x")]);
+        assert_de_tokens_error::<Hrp>(
+            &[Token::Str("")],
+            "hrp is empty, must have at least 1 character",
+        );
+    }
 }
@@ -30,7 +30,9 @@
 //! # assert!(data.iter().copied().eq(byte_iter));
 //! ```
 
-use crate::primitives::checksum::{self, Checksum, PackedFe32};
+use crate::primitives::checksum::{self, Checksum, PackedFe32, PreparedHrp};
+#[cfg(feature = "alloc")]
+use crate::primitives::dyn_checksum::{DynChecksum, DynEncoder};
 use crate::primitives::encode::Encoder;
 use crate::primitives::gf32::Fe32;
 use crate::primitives::hrp::Hrp;
@@ -85,7 +87,24 @@ pub trait Fe32IterExt: Sized + Iterator<Item = Fe32> {
 
     /// Adapts the Fe32 iterator to encode the field elements into a bech32 address.
     #[inline]
-    fn with_checksum<Ck: Checksum>(self, hrp: &Hrp) -> Encoder<Self, Ck> { Encoder::new(self, hrp) }
+    fn with_checksum<Ck: Checksum>(self, hrp: &Hrp) -> Encoder<'_, Self, Ck> {
+        Encoder::new(self, hrp)
+    }
+
+    /// Adapts the Fe32 iterator to encode the field elements into a bech32 address, starting
+    /// the checksum computation from the cached HRP midstate of `prepared`.
+    #[inline]
+    fn with_prepared_hrp<Ck: Checksum>(self, prepared: &PreparedHrp<Ck>) -> Encoder<'_, Self, Ck> {
+        Encoder::with_prepared_hrp(self, prepared)
+    }
+
+    /// Adapts the Fe32 iterator to encode the field elements into a bech32 address using a
+    /// checksum defined at runtime.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn with_dyn_checksum<'a>(self, ck: &'a DynChecksum, hrp: &'a Hrp) -> DynEncoder<'a, Self> {
+        DynEncoder::new(self, ck, hrp)
+    }
 }
 
 impl<I> Fe32IterExt for I where I: Iterator<Item = Fe32> {}
@@ -297,6 +316,15 @@ where
         ret.checksum_engine.input_hrp(hrp);
         ret
     }
+
+    /// Creates a new checksummed iterator which continues the checksum computation of `engine`,
+    /// e.g. one which has already had an [`Hrp`] input into it.
+    #[inline]
+    pub(crate) fn new_engine(engine: checksum::Engine<Ck>, data: I) -> Checksummed<I, Ck> {
+        let mut ret = Self::new(data);
+        ret.checksum_engine = engine;
+        ret
+    }
 }
 
 impl<I, Ck> Iterator for Checksummed<I, Ck>
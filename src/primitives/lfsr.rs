@@ -20,27 +20,29 @@ use alloc::collections::VecDeque;
 use super::{Field, FieldVec, Polynomial};
 
 /// An iterator which returns the output of a linear-feedback-shift register
+///
+/// Without the **alloc** feature, the register can hold at most `N` elements.
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub struct LfsrIter<F: Field> {
+pub struct LfsrIter<F: Field, const N: usize> {
     #[cfg(feature = "alloc")]
     contents: VecDeque<F>,
     #[cfg(not(feature = "alloc"))]
-    contents: FieldVec<F>,
+    contents: FieldVec<F, N>,
     /// The coefficients are internally represented as a polynomial so that
     /// they can be returned as such for use in error correction.
     ///
     /// However, they really aren't a polynomial but rather a list of
     /// coefficients of a linear transformation. Within the algorithm
     /// they are always treated as a FieldVec, by calling `self.coeffs.as_inner`.
-    coeffs: Polynomial<F>,
+    coeffs: Polynomial<F, N>,
 }
 
-impl<F: Field> LfsrIter<F> {
+impl<F: Field, const N: usize> LfsrIter<F, N> {
     /// Accessor for the coefficients used to compute the next element.
     pub fn coefficients(&self) -> &[F] { &self.coeffs.as_inner()[1..] }
 
     /// Accessor for the coefficients used to compute the next element.
-    pub(super) fn coefficient_polynomial(&self) -> &Polynomial<F> { &self.coeffs }
+    pub(super) fn coefficient_polynomial(&self) -> &Polynomial<F, N> { &self.coeffs }
 
     /// Create a minimal LFSR iterator that generates a set of initial
     /// contents, using Berlekamp's algorithm.
@@ -48,7 +50,7 @@ impl<F: Field> LfsrIter<F> {
     /// # Panics
     ///
     /// Panics if given an empty list of initial contents.
-    pub fn berlekamp_massey(initial_contents: &[F]) -> LfsrIter<F> {
+    pub fn berlekamp_massey(initial_contents: &[F]) -> LfsrIter<F, N> {
         assert_ne!(initial_contents.len(), 0, "cannot create a LFSR with no initial contents");
 
         // Step numbers taken from Massey 1969 "Shift-register synthesis and BCH decoding"
@@ -64,8 +66,8 @@ impl<F: Field> LfsrIter<F> {
 
         // Step 1 (init)
         // `conn` and `last_conn` are `C(D)` and `B(D)` respectively, in BE order.
-        let mut conn = FieldVec::<F>::with_capacity(1 + initial_contents.len());
-        let mut old_conn = FieldVec::<F>::with_capacity(1 + initial_contents.len());
+        let mut conn = FieldVec::<F, N>::with_capacity(1 + initial_contents.len());
+        let mut old_conn = FieldVec::<F, N>::with_capacity(1 + initial_contents.len());
         let mut old_d = F::ONE; // `b` in the paper
         let mut x = 1;
 
@@ -153,7 +155,7 @@ impl<F: Field> LfsrIter<F> {
     }
 }
 
-impl<F: Field> Iterator for LfsrIter<F> {
+impl<F: Field, const N: usize> Iterator for LfsrIter<F, N> {
     type Item = F;
     fn next(&mut self) -> Option<F> {
         debug_assert_eq!(self.contents.len(), self.coefficients().len());
@@ -174,8 +176,11 @@ impl<F: Field> Iterator for LfsrIter<F> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::primitives::correction::NO_ALLOC_MAX_LENGTH;
     use crate::Fe32;
 
+    type LfsrIter<F> = super::LfsrIter<F, NO_ALLOC_MAX_LENGTH>;
+
     #[test]
     fn berlekamp_massey_constant() {
         for elem in LfsrIter::berlekamp_massey(&[Fe32::ONE, Fe32::ONE]).take(10) {
@@ -0,0 +1,350 @@
+// SPDX-License-Identifier: MIT
+
+//! List Decoding
+//!
+//! The error corrector in [`crate::primitives::correction`] finds the unique closest valid string,
+//! and only when that string is within the correction capacity of the checksum. Recovery tools
+//! may instead want every valid string within some distance of the input, even beyond the
+//! correction capacity, and let the user (or some other check, like an address book) choose.
+//!
+//! [`ListDecoder`] enumerates every such string, ranked by distance. Substitutions are only made
+//! in the data part, and all candidates share the input's HRP.
+//!
+//! The search is exhaustive, so its cost grows exponentially with the distance. It uses the
+//! linearity of the checksum residue so that each candidate costs a single XOR rather than a full
+//! re-checksum, and the work is capped by a configurable budget.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::primitives::list_decoding::ListDecoder;
+//! use bech32::Bech32;
+//!
+//! // A bech32 string with two errors, beyond what bech32 can correct.
+//! let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+//! let mistyped = s.replace("ar0s", "ax0z");
+//!
+//! let list = ListDecoder::<Bech32>::new(2).decode(&mistyped).expect("valid bech32 string");
+//! assert!(list.is_complete());
+//! assert!(list.candidates().iter().any(|c| c.as_str() == s && c.distance() == 2));
+//! ```
+
+#[cfg(all(not(feature = "std"), not(test)))]
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::primitives::checksum::{self, Checksum, Engine, PackedFe32};
+use crate::primitives::correction::{self, CharCorrection, CorrectionError};
+use crate::primitives::decode::{ChecksumError, UncheckedHrpstring};
+use crate::primitives::gf32::Fe32;
+
+/// The maximum number of coefficients used to sort the single-error residues.
+const KEY_LENGTH: usize = 12;
+
+/// Enumerates the valid strings within a given distance of an input string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListDecoder<Ck: Checksum> {
+    max_distance: usize,
+    budget: usize,
+    phantom: PhantomData<Ck>,
+}
+
+impl<Ck: Checksum> ListDecoder<Ck> {
+    /// The search budget used by [`Self::new`].
+    pub const DEFAULT_BUDGET: usize = 1_000_000;
+
+    /// Constructs a list decoder finding all valid strings within `max_distance` substitutions.
+    #[inline]
+    pub fn new(max_distance: usize) -> Self {
+        Self { max_distance, budget: Self::DEFAULT_BUDGET, phantom: PhantomData }
+    }
+
+    /// Sets the search budget, the maximum number of error patterns to try.
+    ///
+    /// If the budget runs out the search stops early, and the returned list is not complete.
+    #[inline]
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Returns the maximum distance of the candidates.
+    #[inline]
+    pub fn max_distance(&self) -> usize { self.max_distance }
+
+    /// Returns the search budget.
+    #[inline]
+    pub fn budget(&self) -> usize { self.budget }
+
+    /// Finds the valid strings within [`Self::max_distance`] of `s`, ranked by distance.
+    ///
+    /// If `s` is itself valid it is the first candidate, with distance zero.
+    ///
+    /// # Errors
+    ///
+    /// If `s` cannot be parsed, or if it has an invalid length for the checksum `Ck`.
+    pub fn decode(&self, s: &str) -> Result<CandidateList, CorrectionError> {
+        let unchecked = UncheckedHrpstring::new(s)?;
+        match unchecked.validate_checksum::<Ck>() {
+            Ok(()) | Err(ChecksumError::InvalidResidue(_)) => {}
+            Err(e) => return Err(CorrectionError::Checksum(e)),
+        }
+
+        let mut engine = Engine::<Ck>::new();
+        engine.input_hrp(unchecked.hrp());
+        engine.input_fes(unchecked.data_part_ascii().iter().map(|&b| Fe32::from_char_unchecked(b)));
+        let zero = zero::<Ck>();
+        let syndrome = *engine.residue() ^ Ck::TARGET_RESIDUE;
+
+        let mut search = Search::<Ck>::new(unchecked.data_part_ascii().len(), self.budget);
+        let mut candidates = Vec::new();
+        if syndrome == zero {
+            candidates.push(Candidate::new(s, &[])?);
+        }
+        let mut errors = Vec::with_capacity(self.max_distance);
+        for distance in 1..=self.max_distance {
+            search.search(distance, 0, syndrome, &mut errors);
+            for errors in search.found.drain(..) {
+                candidates.push(Candidate::new(s, &errors)?);
+            }
+            if !search.complete {
+                break;
+            }
+        }
+
+        Ok(CandidateList { candidates, complete: search.complete })
+    }
+}
+
+/// The state of an exhaustive search for error patterns.
+struct Search<Ck: Checksum> {
+    /// The residue of a single error, for each location (as a negative index) and non-zero value.
+    single: Vec<Ck::MidstateRepr>,
+    /// Indices into `single`, sorted by [`key`].
+    sorted: Vec<(u64, usize)>,
+    /// The number of error patterns which may still be tried.
+    budget: usize,
+    /// Whether the budget has not run out.
+    complete: bool,
+    /// The error patterns found so far.
+    found: Vec<Vec<(usize, Fe32)>>,
+}
+
+impl<Ck: Checksum> Search<Ck> {
+    fn new(data_length: usize, budget: usize) -> Self {
+        // The residue is linear in the errors, an error of value `e` at negative index `i` changes
+        // it by e * x^i mod the generator, which we compute by feeding `e` and `i` zeros into an
+        // engine starting from zero.
+        let mut single = Vec::with_capacity(31 * data_length);
+        for i in 0..data_length {
+            for e in 1..32 {
+                let residue = if i == 0 {
+                    let mut residue = zero::<Ck>();
+                    input_fe::<Ck>(&mut residue, Fe32(e));
+                    residue
+                } else {
+                    let mut residue = single[31 * (i - 1) + usize::from(e) - 1];
+                    input_fe::<Ck>(&mut residue, Fe32::Q);
+                    residue
+                };
+                single.push(residue);
+            }
+        }
+        let mut sorted: Vec<_> = single.iter().map(key::<Ck>).zip(0..).collect();
+        sorted.sort_unstable();
+
+        Search { single, sorted, budget, complete: true, found: Vec::new() }
+    }
+
+    /// Finds the patterns of `distance` errors at locations `start` or higher which change the
+    /// residue by `syndrome`, appending them to `self.found`.
+    ///
+    /// `errors` holds the errors already chosen, at locations below `start`.
+    fn search(
+        &mut self,
+        distance: usize,
+        start: usize,
+        syndrome: Ck::MidstateRepr,
+        errors: &mut Vec<(usize, Fe32)>,
+    ) {
+        if !self.complete {
+            return;
+        }
+        if self.budget == 0 {
+            self.complete = false;
+            return;
+        }
+        self.budget -= 1;
+
+        // The last error is looked up rather than enumerated.
+        if distance == 1 {
+            let k = key::<Ck>(&syndrome);
+            let from = self.sorted.partition_point(|&(key, _)| key < k);
+            for &(key, idx) in &self.sorted[from..] {
+                if key != k {
+                    break;
+                }
+                if idx / 31 >= start && self.single[idx] == syndrome {
+                    let mut found = errors.clone();
+                    found.push((idx / 31, Fe32((idx % 31 + 1) as u8)));
+                    self.found.push(found);
+                }
+            }
+            return;
+        }
+
+        let locations = self.single.len() / 31;
+        for i in start..locations {
+            for e in 0..31 {
+                errors.push((i, Fe32(e as u8 + 1)));
+                self.search(distance - 1, i + 1, syndrome ^ self.single[31 * i + e], errors);
+                errors.pop();
+                if !self.complete {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the zero residue.
+fn zero<Ck: Checksum>() -> Ck::MidstateRepr { Ck::MidstateRepr::pack(core::iter::empty()) }
+
+/// Adds a single field element to a residue which did not start at the engine's initial value.
+fn input_fe<Ck: Checksum>(residue: &mut Ck::MidstateRepr, e: Fe32) {
+    checksum::input_fe(residue, Ck::CHECKSUM_LENGTH, &Ck::GENERATOR_SH, e)
+}
+
+/// A sort key made of the low coefficients of a residue.
+fn key<Ck: Checksum>(residue: &Ck::MidstateRepr) -> u64 {
+    let len = core::cmp::min(Ck::CHECKSUM_LENGTH, KEY_LENGTH);
+    (0..len).fold(0, |acc, i| acc << 5 | u64::from(residue.unpack(i)))
+}
+
+/// A valid string found by a [`ListDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    string: String,
+    corrections: Vec<CharCorrection>,
+}
+
+impl Candidate {
+    fn new(s: &str, errors: &[(usize, Fe32)]) -> Result<Self, CorrectionError> {
+        let (string, corrections) = correction::apply_errors(s, errors.iter().copied(), None)?;
+        Ok(Candidate { string, corrections })
+    }
+
+    /// Returns the candidate string.
+    #[inline]
+    pub fn as_str(&self) -> &str { &self.string }
+
+    /// Returns the characters changed from the input string, see [`crate::correct`].
+    #[inline]
+    pub fn corrections(&self) -> &[CharCorrection] { &self.corrections }
+
+    /// Returns the number of characters changed from the input string.
+    #[inline]
+    pub fn distance(&self) -> usize { self.corrections.len() }
+
+    /// Converts the candidate into the candidate string.
+    #[inline]
+    pub fn into_string(self) -> String { self.string }
+}
+
+/// The valid strings found by a [`ListDecoder`], ranked by distance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateList {
+    candidates: Vec<Candidate>,
+    complete: bool,
+}
+
+impl CandidateList {
+    /// Returns the candidates, closest first.
+    #[inline]
+    pub fn candidates(&self) -> &[Candidate] { &self.candidates }
+
+    /// Returns whether the search finished within its budget.
+    ///
+    /// If not, the search stopped partway through some distance. Every valid string closer than
+    /// that is listed, but some at that distance or further may be missing.
+    #[inline]
+    pub fn is_complete(&self) -> bool { self.complete }
+
+    /// Converts the list into its candidates, closest first.
+    #[inline]
+    pub fn into_candidates(self) -> Vec<Candidate> { self.candidates }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::decode::CheckedHrpstring;
+    use crate::{Bech32, Bech32m};
+
+    #[test]
+    fn list_decode_bech32() {
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+
+        // A valid string is its own closest candidate.
+        let list = ListDecoder::<Bech32>::new(1).decode(s).unwrap();
+        assert!(list.is_complete());
+        assert_eq!(list.candidates().len(), 1);
+        assert_eq!(list.candidates()[0].as_str(), s);
+        assert_eq!(list.candidates()[0].distance(), 0);
+
+        // Bech32 detects up to 4 errors, so no other valid string is within 3.
+        let list = ListDecoder::<Bech32>::new(3).decode(s).unwrap();
+        assert_eq!(list.candidates().len(), 1);
+
+        // Bech32 strings are at least distance 5 apart, so a single error has a unique candidate.
+        let mistyped = s.replace("zzwf", "zzwg");
+        let list = ListDecoder::<Bech32>::new(3).decode(&mistyped).unwrap();
+        assert_eq!(list.candidates().len(), 1);
+        assert_eq!(list.candidates()[0].as_str(), s);
+        assert_eq!(list.candidates()[0].corrections(), [(37, 'g', 'f')]);
+
+        // Two errors are beyond the correction capacity, but are found by list decoding.
+        let mistyped = s.replace("ar0s", "ax0z");
+        let list = ListDecoder::<Bech32>::new(2).decode(&mistyped).unwrap();
+        assert!(list.is_complete());
+        let found = list.candidates().iter().find(|c| c.as_str() == s).unwrap();
+        assert_eq!(found.corrections(), [(5, 'x', 'r'), (7, 'z', 's')]);
+        for candidate in list.candidates() {
+            assert!(candidate.distance() <= 2);
+            assert!(CheckedHrpstring::new::<Bech32>(candidate.as_str()).is_ok());
+        }
+        // Bech32m residues are different, so the same string is not found.
+        let list = ListDecoder::<Bech32m>::new(2).decode(&mistyped).unwrap();
+        assert!(list.candidates().iter().all(|c| c.as_str() != s));
+
+        // Uppercase is preserved.
+        let upper = mistyped.to_uppercase();
+        let list = ListDecoder::<Bech32>::new(2).decode(&upper).unwrap();
+        assert!(list.candidates().iter().any(|c| c.as_str() == s.to_uppercase()));
+    }
+
+    #[test]
+    fn list_decode_budget() {
+        let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".replace("ar0s", "ax0z");
+
+        let list = ListDecoder::<Bech32>::new(2).with_budget(100).decode(&s).unwrap();
+        assert!(!list.is_complete());
+        assert!(list.candidates().iter().all(|c| c.distance() <= 1));
+
+        let list = ListDecoder::<Bech32>::new(0).with_budget(0).decode(&s).unwrap();
+        assert!(list.is_complete());
+        assert!(list.candidates().is_empty());
+    }
+
+    #[test]
+    fn list_decode_errors() {
+        assert!(matches!(
+            ListDecoder::<Bech32>::new(1).decode("bc1qar0"),
+            Err(CorrectionError::Checksum(ChecksumError::InvalidLength))
+        ));
+        assert!(matches!(
+            ListDecoder::<Bech32>::new(1).decode("bcqar0srrr"),
+            Err(CorrectionError::Parse(_))
+        ));
+    }
+}
@@ -5,6 +5,8 @@
 pub mod checksum;
 pub mod correction;
 pub mod decode;
+#[cfg(feature = "alloc")]
+pub mod dyn_checksum;
 pub mod encode;
 mod field;
 mod fieldvec;
@@ -13,8 +15,11 @@ pub mod gf32_ext;
 pub mod hrp;
 pub mod iter;
 mod lfsr;
+#[cfg(feature = "alloc")]
+pub mod list_decoding;
 mod polynomial;
 pub mod segwit;
+pub mod stream;
 
 use checksum::{Checksum, PackedNull};
 use field::impl_ops_for_fe;
@@ -23,7 +28,7 @@ use fieldvec::FieldVec;
 pub use lfsr::LfsrIter;
 use polynomial::Polynomial;
 
-use crate::{Fe1024, Fe32};
+use crate::{Fe1024, Fe32, Fe32768};
 
 /// The "null checksum" used on bech32 strings for which we want to do no checksum checking.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -41,6 +46,31 @@ pub enum Bech32 {}
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Bech32m {}
 
+/// The codex32 checksum algorithm, defined in [BIP-93].
+///
+/// Used for codex32 strings of up to 93 characters, which covers secrets of up to 46 bytes.
+///
+/// [BIP-93]: <https://github.com/bitcoin/bips/blob/master/bip-0093.mediawiki>
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Codex32 {}
+
+/// The long codex32 checksum algorithm, defined in [BIP-93].
+///
+/// Used for codex32 strings of 125 to 127 characters, which covers 512-bit secrets.
+///
+/// [BIP-93]: <https://github.com/bitcoin/bips/blob/master/bip-0093.mediawiki>
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Codex32Long {}
+
+/// The output descriptor checksum algorithm, defined in [BIP-380].
+///
+/// Descriptor characters are not bech32 characters; they must first be mapped to field elements
+/// as described in the BIP, see the [`crate::descriptor`] module.
+///
+/// [BIP-380]: <https://github.com/bitcoin/bips/blob/master/bip-0380.mediawiki>
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DescriptorChecksum {}
+
 impl Checksum for NoChecksum {
     type MidstateRepr = PackedNull;
 
@@ -56,6 +86,8 @@ impl Checksum for NoChecksum {
 
 // Bech32[m] generator coefficients, copied from Bitcoin Core src/bech32.cpp
 const GEN: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+// Lookup table for the Bech32[m] generator, letting the engine process two characters per step.
+const GEN_PAIRS: [u32; 1024] = checksum::pair_table_u32(6, &GEN);
 
 impl Checksum for Bech32 {
     type MidstateRepr = u32;
@@ -68,6 +100,7 @@ impl Checksum for Bech32 {
     const CHECKSUM_LENGTH: usize = 6;
     const GENERATOR_SH: [u32; 5] = GEN;
     const TARGET_RESIDUE: u32 = 1;
+    const PAIR_TABLE: Option<&'static [u32; 1024]> = Some(&GEN_PAIRS);
 }
 // Same as Bech32 except TARGET_RESIDUE is different
 impl Checksum for Bech32m {
@@ -81,6 +114,62 @@ impl Checksum for Bech32m {
     const CHECKSUM_LENGTH: usize = 6;
     const GENERATOR_SH: [u32; 5] = GEN;
     const TARGET_RESIDUE: u32 = 0x2bc830a3;
+    const PAIR_TABLE: Option<&'static [u32; 1024]> = Some(&GEN_PAIRS);
+}
+
+impl Checksum for Codex32 {
+    type MidstateRepr = u128;
+
+    type CorrectionField = Fe1024;
+    const ROOT_GENERATOR: Self::CorrectionField = Fe1024::new([Fe32::_9, Fe32::_9]);
+    const ROOT_EXPONENTS: core::ops::RangeInclusive<usize> = 9..=16;
+
+    const CODE_LENGTH: usize = 93;
+    const CHECKSUM_LENGTH: usize = 13;
+    // Copied from BIP-93
+    const GENERATOR_SH: [u128; 5] = [
+        0x19dc500ce73fde210,
+        0x1bfae00def77fe529,
+        0x1fbd920fffe7bee52,
+        0x1739640bdeee3fdad,
+        0x07729a039cfc75f5a,
+    ];
+    const TARGET_RESIDUE: u128 = 0x10ce0795c2fd1e62a;
+}
+
+impl Checksum for Codex32Long {
+    type MidstateRepr = u128;
+
+    type CorrectionField = Fe1024;
+    const ROOT_GENERATOR: Self::CorrectionField = Fe1024::new([Fe32::Y, Fe32::_9]);
+    const ROOT_EXPONENTS: core::ops::RangeInclusive<usize> = 1020..=1027;
+
+    const CODE_LENGTH: usize = 1023;
+    const CHECKSUM_LENGTH: usize = 15;
+    // Copied from BIP-93
+    const GENERATOR_SH: [u128; 5] = [
+        0x3d59d273535ea62d897,
+        0x7a9becb6361c6c51507,
+        0x543f9b7e6c38d8a2a0e,
+        0x0c577eaeccf1990d13c,
+        0x1887f74f8dc71b10651,
+    ];
+    const TARGET_RESIDUE: u128 = 0x43381e570bf4798ab26;
+}
+
+impl Checksum for DescriptorChecksum {
+    type MidstateRepr = u64;
+
+    type CorrectionField = Fe32768;
+    const ROOT_GENERATOR: Self::CorrectionField = Fe32768::new([Fe32::T, Fe32::G, Fe32::_0]);
+    const ROOT_EXPONENTS: core::ops::RangeInclusive<usize> = 1056..=1058;
+
+    const CODE_LENGTH: usize = 32767;
+    const CHECKSUM_LENGTH: usize = 8;
+    // Copied from Bitcoin Core src/script/descriptor.cpp
+    const GENERATOR_SH: [u64; 5] =
+        [0xf5dee51989, 0xa9fdca3312, 0x1bab10e32d, 0x3706b1677a, 0x644d626ffd];
+    const TARGET_RESIDUE: u64 = 1;
 }
 
 #[cfg(test)]
@@ -92,4 +181,13 @@ mod tests {
 
     #[test]
     fn bech32m_sanity() { Bech32m::sanity_check(); }
+
+    #[test]
+    fn codex32_sanity() { Codex32::sanity_check(); }
+
+    #[test]
+    fn codex32_long_sanity() { Codex32Long::sanity_check(); }
+
+    #[test]
+    fn descriptor_checksum_sanity() { DescriptorChecksum::sanity_check(); }
 }
@@ -10,24 +10,24 @@ use crate::Fe32;
 
 /// A polynomial over some field.
 #[derive(Clone, Debug)]
-pub struct Polynomial<F> {
+pub struct Polynomial<F, const N: usize> {
     /// The coefficients of the polynomial, in "little-endian" order.
     /// That is the constant term is at index 0.
-    inner: FieldVec<F>,
+    inner: FieldVec<F, N>,
 }
 
-impl<F: Field> PartialEq for Polynomial<F> {
+impl<F: Field, const N: usize> PartialEq for Polynomial<F, N> {
     fn eq(&self, other: &Self) -> bool { self.coefficients() == other.coefficients() }
 }
 
-impl<F: Field> Eq for Polynomial<F> {}
+impl<F: Field, const N: usize> Eq for Polynomial<F, N> {}
 
-impl Polynomial<Fe32> {
+impl<const N: usize> Polynomial<Fe32, N> {
     pub fn from_residue<R: PackedFe32>(residue: R) -> Self {
         (0..R::WIDTH).map(|i| Fe32(residue.unpack(i))).collect()
     }
 }
-impl<F: Field> Polynomial<F> {
+impl<F: Field, const N: usize> Polynomial<F, N> {
     /// Determines whether the residue is representable, given the current
     /// compilation context.
     pub fn has_data(&self) -> bool { self.inner.has_data() }
@@ -36,15 +36,12 @@ impl<F: Field> Polynomial<F> {
     pub fn assert_has_data(&self) { self.inner.assert_has_data() }
 
     /// Provides access to the underlying [`FieldVec`].
-    pub fn into_inner(self) -> FieldVec<F> { self.inner }
-
-    /// Provides access to the underlying [`FieldVec`].
-    pub fn as_inner(&self) -> &FieldVec<F> { &self.inner }
+    pub fn as_inner(&self) -> &FieldVec<F, N> { &self.inner }
 
     /// Constructs a polynomial from a slice of field elements, prepending
     /// a 1 value to produce a monic polynomial.
     pub fn with_monic_leading_term(coeffs: &[F]) -> Self {
-        let mut inner: FieldVec<_> = coeffs.iter().rev().cloned().collect();
+        let mut inner: FieldVec<_, N> = coeffs.iter().rev().cloned().collect();
         inner.push(F::ONE);
         Polynomial { inner }
     }
@@ -73,7 +70,7 @@ impl<F: Field> Polynomial<F> {
     /// # Panics
     ///
     /// Panics if [`Self::has_data`] is false.
-    pub fn iter(&self) -> slice::Iter<F> {
+    pub fn iter(&self) -> slice::Iter<'_, F> {
         self.assert_has_data();
         self.coefficients().iter()
     }
@@ -127,7 +124,7 @@ impl<F: Field> Polynomial<F> {
     /// # Panics
     ///
     /// Panics if [`Self::has_data`] is false.
-    pub fn find_nonzero_distinct_roots<E: Field + From<F>>(&self, base: E) -> RootIter<E> {
+    pub fn find_nonzero_distinct_roots<E: Field + From<F>>(&self, base: E) -> RootIter<E, N> {
         self.inner.assert_has_data();
 
         RootIter {
@@ -184,7 +181,7 @@ impl<F: Field> Polynomial<F> {
             (sidx..=eidx).map(|i| self.inner[exp - i].clone() * &other.inner[i]).sum()
         };
 
-        let max_n = cmp::min(sdeg + odeg + 1, d - 1);
+        let max_n = cmp::min(sdeg + odeg, d - 1);
         (0..=max_n).map(convolution_product).collect()
     }
 
@@ -220,17 +217,54 @@ impl<F: Field> Polynomial<F> {
     pub fn bch_generator_primitive_element<E: ExtensionField<BaseField = F>>(
         &self,
     ) -> (E, usize, ops::RangeInclusive<usize>) {
-        let roots: FieldVec<usize> = self.find_nonzero_distinct_roots(E::GENERATOR).collect();
+        match self.bch_root_series() {
+            Ok((prim_elem, code_len, min_index, max_length)) => {
+                // We write `a..=b - 1` instead of `a..b` because RangeInclusive is actually
+                // a different type than Range, so the two syntaxes are not equivalent here.
+                (prim_elem, code_len, min_index..=min_index + max_length - 1)
+            }
+            Err(RootSeriesError::NotSplit(roots)) => {
+                assert_eq!(
+                    self.degree() + usize::from(self.zero_is_root()),
+                    roots.len(),
+                    "Found {} roots ({:?}) for a polynomial of degree {}; polynomial appears not to split.",
+                    roots.len(),
+                    roots,
+                    self.degree(),
+                );
+                unreachable!("the assertion above fails for polynomials which do not split")
+            }
+            Err(RootSeriesError::NotPowers { initial_elem, prim_elem, max_length }) => panic!("Found geometric series within roots starting from {} (ratio {} length {}), but the series does not consist of powers of the ratio.", initial_elem, prim_elem, max_length),
+        }
+    }
+
+    /// Same as [`Self::bch_generator_primitive_element`] but returns `None` rather than
+    /// panicking if the polynomial is not a BCH generator polynomial, or if its roots do not
+    /// contain a geometric series of length at least 2.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn try_bch_generator_primitive_element<E: ExtensionField<BaseField = F>>(
+        &self,
+    ) -> Option<(E, usize, ops::RangeInclusive<usize>)> {
+        let (prim_elem, code_len, min_index, max_length) = self.bch_root_series().ok()?;
+        if max_length < 2 {
+            return None;
+        }
+        Some((prim_elem, code_len, min_index..=min_index + max_length - 1))
+    }
+
+    /// Finds the longest geometric series within the roots of a BCH generator polynomial.
+    ///
+    /// Returns the ratio of the series, its multiplicative order, the exponent of the ratio
+    /// which is the first element of the series, and the length of the series.
+    fn bch_root_series<E: ExtensionField<BaseField = F>>(
+        &self,
+    ) -> Result<(E, usize, usize, usize), RootSeriesError<E, N>> {
+        let roots: FieldVec<usize, N> = self.find_nonzero_distinct_roots(E::GENERATOR).collect();
         debug_assert!(roots.len() <= self.degree());
         // debug_assert!(roots.is_sorted()); // nightly only API
-        assert_eq!(
-            self.degree() + usize::from(self.zero_is_root()),
-            roots.len(),
-            "Found {} roots ({:?}) for a polynomial of degree {}; polynomial appears not to split.",
-            roots.len(),
-            roots,
-            self.degree(),
-        );
+        if self.degree() + usize::from(self.zero_is_root()) != roots.len() {
+            return Err(RootSeriesError::NotSplit(roots));
+        }
 
         // Brute-force (worst case n^3*log(n) in the length of the polynomial) the longest
         // geometric series within the set of roots. The common ratio between these
@@ -292,18 +326,22 @@ impl<F: Field> Polynomial<F> {
             }
             base *= &prim_elem;
         }
-        let min_index = match min_index {
-            Some(idx) => idx,
-            None => panic!("Found geometric series within roots starting from {} (ratio {} length {}), but the series does not consist of powers of the ratio.", initial_elem, prim_elem, max_length),
-        };
-
-        // We write `a..=b - 1` instead of `a..b` because RangeInclusive is actually
-        // a different type than Range, so the two syntaxes are not equivalent here.
-        (prim_elem, code_len, min_index..=min_index + max_length - 1)
+        match min_index {
+            Some(min_index) => Ok((prim_elem, code_len, min_index, max_length)),
+            None => Err(RootSeriesError::NotPowers { initial_elem, prim_elem, max_length }),
+        }
     }
 }
 
-impl<F: Field> fmt::Display for Polynomial<F> {
+/// The reason [`Polynomial::bch_root_series`] failed.
+enum RootSeriesError<E, const N: usize> {
+    /// The polynomial does not split, these are the roots which were found.
+    NotSplit(FieldVec<usize, N>),
+    /// The longest geometric series does not consist of powers of its ratio.
+    NotPowers { initial_elem: E, prim_elem: E, max_length: usize },
+}
+
+impl<F: Field, const N: usize> fmt::Display for Polynomial<F, N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if self.has_data() {
             for fe in self.iter() {
@@ -316,7 +354,7 @@ impl<F: Field> fmt::Display for Polynomial<F> {
     }
 }
 
-impl<F: Field> iter::FromIterator<F> for Polynomial<F> {
+impl<F: Field, const N: usize> iter::FromIterator<F> for Polynomial<F, N> {
     #[inline]
     fn from_iter<I>(iter: I) -> Self
     where
@@ -326,38 +364,38 @@ impl<F: Field> iter::FromIterator<F> for Polynomial<F> {
     }
 }
 
-impl<F> From<FieldVec<F>> for Polynomial<F> {
-    fn from(inner: FieldVec<F>) -> Self { Self { inner } }
+impl<F, const N: usize> From<FieldVec<F, N>> for Polynomial<F, N> {
+    fn from(inner: FieldVec<F, N>) -> Self { Self { inner } }
 }
 
-impl<F: Field> ops::Add<&Polynomial<F>> for Polynomial<F> {
-    type Output = Polynomial<F>;
+impl<F: Field, const N: usize> ops::Add<&Polynomial<F, N>> for Polynomial<F, N> {
+    type Output = Polynomial<F, N>;
 
-    fn add(mut self, other: &Polynomial<F>) -> Polynomial<F> {
+    fn add(mut self, other: &Polynomial<F, N>) -> Polynomial<F, N> {
         self += other;
         self
     }
 }
 
-impl<F: Field> ops::Add<Polynomial<F>> for Polynomial<F> {
-    type Output = Polynomial<F>;
-    fn add(self, other: Polynomial<F>) -> Polynomial<F> { self + &other }
+impl<F: Field, const N: usize> ops::Add<Polynomial<F, N>> for Polynomial<F, N> {
+    type Output = Polynomial<F, N>;
+    fn add(self, other: Polynomial<F, N>) -> Polynomial<F, N> { self + &other }
 }
 
-impl<F: Field> ops::Sub<&Polynomial<F>> for Polynomial<F> {
-    type Output = Polynomial<F>;
-    fn sub(mut self, other: &Polynomial<F>) -> Polynomial<F> {
+impl<F: Field, const N: usize> ops::Sub<&Polynomial<F, N>> for Polynomial<F, N> {
+    type Output = Polynomial<F, N>;
+    fn sub(mut self, other: &Polynomial<F, N>) -> Polynomial<F, N> {
         self -= other;
         self
     }
 }
 
-impl<F: Field> ops::Sub<Polynomial<F>> for Polynomial<F> {
-    type Output = Polynomial<F>;
-    fn sub(self, other: Polynomial<F>) -> Polynomial<F> { self - &other }
+impl<F: Field, const N: usize> ops::Sub<Polynomial<F, N>> for Polynomial<F, N> {
+    type Output = Polynomial<F, N>;
+    fn sub(self, other: Polynomial<F, N>) -> Polynomial<F, N> { self - &other }
 }
 
-impl<F: Field> ops::AddAssign<&Polynomial<F>> for Polynomial<F> {
+impl<F: Field, const N: usize> ops::AddAssign<&Polynomial<F, N>> for Polynomial<F, N> {
     fn add_assign(&mut self, other: &Self) {
         self.zero_pad_up_to(other.inner.len());
         for i in 0..other.inner.len() {
@@ -366,12 +404,12 @@ impl<F: Field> ops::AddAssign<&Polynomial<F>> for Polynomial<F> {
     }
 }
 
-impl<F: Field> ops::AddAssign for Polynomial<F> {
-    fn add_assign(&mut self, other: Polynomial<F>) { *self += &other; }
+impl<F: Field, const N: usize> ops::AddAssign for Polynomial<F, N> {
+    fn add_assign(&mut self, other: Polynomial<F, N>) { *self += &other; }
 }
 
-impl<F: Field> ops::SubAssign<&Polynomial<F>> for Polynomial<F> {
-    fn sub_assign(&mut self, other: &Polynomial<F>) {
+impl<F: Field, const N: usize> ops::SubAssign<&Polynomial<F, N>> for Polynomial<F, N> {
+    fn sub_assign(&mut self, other: &Polynomial<F, N>) {
         self.zero_pad_up_to(other.inner.len());
         for i in 0..other.inner.len() {
             self.inner[i] -= &other.inner[i];
@@ -379,8 +417,8 @@ impl<F: Field> ops::SubAssign<&Polynomial<F>> for Polynomial<F> {
     }
 }
 
-impl<F: Field> ops::SubAssign for Polynomial<F> {
-    fn sub_assign(&mut self, other: Polynomial<F>) { *self -= &other; }
+impl<F: Field, const N: usize> ops::SubAssign for Polynomial<F, N> {
+    fn sub_assign(&mut self, other: Polynomial<F, N>) { *self -= &other; }
 }
 
 /// An iterator over the roots of a polynomial.
@@ -389,14 +427,14 @@ impl<F: Field> ops::SubAssign for Polynomial<F> {
 /// method, which takes a field element as a base. The roots of the
 /// polynomial are yielded as exponents of the base. See the documentation
 /// of that method for more information.
-pub struct RootIter<F> {
+pub struct RootIter<F, const N: usize> {
     idx: usize,
     max_idx: usize,
-    base_powers: FieldVec<F>,
-    polynomial: FieldVec<F>,
+    base_powers: FieldVec<F, N>,
+    polynomial: FieldVec<F, N>,
 }
 
-impl<F: Field> Iterator for RootIter<F> {
+impl<F: Field, const N: usize> Iterator for RootIter<F, N> {
     type Item = usize;
     fn next(&mut self) -> Option<usize> {
         // A zero-length polynomial has no nonzero roots. Special-case this
@@ -422,8 +460,11 @@ mod tests {
     use Fe32 as F;
 
     use super::*;
+    use crate::primitives::correction::NO_ALLOC_MAX_LENGTH;
     use crate::{Fe1024, Fe32};
 
+    type Polynomial<F> = super::Polynomial<F, NO_ALLOC_MAX_LENGTH>;
+
     #[test]
     #[cfg(feature = "alloc")]
     fn roots() {
@@ -5,6 +5,8 @@
 //! [BIP-173]: <https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki>
 //! [BIP-350]: <https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki>
 
+use core::cmp::Ordering;
+use core::convert::TryFrom;
 use core::fmt;
 
 use crate::primitives::gf32::Fe32;
@@ -60,6 +62,63 @@ pub fn validate_witness_program_length(
     Ok(())
 }
 
+/// A segwit witness version, guaranteed to be in the range 0 to 16 inclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WitnessVersion(Fe32);
+
+impl WitnessVersion {
+    /// Segwit version 0.
+    pub const V0: WitnessVersion = WitnessVersion(VERSION_0);
+    /// Segwit version 1 (taproot).
+    pub const V1: WitnessVersion = WitnessVersion(VERSION_1);
+
+    /// Constructs a witness version from the field element that encodes it.
+    ///
+    /// # Errors
+    ///
+    /// If `fe` does not represent a valid segwit version.
+    #[inline]
+    pub fn from_fe(fe: Fe32) -> Result<Self, InvalidWitnessVersionError> {
+        validate_witness_version(fe)?;
+        Ok(WitnessVersion(fe))
+    }
+
+    /// Returns the field element that encodes this witness version.
+    #[inline]
+    pub fn to_fe(self) -> Fe32 { self.0 }
+
+    /// Returns the witness version as a number.
+    #[inline]
+    pub fn to_u8(self) -> u8 { self.0.to_u8() }
+}
+
+impl Ord for WitnessVersion {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering { self.to_u8().cmp(&other.to_u8()) }
+}
+
+impl PartialOrd for WitnessVersion {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+/// Displays the witness version as a number.
+impl fmt::Display for WitnessVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::Display::fmt(&self.to_u8(), f) }
+}
+
+impl TryFrom<Fe32> for WitnessVersion {
+    type Error = InvalidWitnessVersionError;
+
+    #[inline]
+    fn try_from(fe: Fe32) -> Result<Self, Self::Error> { Self::from_fe(fe) }
+}
+
+impl From<WitnessVersion> for Fe32 {
+    #[inline]
+    fn from(version: WitnessVersion) -> Fe32 { version.to_fe() }
+}
+
 /// Field element does not represent a valid witness version.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -0,0 +1,500 @@
+// SPDX-License-Identifier: MIT
+
+//! Incremental decoding of HRP strings which arrive in chunks.
+//!
+//! The [`decode`] module requires the whole string up front. [`StreamDecoder`] instead is fed
+//! the string a chunk of bytes at a time, e.g. as it is read from a socket or serial link. It
+//! validates characters as they arrive, keeps a running checksum, and emits the decoded data as
+//! soon as it is known not to be part of the checksum.
+//!
+//! Once all the input has been pushed, [`StreamDecoder::finish`] returns the same result as
+//! [`CheckedHrpstring::new`] would for the whole string. Data emitted before then is tentative:
+//! it must be discarded if `finish` returns an error. Until the string is longer than the
+//! maximum HRP length it is not known where the HRP ends, so nothing is emitted before then.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::primitives::decode::CheckedHrpstring;
+//! use bech32::primitives::stream::StreamDecoder;
+//! use bech32::{hrp, Bech32};
+//!
+//! let s = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+//!
+//! let mut decoder = StreamDecoder::<Bech32>::new();
+//! let mut data = vec![];
+//! for chunk in s.as_bytes().chunks(5) {
+//!     decoder.push_bytes(chunk, |b| data.push(b));
+//! }
+//! let hrp = decoder.finish_bytes(|b| data.push(b)).expect("valid string");
+//!
+//! assert_eq!(hrp, hrp::BC);
+//! let checked = CheckedHrpstring::new::<Bech32>(s).unwrap();
+//! assert!(checked.byte_iter().eq(data));
+//! ```
+//!
+//! [`decode`]: crate::primitives::decode
+//! [`CheckedHrpstring::new`]: crate::primitives::decode::CheckedHrpstring::new
+
+use core::{char, fmt, str};
+
+use crate::primitives::checksum::{self, Checksum, PackedFe32};
+use crate::primitives::decode::{
+    CharError, CheckedHrpstringError, ChecksumError, CodeLengthError, InvalidResidueError,
+    UncheckedHrpstringError,
+};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::{self, Hrp, MAX_HRP_LEN};
+
+/// The separator between the hrp and payload of bech32 strings.
+const SEP: char = '1';
+
+/// The number of bytes buffered before the human-readable part is known.
+///
+/// Once the input is longer than [`MAX_HRP_LEN`], any later separator would make the HRP too
+/// long, so the last separator seen so far must be the real one (or the string is invalid). A
+/// character starting at position `MAX_HRP_LEN - 1` may be up to four bytes long.
+const BUF_LEN: usize = MAX_HRP_LEN + 4;
+
+/// A push-based decoder for HRP strings with the checksum `Ck`.
+///
+/// Feed it the string with [`Self::push`], then call [`Self::finish`]. To receive the data as
+/// bytes rather than field elements use [`Self::push_bytes`] and [`Self::finish_bytes`] instead.
+///
+/// The input is bytes rather than `str`s so that chunks do not need to end on a character
+/// boundary. Input which is not valid UTF-8 is treated as if each invalid sequence were replaced
+/// by U+FFFD (which is never valid in an HRP string).
+pub struct StreamDecoder<Ck: Checksum> {
+    /// The first [`BUF_LEN`] bytes of input.
+    buf: [u8; BUF_LEN],
+    /// The number of bytes of input received, including any incomplete character.
+    received: usize,
+    /// A partially received multi-byte UTF-8 character.
+    utf8: [u8; 4],
+    /// The number of bytes of `utf8` received so far.
+    utf8_len: usize,
+    /// The number of bytes `utf8` will have when complete.
+    utf8_need: usize,
+
+    /// The length in bytes of the complete characters received.
+    len: usize,
+    /// The position of the last separator.
+    sep_pos: Option<usize>,
+    /// The last character after the last separator which is not a valid bech32 character.
+    invalid_char: Option<char>,
+    /// Whether any uppercase characters have been seen.
+    has_upper: bool,
+    /// Whether any lowercase characters have been seen.
+    has_lower: bool,
+
+    /// Where we are in decoding the data part.
+    state: State,
+    /// The checksum engine, once the HRP has been input into it.
+    engine: checksum::Engine<Ck>,
+    /// The last `Ck::CHECKSUM_LENGTH` field elements of the data part, which may be checksum.
+    delay: Ck::MidstateRepr,
+    /// The number of field elements of the data part input into `engine`.
+    data_len: usize,
+    /// Decoded data not yet emitted by [`Self::push_bytes`].
+    pending_bits: Bits,
+}
+
+/// Bits of decoded data which do not yet make up a whole byte.
+#[derive(Debug, Copy, Clone, Default)]
+struct Bits {
+    /// The bits, in the least significant `count` bits.
+    bits: u16,
+    /// The number of bits.
+    count: u8,
+}
+
+impl Bits {
+    /// Adds the bits of `fe`, emitting a byte if there are now enough bits.
+    fn push<F: FnMut(u8)>(&mut self, fe: Fe32, emit: &mut F) {
+        self.bits = (self.bits << 5) | u16::from(fe.to_u8());
+        self.count += 5;
+        if self.count >= 8 {
+            self.count -= 8;
+            emit((self.bits >> self.count) as u8);
+        }
+    }
+}
+
+/// Where a [`StreamDecoder`] is in decoding the data part.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    /// The HRP is not yet known, input is being buffered.
+    Buffering,
+    /// The HRP is known and the data part is being decoded.
+    Data,
+    /// The string is known to be invalid, nothing more will be emitted.
+    Invalid,
+}
+
+impl<Ck: Checksum> Default for StreamDecoder<Ck> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<Ck: Checksum> StreamDecoder<Ck> {
+    /// Constructs a new decoder with no input.
+    pub fn new() -> Self {
+        StreamDecoder {
+            buf: [0; BUF_LEN],
+            received: 0,
+            utf8: [0; 4],
+            utf8_len: 0,
+            utf8_need: 0,
+            len: 0,
+            sep_pos: None,
+            invalid_char: None,
+            has_upper: false,
+            has_lower: false,
+            state: State::Buffering,
+            engine: checksum::Engine::new(),
+            delay: Ck::MidstateRepr::pack(core::iter::empty()),
+            data_len: 0,
+            pending_bits: Bits::default(),
+        }
+    }
+
+    /// Feeds the next chunk of the string into the decoder.
+    ///
+    /// `emit` is called with each field element of the data part (excluding the checksum) as
+    /// soon as it is known.
+    pub fn push<F: FnMut(Fe32)>(&mut self, chunk: &[u8], mut emit: F) {
+        for &b in chunk {
+            if self.received < BUF_LEN {
+                self.buf[self.received] = b;
+            }
+            self.received += 1;
+            self.push_byte(b, &mut emit);
+        }
+    }
+
+    /// Feeds the next chunk of the string into the decoder.
+    ///
+    /// `emit` is called with each byte of the data part (excluding the checksum) as soon as it is
+    /// known, as [`CheckedHrpstring::byte_iter`] would yield them. This should not be mixed with
+    /// [`Self::push`] on the same decoder.
+    ///
+    /// [`CheckedHrpstring::byte_iter`]: crate::primitives::decode::CheckedHrpstring::byte_iter
+    pub fn push_bytes<F: FnMut(u8)>(&mut self, chunk: &[u8], mut emit: F) {
+        let mut bits = self.pending_bits;
+        self.push(chunk, |fe| bits.push(fe, &mut emit));
+        self.pending_bits = bits;
+    }
+
+    /// Completes decoding, returning the human-readable part if the string was valid.
+    ///
+    /// The result is the same as that of [`CheckedHrpstring::new`] on the whole string.
+    ///
+    /// Until more than 83 bytes (the maximum HRP length) of input have been received a later
+    /// separator may still move the start of the data part, so nothing is emitted before then,
+    /// whatever the length of the HRP. For shorter strings `emit` is therefore called here with
+    /// the whole data part (excluding the checksum).
+    ///
+    /// [`CheckedHrpstring::new`]: crate::primitives::decode::CheckedHrpstring::new
+    pub fn finish<F: FnMut(Fe32)>(mut self, mut emit: F) -> Result<Hrp, CheckedHrpstringError> {
+        // An incomplete trailing character.
+        if self.utf8_need > 0 {
+            self.push_char(char::REPLACEMENT_CHARACTER, self.utf8_len, &mut emit);
+        }
+        if self.state == State::Buffering {
+            self.commit(&mut emit);
+        }
+
+        let hrp = self.validate_characters().map_err(CheckedHrpstringError::Parse)?;
+        debug_assert_eq!(self.state, State::Data);
+        self.validate_checksum()?;
+        Ok(hrp)
+    }
+
+    /// Completes decoding, as [`Self::finish`] does, emitting any remaining bytes of the data part
+    /// (as [`Self::push_bytes`] does).
+    pub fn finish_bytes<F: FnMut(u8)>(self, mut emit: F) -> Result<Hrp, CheckedHrpstringError> {
+        let mut bits = self.pending_bits;
+        self.finish(|fe| bits.push(fe, &mut emit))
+    }
+
+    /// Checks the characters and parses the HRP, as [`UncheckedHrpstring::new`] does.
+    ///
+    /// [`UncheckedHrpstring::new`]: crate::primitives::decode::UncheckedHrpstring::new
+    fn validate_characters(&self) -> Result<Hrp, UncheckedHrpstringError> {
+        if let Some(ch) = self.invalid_char {
+            return Err(CharError::InvalidChar(ch).into());
+        }
+        if self.has_upper && self.has_lower {
+            return Err(CharError::MixedCase.into());
+        }
+        let sep_pos = self.sep_pos.ok_or(CharError::MissingSeparator)?;
+
+        if sep_pos == 0 {
+            return Err(hrp::Error::Empty.into());
+        }
+        if sep_pos > MAX_HRP_LEN {
+            return Err(hrp::Error::TooLong(sep_pos).into());
+        }
+        // The HRP is short enough to be in the buffer.
+        let hrp = str::from_utf8(&self.buf[..sep_pos])
+            .map_err(|_| hrp::Error::NonAsciiChar(char::REPLACEMENT_CHARACTER))?;
+        Ok(Hrp::parse(hrp)?)
+    }
+
+    /// Checks the checksum, as [`UncheckedHrpstring::validate_checksum`] does.
+    ///
+    /// [`UncheckedHrpstring::validate_checksum`]: crate::primitives::decode::UncheckedHrpstring::validate_checksum
+    fn validate_checksum(&self) -> Result<(), ChecksumError> {
+        if self.len > Ck::CODE_LENGTH {
+            return Err(ChecksumError::CodeLength(CodeLengthError {
+                encoded_length: self.len,
+                code_length: Ck::CODE_LENGTH,
+            }));
+        }
+        if Ck::CHECKSUM_LENGTH == 0 {
+            return Ok(());
+        }
+        if self.data_len < Ck::CHECKSUM_LENGTH {
+            return Err(ChecksumError::InvalidLength);
+        }
+        let residue = *self.engine.residue();
+        if residue != Ck::TARGET_RESIDUE {
+            return Err(ChecksumError::InvalidResidue(InvalidResidueError::new(
+                residue,
+                Ck::TARGET_RESIDUE,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Decodes UTF-8 a byte at a time.
+    fn push_byte<F: FnMut(Fe32)>(&mut self, b: u8, emit: &mut F) {
+        if self.utf8_need > 0 {
+            if b & 0xc0 == 0x80 {
+                self.utf8[self.utf8_len] = b;
+                self.utf8_len += 1;
+                if self.utf8_len == self.utf8_need {
+                    let len = self.utf8_len;
+                    let ch = str::from_utf8(&self.utf8[..len])
+                        .ok()
+                        .and_then(|s| s.chars().next())
+                        .unwrap_or(char::REPLACEMENT_CHARACTER);
+                    self.utf8_need = 0;
+                    self.push_char(ch, len, emit);
+                }
+                return;
+            }
+            // Truncated sequence, `b` starts a new character.
+            let len = self.utf8_len;
+            self.utf8_need = 0;
+            self.push_char(char::REPLACEMENT_CHARACTER, len, emit);
+        }
+
+        match b {
+            0x00..=0x7f => self.push_char(char::from(b), 1, emit),
+            0xc2..=0xf4 => {
+                self.utf8[0] = b;
+                self.utf8_len = 1;
+                self.utf8_need = match b {
+                    0xc2..=0xdf => 2,
+                    0xe0..=0xef => 3,
+                    _ => 4,
+                };
+            }
+            _ => self.push_char(char::REPLACEMENT_CHARACTER, 1, emit),
+        }
+    }
+
+    /// Processes the next character, `width` bytes long.
+    fn push_char<F: FnMut(Fe32)>(&mut self, ch: char, width: usize, emit: &mut F) {
+        let pos = self.len;
+        self.len += width;
+
+        // Track what `check_characters` and `Hrp::parse` need to know about the whole string.
+        if ch == SEP {
+            self.sep_pos = Some(pos);
+            // Characters before the last separator are part of the HRP.
+            self.invalid_char = None;
+        } else if Fe32::from_char(ch).is_err() {
+            self.invalid_char = Some(ch);
+        }
+        if ch.is_ascii_uppercase() {
+            self.has_upper = true;
+        } else if ch.is_ascii_lowercase() {
+            self.has_lower = true;
+        }
+
+        match self.state {
+            State::Buffering =>
+                if self.len > MAX_HRP_LEN {
+                    self.commit(emit);
+                },
+            State::Data =>
+                if ch == SEP {
+                    // The HRP would be too long.
+                    self.state = State::Invalid;
+                } else {
+                    self.push_data(ch, emit);
+                },
+            State::Invalid => {}
+        }
+    }
+
+    /// Parses the HRP from the buffer and decodes the buffered part of the data part.
+    fn commit<F: FnMut(Fe32)>(&mut self, emit: &mut F) {
+        self.state = State::Invalid;
+        let sep_pos = match self.sep_pos {
+            Some(pos) => pos,
+            None => return,
+        };
+        let hrp = match str::from_utf8(&self.buf[..sep_pos]).ok().map(Hrp::parse) {
+            Some(Ok(hrp)) => hrp,
+            _ => return,
+        };
+        self.engine.input_hrp(hrp);
+        self.state = State::Data;
+
+        let buf = self.buf;
+        for &b in &buf[sep_pos + 1..self.len] {
+            self.push_data(char::from(b), emit);
+        }
+    }
+
+    /// Inputs a character of the data part into the checksum, emitting the field element which
+    /// is now known not to be part of the checksum, if any.
+    fn push_data<F: FnMut(Fe32)>(&mut self, ch: char, emit: &mut F) {
+        let fe = match Fe32::from_char(ch) {
+            Ok(fe) if self.state == State::Data => fe,
+            _ => {
+                self.state = State::Invalid;
+                return;
+            }
+        };
+        self.engine.input_fe(fe);
+        self.data_len += 1;
+        if Ck::CHECKSUM_LENGTH == 0 {
+            emit(fe);
+        } else {
+            let out = self.delay.mul_by_x_then_add(Ck::CHECKSUM_LENGTH, fe.to_u8());
+            if self.data_len > Ck::CHECKSUM_LENGTH {
+                emit(Fe32(out));
+            }
+        }
+    }
+}
+
+impl<Ck: Checksum> fmt::Debug for StreamDecoder<Ck> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamDecoder")
+            .field("len", &self.len)
+            .field("sep_pos", &self.sep_pos)
+            .field("data_len", &self.data_len)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(feature = "alloc", not(feature = "std")))]
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::primitives::decode::CheckedHrpstring;
+    use crate::{Bech32, Bech32m, Codex32, NoChecksum};
+
+    /// Checks that streaming `s` in chunks of every size gives the same result as the one-shot
+    /// decoder.
+    fn check<Ck: Checksum>(s: &str) {
+        let expected = CheckedHrpstring::new::<Ck>(s);
+        for chunk_len in 1..=s.len().max(1) {
+            let mut decoder = StreamDecoder::<Ck>::new();
+            let mut fes = Vec::new();
+            for chunk in s.as_bytes().chunks(chunk_len) {
+                decoder.push(chunk, |fe| fes.push(fe));
+            }
+            let res = decoder.finish(|fe| fes.push(fe));
+
+            let mut decoder = StreamDecoder::<Ck>::new();
+            let mut bytes = Vec::new();
+            for chunk in s.as_bytes().chunks(chunk_len) {
+                decoder.push_bytes(chunk, |b| bytes.push(b));
+            }
+            assert_eq!(decoder.finish_bytes(|b| bytes.push(b)), res);
+
+            match expected {
+                Ok(ref checked) => {
+                    assert_eq!(res, Ok(checked.hrp()), "{}", s);
+                    assert_eq!(
+                        fes,
+                        checked.fe32_iter(0).collect::<Vec<_>>(),
+                        "{} {}",
+                        s,
+                        chunk_len
+                    );
+                    assert!(bytes.iter().copied().eq(checked.byte_iter()), "{}", s);
+                }
+                Err(ref e) => assert_eq!(res.as_ref(), Err(e), "{} (chunks of {})", s, chunk_len),
+            }
+        }
+    }
+
+    #[test]
+    fn matches_one_shot() {
+        let strings = [
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+            "BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ",
+            "23451QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZLKULZK",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdr",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5md",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+            "a12uel5l",
+            "A12UEL5L",
+            "a12uEL5L",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx",
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "1qzzfhee",
+            "pzry9x0s0muk",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdb1q",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdbq",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdé",
+            "bcé1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5md",
+            "\u{20}1nwldj5",
+            "\u{7f}1axkwrx",
+            "x1b4n0q5v",
+            "li1dgmt3",
+            "de1lg7wt\u{ff}",
+            "",
+            "1",
+            "abc",
+        ];
+        for s in &strings {
+            check::<Bech32>(s);
+            check::<Bech32m>(s);
+            check::<NoChecksum>(s);
+        }
+        check::<Codex32>("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw");
+        // Reporting an invalid residue this long requires an allocator.
+        #[cfg(feature = "alloc")]
+        check::<Codex32>("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlx");
+
+        // Long inputs which only become invalid after the HRP has been committed.
+        let long =
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdqqar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq1q";
+        check::<Bech32>(long);
+        check::<Bech32>(&long[..long.len() - 2]);
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut decoder = StreamDecoder::<Bech32>::new();
+        decoder.push(b"bc1qar0srrr7xfkvy5l643\xff", |_| {});
+        assert_eq!(
+            decoder.finish(|_| {}),
+            Err(CheckedHrpstringError::Parse(UncheckedHrpstringError::Char(
+                CharError::InvalidChar(char::REPLACEMENT_CHARACTER)
+            ))),
+        );
+    }
+}
@@ -41,26 +41,33 @@
 //! [`bip_350_test_vectors.rs`]: <https://github.com/rust-bitcoin/rust-bech32/blob/master/tests/bip_350_test_vectors.rs>
 
 #[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
-use alloc::{string::String, vec::Vec};
+use alloc::{string::String, vec, vec::Vec};
 use core::fmt;
 
-#[cfg(feature = "alloc")]
 use crate::error::write_err;
-use crate::primitives::decode::SegwitCodeLengthError;
 #[cfg(feature = "alloc")]
-use crate::primitives::decode::{SegwitHrpstring, SegwitHrpstringError};
+use crate::primitives::checksum::Checksum;
+#[cfg(feature = "alloc")]
+use crate::primitives::correction::{
+    self, CharCorrection, CorrectableError, CorrectionError, HrpEdit,
+};
+#[cfg(feature = "alloc")]
+use crate::primitives::decode::{ChecksumError, UncheckedHrpstring};
+use crate::primitives::decode::{SegwitCodeLengthError, SegwitHrpstring, SegwitHrpstringError};
 use crate::primitives::gf32::Fe32;
 use crate::primitives::hrp::Hrp;
 use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
-use crate::primitives::segwit::MAX_STRING_LENGTH;
 #[cfg(feature = "alloc")]
-use crate::primitives::segwit::{self, InvalidWitnessVersionError, WitnessLengthError};
+use crate::primitives::segwit::{self, InvalidWitnessVersionError};
+use crate::primitives::segwit::{WitnessLengthError, MAX_STRING_LENGTH};
 use crate::primitives::{Bech32, Bech32m};
+#[cfg(feature = "alloc")]
+use crate::Variant;
 
 #[rustfmt::skip]                // Keep public re-exports separate.
 #[doc(inline)]
 pub use {
-    crate::primitives::segwit::{VERSION_0, VERSION_1},
+    crate::primitives::segwit::{VERSION_0, VERSION_1, WitnessVersion},
 };
 
 /// Decodes a segwit address.
@@ -83,6 +90,215 @@ pub fn decode(s: &str) -> Result<(Hrp, Fe32, Vec<u8>), DecodeError> {
     Ok((segwit.hrp(), segwit.witness_version(), segwit.byte_iter().collect::<Vec<u8>>()))
 }
 
+/// Corrects errors in a segwit address.
+///
+/// The checksum algorithm is chosen by the witness version, as in [`decode`]. If the address is
+/// already valid it is returned unchanged. Otherwise the errors are located and fixed, and the
+/// corrected address is validated again before being returned. Since the witness version itself
+/// may be corrupted, if correcting with its checksum algorithm fails the other one is tried.
+///
+/// # Returns
+///
+/// The corrected address and the corrected characters as `(position, old, new)` tuples, see
+/// [`crate::correct`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32::segwit;
+///
+/// // Corrupt a single character of a valid address.
+/// let address = "bc1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqcgrtxs";
+/// let (corrected, corrections) = segwit::correct(&address.replace("y3m7", "y3m8")).unwrap();
+/// assert_eq!(corrected, address);
+/// assert_eq!(corrections, [(7, '8', '7')]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn correct(s: &str) -> Result<(String, Vec<CharCorrection>), CorrectionError> {
+    let err = match SegwitHrpstring::new(s) {
+        Ok(_) => return Ok((s.into(), Vec::new())),
+        Err(e) => e,
+    };
+    if err.residue_error().is_none() {
+        return Err(CorrectionError::Segwit(err));
+    }
+
+    // Only the checksum failed, so the string parses and has a valid witness version. A corrupted
+    // witness version implies the wrong checksum, so if that fails also try the other one.
+    let unchecked = UncheckedHrpstring::new(s)?;
+    if unchecked.witness_version() == Some(VERSION_0) {
+        correct_with::<Bech32>(&unchecked, s)
+            .or_else(|e| correct_with::<Bech32m>(&unchecked, s).map_err(|_| e))
+    } else {
+        correct_with::<Bech32m>(&unchecked, s)
+            .or_else(|e| correct_with::<Bech32>(&unchecked, s).map_err(|_| e))
+    }
+}
+
+/// Corrects `s` using the `Ck` checksum, keeping the result only if it is a valid segwit address.
+#[cfg(feature = "alloc")]
+fn correct_with<Ck: Checksum>(
+    unchecked: &UncheckedHrpstring,
+    s: &str,
+) -> Result<(String, Vec<CharCorrection>), CorrectionError> {
+    let err = match unchecked.validate_checksum::<Ck>() {
+        Ok(()) => return Err(CorrectionError::TooManyErrors),
+        Err(e) => e,
+    };
+    let corrector = err.correction_context::<Ck>().ok_or(CorrectionError::TooManyErrors)?;
+    let (corrected, corrections) = correction::correct_string(s, &corrector, None)?;
+    SegwitHrpstring::new(&corrected).map_err(CorrectionError::SegwitRevalidation)?;
+    Ok((corrected, corrections))
+}
+
+/// Corrects errors in a segwit address whose human-readable part is known to be one of `expected`.
+///
+/// The closest expected HRP is substituted for a corrupted one, as in [`crate::correct_with_hrp`],
+/// and the rest of the address is corrected as in [`correct`].
+///
+/// # Examples
+///
+/// ```
+/// use bech32::{hrp, segwit};
+///
+/// // A testnet address with a mainnet HRP.
+/// let address = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+/// let (corrected, edit, _) = segwit::correct_with_hrp(&address.replace("tb1", "bc1"), &[hrp::TB]).unwrap();
+/// assert_eq!(corrected, address);
+/// assert_eq!(edit.unwrap().corrected, hrp::TB);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn correct_with_hrp(
+    s: &str,
+    expected: &[Hrp],
+) -> Result<(String, Option<HrpEdit>, Vec<CharCorrection>), CorrectionError> {
+    let ((corrected, corrections), edit) = correction::correct_hrp(s, expected, correct)?;
+    Ok((corrected, edit, corrections))
+}
+
+/// Locates the likely errors in a mistyped segwit address, like Bitcoin Core's `LocateErrors`.
+///
+/// This is meant for user interfaces that highlight the bad characters of an address. It never
+/// suggests a corrected address, only where the errors probably are.
+///
+/// # Returns
+///
+/// `None` if `s` is a valid segwit address. Otherwise, the reason the address is invalid and the
+/// character positions in `s` (not byte offsets) of the characters that are likely wrong. The positions may be empty,
+/// for example when the errors in the checksum cannot be located.
+///
+/// # Examples
+///
+/// ```
+/// use bech32::segwit::{self, LocateReason};
+/// use bech32::Variant;
+///
+/// let address = "bc1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqcgrtxs";
+/// assert!(segwit::locate_errors(address).is_none());
+///
+/// let located = segwit::locate_errors(&address.replace("y3m7", "y3m8")).unwrap();
+/// assert_eq!(*located.reason(), LocateReason::InvalidChecksum(Variant::Bech32m));
+/// assert_eq!(located.positions(), [7]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn locate_errors(s: &str) -> Option<ErrorLocations> {
+    use LocateReason::*;
+
+    if s.len() > MAX_STRING_LENGTH {
+        // The first character which does not fit entirely within the maximum length.
+        let pos =
+            s.char_indices().take_while(|&(i, c)| i + c.len_utf8() <= MAX_STRING_LENGTH).count();
+        return Some(ErrorLocations::new(TooLong, vec![pos]));
+    }
+
+    // Characters that are invalid anywhere, or invalid in the data part, or of the wrong case.
+    let sep = s.rfind('1');
+    let first_cased = s.bytes().find(|b| b.is_ascii_alphabetic());
+    let mut invalid = Vec::new();
+    for (pos, (i, c)) in s.char_indices().enumerate() {
+        let valid = if sep.map_or(false, |sep| i > sep) {
+            Fe32::from_char(c).is_ok()
+        } else {
+            c.is_ascii() && (33..=126).contains(&(c as u8))
+        };
+        let wrong_case = c.is_ascii_alphabetic()
+            && first_cased.map_or(false, |b| b.is_ascii_lowercase() != c.is_ascii_lowercase());
+        if !valid || wrong_case {
+            invalid.push(pos);
+        }
+    }
+    if !invalid.is_empty() {
+        return Some(ErrorLocations::new(InvalidCharacter, invalid));
+    }
+    // All characters are ASCII from here on, so byte offsets are character positions.
+
+    let sep = match sep {
+        Some(sep) => sep,
+        None => return Some(ErrorLocations::new(MissingSeparator, Vec::new())),
+    };
+    if sep == 0 || s.len() - sep - 1 < Bech32::CHECKSUM_LENGTH {
+        return Some(ErrorLocations::new(InvalidSeparatorPosition, vec![sep]));
+    }
+
+    let unchecked = UncheckedHrpstring::new(s).expect("characters and separator checked above");
+    // `None` if the witness version character is above 16, possibly because it is the typo.
+    let version = unchecked.witness_version();
+    let bech32 = unchecked.validate_checksum::<Bech32>();
+    let bech32m = unchecked.validate_checksum::<Bech32m>();
+
+    let found = if bech32.is_ok() {
+        Some(Variant::Bech32)
+    } else if bech32m.is_ok() {
+        Some(Variant::Bech32m)
+    } else {
+        None
+    };
+    if let Some(found) = found {
+        let version = match version {
+            Some(version) => version,
+            None => {
+                let e = SegwitHrpstring::new(s).expect_err("invalid witness version");
+                return Some(ErrorLocations::new(InvalidAddress(e), vec![sep + 1]));
+            }
+        };
+        let expected = if version == VERSION_0 { Variant::Bech32 } else { Variant::Bech32m };
+        if found != expected {
+            return Some(ErrorLocations::new(WrongVariant { expected }, Vec::new()));
+        }
+        return match SegwitHrpstring::new(s) {
+            Ok(_) => None,
+            Err(e) => Some(ErrorLocations::new(InvalidAddress(e), Vec::new())),
+        };
+    }
+
+    // Prefer the checksum that needs the fewest changes, and bech32 on a tie, as Core does.
+    let located = match (locate::<Bech32>(s, &bech32), locate::<Bech32m>(s, &bech32m)) {
+        (Some(a), Some(b)) if b.len() < a.len() => Some((Variant::Bech32m, b)),
+        (Some(a), _) => Some((Variant::Bech32, a)),
+        (None, Some(b)) => Some((Variant::Bech32m, b)),
+        (None, None) => None,
+    };
+    match located {
+        Some((variant, positions)) =>
+            Some(ErrorLocations::new(InvalidChecksum(variant), positions)),
+        None => Some(ErrorLocations::new(Unlocatable, Vec::new())),
+    }
+}
+
+/// Locates the errors in `s` assuming it was checksummed with `Ck`.
+///
+/// The errors are only reported if fixing them results in a valid segwit address, this guards
+/// against the corrector finding a nearby codeword that is not an address at all.
+#[cfg(feature = "alloc")]
+fn locate<Ck: Checksum>(s: &str, validation: &Result<(), ChecksumError>) -> Option<Vec<usize>> {
+    let corrector = validation.as_ref().err()?.correction_context::<Ck>()?;
+    let (corrected, corrections) = correction::correct_string(s, &corrector, None).ok()?;
+    if corrections.is_empty() || SegwitHrpstring::new(&corrected).is_err() {
+        return None;
+    }
+    Some(corrections.into_iter().map(|(pos, _, _)| pos).collect())
+}
+
 /// Encodes a segwit address.
 ///
 /// Does validity checks on the `witness_version`, length checks on the `witness_program`, and
@@ -346,6 +562,178 @@ pub fn encoded_length(
     }
 }
 
+/// A Bitcoin network, as far as it can be told apart by the HRP of a segwit address.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Network {
+    /// Bitcoin mainnet, HRP "bc".
+    Mainnet,
+    /// Bitcoin testnet and signet, which share the HRP "tb".
+    Testnet,
+    /// Bitcoin regtest, HRP "bcrt".
+    Regtest,
+}
+
+impl Network {
+    /// Returns the HRP used by segwit addresses on this network.
+    #[inline]
+    pub fn hrp(self) -> Hrp {
+        match self {
+            Network::Mainnet => crate::hrp::BC,
+            Network::Testnet => crate::hrp::TB,
+            Network::Regtest => crate::hrp::BCRT,
+        }
+    }
+
+    /// Returns the network that uses `hrp` for segwit addresses, if any.
+    ///
+    /// Comparison is case insensitive.
+    #[inline]
+    pub fn from_hrp(hrp: Hrp) -> Option<Network> {
+        if hrp.is_valid_on_mainnet() {
+            Some(Network::Mainnet)
+        } else if hrp.is_valid_on_testnet() {
+            Some(Network::Testnet)
+        } else if hrp.is_valid_on_regtest() {
+            Some(Network::Regtest)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Network> for Hrp {
+    #[inline]
+    fn from(network: Network) -> Hrp { network.hrp() }
+}
+
+/// The maximum length of a witness program.
+const MAX_PROGRAM_LENGTH: usize = 40;
+
+/// A segwit address: a network, a witness version and a valid length witness program.
+///
+/// Parsing (via [`core::str::FromStr`]) enforces the checksum required by the witness version,
+/// bech32 for version 0 and bech32m otherwise. Displays the address in lowercase.
+///
+/// # Examples
+///
+/// ```
+/// use bech32::segwit::{Network, SegwitAddress, WitnessVersion};
+///
+/// let s = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+/// let address = s.parse::<SegwitAddress>().expect("valid address");
+///
+/// assert_eq!(address.network(), Network::Mainnet);
+/// assert_eq!(address.witness_version(), WitnessVersion::V1);
+/// assert_eq!(address.program().len(), 32);
+/// assert_eq!(address.to_string(), s);
+///
+/// let testnet = SegwitAddress::new(Network::Testnet, WitnessVersion::V1, address.program())
+///     .expect("valid program length");
+/// assert!(testnet.to_string().starts_with("tb1p"));
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SegwitAddress {
+    network: Network,
+    witness_version: WitnessVersion,
+    /// The witness program, followed by zeros.
+    program: [u8; MAX_PROGRAM_LENGTH],
+    /// The length of the witness program.
+    program_length: usize,
+}
+
+impl SegwitAddress {
+    /// Constructs a new segwit address.
+    ///
+    /// # Errors
+    ///
+    /// If the length of `program` is not valid for `witness_version`.
+    #[inline]
+    pub fn new(
+        network: Network,
+        witness_version: WitnessVersion,
+        program: &[u8],
+    ) -> Result<Self, WitnessLengthError> {
+        crate::primitives::segwit::validate_witness_program_length(
+            program.len(),
+            witness_version.to_fe(),
+        )?;
+
+        let mut buf = [0u8; MAX_PROGRAM_LENGTH];
+        buf[..program.len()].copy_from_slice(program);
+        Ok(SegwitAddress { network, witness_version, program: buf, program_length: program.len() })
+    }
+
+    /// Returns the network of this address.
+    #[inline]
+    pub fn network(&self) -> Network { self.network }
+
+    /// Returns the HRP of this address.
+    #[inline]
+    pub fn hrp(&self) -> Hrp { self.network.hrp() }
+
+    /// Returns the witness version of this address.
+    #[inline]
+    pub fn witness_version(&self) -> WitnessVersion { self.witness_version }
+
+    /// Returns the witness program of this address.
+    #[inline]
+    pub fn program(&self) -> &[u8] { &self.program[..self.program_length] }
+}
+
+impl fmt::Debug for SegwitAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SegwitAddress")
+            .field("network", &self.network)
+            .field("witness_version", &self.witness_version)
+            .field("program", &self.program())
+            .finish()
+    }
+}
+
+/// Displays the address in lowercase.
+impl fmt::Display for SegwitAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        encode_lower_to_fmt_unchecked(f, self.hrp(), self.witness_version.to_fe(), self.program())
+    }
+}
+
+impl core::str::FromStr for SegwitAddress {
+    type Err = ParseAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segwit = SegwitHrpstring::new(s)?;
+        let hrp = segwit.hrp();
+        let network = Network::from_hrp(hrp).ok_or(ParseAddressError::UnknownHrp(hrp))?;
+        let witness_version = WitnessVersion::from_fe(segwit.witness_version())
+            .expect("validated by SegwitHrpstring");
+
+        let mut program = [0u8; MAX_PROGRAM_LENGTH];
+        let mut program_length = 0;
+        for (dst, src) in program.iter_mut().zip(segwit.byte_iter()) {
+            *dst = src;
+            program_length += 1;
+        }
+        Ok(SegwitAddress { network, witness_version, program, program_length })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SegwitAddress {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SegwitAddress {
+    #[inline]
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_str(crate::serde_utils::FromStrVisitor::new("a segwit address"))
+    }
+}
+
 /// An error while decoding a segwit address.
 #[cfg(feature = "alloc")]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -370,6 +758,44 @@ impl From<SegwitHrpstringError> for DecodeError {
     fn from(e: SegwitHrpstringError) -> Self { Self(e) }
 }
 
+/// An error while parsing a [`SegwitAddress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseAddressError {
+    /// The string is not a valid segwit address.
+    Segwit(SegwitHrpstringError),
+    /// The HRP is not used by any known [`Network`].
+    UnknownHrp(Hrp),
+}
+
+impl fmt::Display for ParseAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ParseAddressError::*;
+
+        match *self {
+            Segwit(ref e) => write_err!(f, "decoding segwit address failed"; e),
+            UnknownHrp(ref hrp) => write!(f, "unknown human-readable part: {}", hrp),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseAddressError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use ParseAddressError::*;
+
+        match *self {
+            Segwit(ref e) => Some(e),
+            UnknownHrp(_) => None,
+        }
+    }
+}
+
+impl From<SegwitHrpstringError> for ParseAddressError {
+    #[inline]
+    fn from(e: SegwitHrpstringError) -> Self { Self::Segwit(e) }
+}
+
 /// An error while constructing a [`SegwitHrpstring`] type.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -438,6 +864,80 @@ impl From<fmt::Error> for EncodeError {
     fn from(e: fmt::Error) -> Self { Self::Fmt(e) }
 }
 
+/// The errors located in a segwit address by [`locate_errors`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorLocations {
+    reason: LocateReason,
+    positions: Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl ErrorLocations {
+    fn new(reason: LocateReason, positions: Vec<usize>) -> Self { Self { reason, positions } }
+
+    /// Returns the reason the address is invalid.
+    #[inline]
+    pub fn reason(&self) -> &LocateReason { &self.reason }
+
+    /// Returns the character positions of the likely wrong characters, in ascending order.
+    #[inline]
+    pub fn positions(&self) -> &[usize] { &self.positions }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for ErrorLocations {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::Display::fmt(&self.reason, f) }
+}
+
+/// The reason a segwit address is invalid, as reported by [`locate_errors`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LocateReason {
+    /// The string is longer than 90 characters, the position is that of the first extra character.
+    TooLong,
+    /// Some characters are invalid or of the wrong case.
+    InvalidCharacter,
+    /// The string has no separator.
+    MissingSeparator,
+    /// The separator is at the start of the string or too close to the end.
+    InvalidSeparatorPosition,
+    /// The checksum is valid but of the wrong variant for the witness version.
+    WrongVariant {
+        /// The checksum variant required by the witness version.
+        expected: Variant,
+    },
+    /// The checksum is invalid and the errors were located assuming this variant.
+    InvalidChecksum(Variant),
+    /// The checksum is invalid and the errors could not be located.
+    Unlocatable,
+    /// The checksum is valid but the address is not, e.g. the witness program has a bad length.
+    InvalidAddress(SegwitHrpstringError),
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for LocateReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use LocateReason::*;
+
+        match *self {
+            TooLong => write!(f, "bech32 string too long"),
+            InvalidCharacter => write!(f, "invalid character or mixed case"),
+            MissingSeparator => write!(f, "missing separator"),
+            InvalidSeparatorPosition => write!(f, "invalid separator position"),
+            WrongVariant { expected: Variant::Bech32 } =>
+                write!(f, "version 0 witness address must use bech32 checksum"),
+            WrongVariant { expected: Variant::Bech32m } =>
+                write!(f, "version 1+ witness address must use bech32m checksum"),
+            InvalidChecksum(Variant::Bech32) => write!(f, "invalid bech32 checksum"),
+            InvalidChecksum(Variant::Bech32m) => write!(f, "invalid bech32m checksum"),
+            Unlocatable => write!(f, "invalid checksum"),
+            InvalidAddress(ref e) => write_err!(f, "invalid segwit address"; e),
+        }
+    }
+}
+
 #[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
@@ -585,4 +1085,206 @@ mod tests {
 
         assert_eq!(decode(address).unwrap_err(), DecodeError(SegwitHrpstringError::TooLong(91)));
     }
+
+    #[test]
+    fn correct_segwit_addresses() {
+        // Segwit v0 uses bech32 and segwit v1 uses bech32m.
+        let v0 = "bc1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnssdmnjq";
+        let (corrected, corrections) = correct(&v0.replace("2s3r", "2s4r")).unwrap();
+        assert_eq!(corrected, v0);
+        assert_eq!(corrections, [(6, '4', '3')]);
+
+        let v1 = "bc1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqcgrtxs";
+        assert_eq!(correct(v1).unwrap(), (v1.to_string(), vec![]));
+        let (corrected, corrections) = correct(&v1.replace("grtxs", "grtqs")).unwrap();
+        assert_eq!(corrected, v1);
+        assert_eq!(corrections, [(v1.len() - 2, 'q', 'x')]);
+
+        // A corrupted witness version implies the wrong checksum.
+        let (corrected, corrections) =
+            correct("bc1par0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").unwrap();
+        assert_eq!(corrected, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+        assert_eq!(corrections, [(3, 'p', 'q')]);
+
+        // Invalid witness program length cannot be corrected.
+        let s = crate::encode::<Bech32m>(hrp::BC, &[0x51, 0x00]).unwrap();
+        assert!(matches!(correct(&s), Err(CorrectionError::Segwit(_))));
+
+        // A corrupted HRP is fixed given the expected one.
+        let (corrected, edit, corrections) =
+            correct_with_hrp(&v1.replace("bc1p", "b1p").replace("grtxs", "grtqs"), &[hrp::BC])
+                .unwrap();
+        assert_eq!(corrected, v1);
+        assert_eq!(edit.unwrap().original, "b");
+        assert_eq!(corrections, [(v1.len() - 2, 'q', 'x')]);
+    }
+
+    #[test]
+    fn locate_errors_in_addresses() {
+        use LocateReason::*;
+
+        let v0 = "bc1q2s3rjwvam9dt2ftt4sqxqjf3twav0gdx0k0q2etxflx38c3x8tnssdmnjq";
+        let v1 = "bc1py3m7vwnghyne9gnvcjw82j7gqt2rafgdmlmwmqnn3hvcmdm09rjqcgrtxs";
+        assert_eq!(locate_errors(v0), None);
+        assert_eq!(locate_errors(&v1.to_uppercase()), None);
+
+        let located = locate_errors(&v0.replace("2s3r", "2s4r")).unwrap();
+        assert_eq!(*located.reason(), InvalidChecksum(Variant::Bech32));
+        assert_eq!(located.positions(), [6]);
+
+        let located = locate_errors(&v1.replace("grtxs", "grtqs")).unwrap();
+        assert_eq!(*located.reason(), InvalidChecksum(Variant::Bech32m));
+        assert_eq!(located.positions(), [v1.len() - 2]);
+
+        // Too many errors to locate.
+        let located = locate_errors(&v1.replace("y3m7vwn", "y4m8vwm")).unwrap();
+        assert_eq!(*located.reason(), Unlocatable);
+        assert!(located.positions().is_empty());
+
+        // Segwit addresses with the checksum variant of the other witness version.
+        let program = [0x01; 32];
+        let mixup = |ver: Fe32, bech32: bool| -> String {
+            let fes = program.iter().copied().bytes_to_fes();
+            if bech32 {
+                fes.with_checksum::<Bech32>(&hrp::BC).with_witness_version(ver).chars().collect()
+            } else {
+                fes.with_checksum::<Bech32m>(&hrp::BC).with_witness_version(ver).chars().collect()
+            }
+        };
+        let located = locate_errors(&mixup(VERSION_1, true)).unwrap();
+        assert_eq!(*located.reason(), WrongVariant { expected: Variant::Bech32m });
+        let located = locate_errors(&mixup(VERSION_0, false)).unwrap();
+        assert_eq!(*located.reason(), WrongVariant { expected: Variant::Bech32 });
+        assert!(located.positions().is_empty());
+
+        // A typo in the witness version character, 'q' to 'l' is version 31.
+        let located = locate_errors("bc1lar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").unwrap();
+        assert_eq!(*located.reason(), InvalidChecksum(Variant::Bech32));
+        assert_eq!(located.positions(), [3]);
+
+        // Invalid witness version with a valid checksum.
+        let fes = Some(Fe32::L).into_iter().chain(program.iter().copied().bytes_to_fes());
+        let s = fes.with_checksum::<Bech32m>(&hrp::BC).chars().collect::<String>();
+        let located = locate_errors(&s).unwrap();
+        assert!(matches!(located.reason(), InvalidAddress(_)));
+        assert_eq!(located.positions(), [3]);
+
+        // Invalid witness program length.
+        let s = crate::encode::<Bech32m>(hrp::BC, &[0x51, 0x00]).unwrap();
+        assert!(matches!(locate_errors(&s).unwrap().reason(), InvalidAddress(_)));
+
+        let located = locate_errors("bc1qar0srrr7xfkvy5l643lydnW9re59gtzzwf5mdq").unwrap();
+        assert_eq!(*located.reason(), InvalidCharacter);
+        assert_eq!(located.positions(), [26]);
+        let located = locate_errors("bc1qar0srrr7xfkvy5l643lydnb9re59gtzzwf5mdq").unwrap();
+        assert_eq!(located.positions(), [26]);
+        // Positions are counted in characters, not bytes.
+        let located = locate_errors("bé1qar0srrr7xfkvy5l643lydnW9re59gtzzwf5mdq").unwrap();
+        assert_eq!(*located.reason(), InvalidCharacter);
+        assert_eq!(located.positions(), [1, 26]);
+
+        let located = locate_errors("bcqar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").unwrap();
+        assert_eq!(*located.reason(), MissingSeparator);
+        let located = locate_errors("bc1qar0s").unwrap();
+        assert_eq!(*located.reason(), InvalidSeparatorPosition);
+        assert_eq!(located.positions(), [2]);
+        let located = locate_errors(&format!("{}{}", v0, "q".repeat(30))).unwrap();
+        assert_eq!(*located.reason(), TooLong);
+        assert_eq!(located.positions(), [90]);
+        // 62 ASCII and 14 two byte characters make 90 bytes.
+        let located = locate_errors(&format!("{}{}", v0, "é".repeat(30))).unwrap();
+        assert_eq!(*located.reason(), TooLong);
+        assert_eq!(located.positions(), [76]);
+    }
+
+    #[test]
+    fn segwit_address_roundtrip() {
+        let addresses = [
+            ("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", Network::Mainnet, 0, 20),
+            (
+                "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+                Network::Testnet,
+                0,
+                32,
+            ),
+            (
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+                Network::Mainnet,
+                1,
+                40,
+            ),
+            ("BC1SW50QGDZ25J", Network::Mainnet, 16, 2),
+        ];
+
+        for &(s, network, version, len) in &addresses {
+            let address = s.parse::<SegwitAddress>().expect("valid address");
+            assert_eq!(address.network(), network);
+            assert_eq!(address.witness_version().to_u8(), version);
+            assert_eq!(address.program().len(), len);
+            assert_eq!(address.to_string(), s.to_lowercase());
+
+            let (hrp, witness_version, program) = decode(s).unwrap();
+            assert_eq!(address.hrp(), hrp);
+            assert_eq!(address.witness_version().to_fe(), witness_version);
+            assert_eq!(address.program(), &program[..]);
+        }
+    }
+
+    #[test]
+    fn segwit_address_new() {
+        let program = witness_program();
+        let address = SegwitAddress::new(Network::Regtest, WitnessVersion::V0, &program).unwrap();
+        assert_eq!(address.to_string(), encode_v0(hrp::BCRT, &program).unwrap());
+        assert_eq!(address.to_string().parse::<SegwitAddress>(), Ok(address));
+
+        assert_eq!(
+            SegwitAddress::new(Network::Mainnet, WitnessVersion::V0, &program[..19]),
+            Err(WitnessLengthError::InvalidSegwitV0)
+        );
+        assert!(SegwitAddress::new(Network::Mainnet, WitnessVersion::V1, &program[..19]).is_ok());
+    }
+
+    #[test]
+    fn segwit_address_errors() {
+        // Version 1 address with a bech32 checksum (from BIP-350).
+        let s = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd";
+        assert!(matches!(s.parse::<SegwitAddress>(), Err(ParseAddressError::Segwit(_))));
+
+        let s = "tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut";
+        let hrp = Hrp::parse("tc").unwrap();
+        assert_eq!(s.parse::<SegwitAddress>(), Err(ParseAddressError::UnknownHrp(hrp)));
+    }
+
+    #[test]
+    fn network_hrp() {
+        for &network in &[Network::Mainnet, Network::Testnet, Network::Regtest] {
+            assert_eq!(Network::from_hrp(network.hrp()), Some(network));
+            assert_eq!(Hrp::from(network), network.hrp());
+        }
+        assert_eq!(Network::from_hrp(Hrp::parse("TB").unwrap()), Some(Network::Testnet));
+        assert_eq!(Network::from_hrp(Hrp::parse("tc").unwrap()), None);
+    }
+
+    #[test]
+    fn witness_version() {
+        assert_eq!(WitnessVersion::from_fe(Fe32::S).unwrap().to_u8(), 16);
+        assert_eq!(WitnessVersion::from_fe(Fe32::_3), Err(InvalidWitnessVersionError(Fe32::_3)));
+        assert!(WitnessVersion::V0 < WitnessVersion::V1);
+        assert_eq!(WitnessVersion::V1.to_string(), "1");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn segwit_address_serde() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        let s = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+        assert_tokens(&s.parse::<SegwitAddress>().unwrap(), &[Token::Str(s)]);
+
+        let s = "tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut";
+        assert_de_tokens_error::<SegwitAddress>(
+            &[Token::Str(s)],
+            "unknown human-readable part: tc",
+        );
+    }
 }
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: MIT
+
+//! Helpers for the `serde` implementations.
+//!
+//! All types serialize as their canonical (lowercase) string, deserializing parses that string
+//! with full validation.
+
+use core::marker::PhantomData;
+use core::{fmt, str};
+
+use serde::de;
+
+/// Displays an ASCII string in lowercase, used to serialize without allocating.
+pub(crate) struct Lowercase<'a>(pub(crate) &'a str);
+
+impl fmt::Display for Lowercase<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use fmt::Write as _;
+
+        self.0.chars().try_for_each(|c| f.write_char(c.to_ascii_lowercase()))
+    }
+}
+
+/// A visitor that parses a string using the [`str::FromStr`] impl of `T`.
+pub(crate) struct FromStrVisitor<T> {
+    /// Describes the expected string, e.g. "a bech32 string".
+    expecting: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<T> FromStrVisitor<T> {
+    /// Constructs a new visitor, `expecting` is used in error messages.
+    pub(crate) fn new(expecting: &'static str) -> Self {
+        FromStrVisitor { expecting, marker: PhantomData }
+    }
+}
+
+impl<'de, T> de::Visitor<'de> for FromStrVisitor<T>
+where
+    T: str::FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(self.expecting) }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+}
@@ -0,0 +1,291 @@
+// SPDX-License-Identifier: MIT
+
+//! Silent Payments API - enables encoding and decoding [BIP-352] silent payment addresses.
+//!
+//! A silent payment address is a bech32m string made up of a version character followed by the
+//! receiver's 33-byte scan public key and 33-byte spend public key. Unlike segwit addresses these
+//! strings may be up to 1023 characters long, so that future versions can append more data.
+//!
+//! As specified by BIP-352, decoding is forward compatible: an address with a version between 1
+//! and 30 (inclusive) is accepted and any data after the two public keys is ignored.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use bech32::{hrp, silent_payments};
+//!
+//! let address = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+//! let decoded = silent_payments::decode(address).expect("valid address");
+//!
+//! assert!(decoded.has_valid_hrp());
+//! assert_eq!(decoded.version, silent_payments::VERSION_0);
+//!
+//! let encoded = silent_payments::encode(hrp::SP, &decoded.scan_key, &decoded.spend_key);
+//! assert_eq!(encoded, address);
+//! # }
+//! ```
+//!
+//! [BIP-352]: <https://github.com/bitcoin/bips/blob/master/bip-0352.mediawiki>
+
+#[cfg(all(feature = "alloc", not(feature = "std"), not(test)))]
+use alloc::string::String;
+use core::fmt;
+
+use crate::error::write_err;
+use crate::primitives::correction::CorrectableError;
+use crate::primitives::decode::{
+    CheckedHrpstring, CheckedHrpstringError, InvalidResidueError, PaddingError,
+};
+use crate::primitives::gf32::Fe32;
+use crate::primitives::hrp::{self, Hrp};
+use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+use crate::primitives::Bech32m;
+
+/// The maximum enforced string length of a silent payment address.
+pub const MAX_STRING_LENGTH: usize = 1023;
+
+/// The version 0 silent payment address version.
+pub const VERSION_0: Fe32 = Fe32::Q;
+
+/// The reserved version, used to signal a backwards incompatible change.
+const VERSION_RESERVED: Fe32 = Fe32::L;
+
+/// The length of a compressed public key.
+const KEY_LENGTH: usize = 33;
+
+/// A decoded silent payment address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SilentPaymentAddress {
+    /// The human-readable part.
+    pub hrp: Hrp,
+    /// The address version (0-30 inclusive).
+    pub version: Fe32,
+    /// The receiver's scan public key.
+    pub scan_key: [u8; 33],
+    /// The receiver's spend public key.
+    pub spend_key: [u8; 33],
+}
+
+impl SilentPaymentAddress {
+    /// Returns `true` if the HRP is one of `sp`, `tsp` or `sprt`.
+    #[inline]
+    pub fn has_valid_hrp(&self) -> bool {
+        self.hrp == hrp::SP || self.hrp == hrp::TSP || self.hrp == hrp::SPRT
+    }
+}
+
+/// Decodes a silent payment address.
+///
+/// Data following the two public keys of a version 1 to 30 address is ignored, as required by
+/// BIP-352. The HRP is not validated, see [`SilentPaymentAddress::has_valid_hrp`].
+pub fn decode(s: &str) -> Result<SilentPaymentAddress, DecodeError> {
+    use DecodeError::*;
+
+    let mut checked = CheckedHrpstring::new::<Bech32m>(s)?;
+    if checked.data_part_ascii_no_checksum().is_empty() {
+        return Err(MissingVersion);
+    }
+    let version = Fe32::from_char_unchecked(checked.remove_prefix(1)[0]);
+    if version == VERSION_RESERVED {
+        return Err(InvalidVersion(version));
+    }
+    checked.validate_segwit_padding()?;
+
+    let data_len = checked.data_part_ascii_no_checksum().len() * 5 / 8;
+    let valid_len =
+        if version == VERSION_0 { data_len == 2 * KEY_LENGTH } else { data_len >= 2 * KEY_LENGTH };
+    if !valid_len {
+        return Err(InvalidDataLength(data_len));
+    }
+
+    let mut scan_key = [0u8; KEY_LENGTH];
+    let mut spend_key = [0u8; KEY_LENGTH];
+    let mut iter = checked.byte_iter();
+    for (dst, b) in scan_key.iter_mut().chain(spend_key.iter_mut()).zip(&mut iter) {
+        *dst = b;
+    }
+
+    Ok(SilentPaymentAddress { hrp: checked.hrp(), version, scan_key, spend_key })
+}
+
+/// Encodes a version 0 silent payment address.
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn encode(hrp: Hrp, scan_key: &[u8; 33], spend_key: &[u8; 33]) -> String {
+    let mut buf = String::new();
+    encode_to_fmt(&mut buf, hrp, scan_key, spend_key).expect("writing to string never fails");
+    buf
+}
+
+/// Encodes a version 0 silent payment address to a writer ([`fmt::Write`]) using lowercase
+/// characters.
+///
+/// The HRP is at most 83 characters, so the encoded address is always shorter than
+/// [`MAX_STRING_LENGTH`].
+pub fn encode_to_fmt<W: fmt::Write>(
+    fmt: &mut W,
+    hrp: Hrp,
+    scan_key: &[u8; 33],
+    spend_key: &[u8; 33],
+) -> fmt::Result {
+    let iter = scan_key.iter().chain(spend_key.iter()).copied().bytes_to_fes();
+    let chars = iter.with_checksum::<Bech32m>(&hrp).with_witness_version(VERSION_0).chars();
+    for c in chars {
+        fmt.write_char(c)?;
+    }
+    Ok(())
+}
+
+/// An error while decoding a silent payment address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// Error while parsing the bech32m string.
+    Checked(CheckedHrpstringError),
+    /// The data part is empty, there is no version character.
+    MissingVersion,
+    /// The address uses the reserved version 31.
+    InvalidVersion(Fe32),
+    /// The data part has invalid padding.
+    Padding(PaddingError),
+    /// The data part has an invalid number of bytes for the address version.
+    InvalidDataLength(usize),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DecodeError::*;
+
+        match *self {
+            Checked(ref e) => write_err!(f, "invalid bech32m string"; e),
+            MissingVersion => write!(f, "missing silent payment version"),
+            InvalidVersion(v) => write!(f, "invalid silent payment version: {}", v.to_u8()),
+            Padding(ref e) => write_err!(f, "invalid padding"; e),
+            InvalidDataLength(len) => write!(f, "invalid data length: {} bytes", len),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use DecodeError::*;
+
+        match *self {
+            Checked(ref e) => Some(e),
+            Padding(ref e) => Some(e),
+            MissingVersion | InvalidVersion(_) | InvalidDataLength(_) => None,
+        }
+    }
+}
+
+impl From<CheckedHrpstringError> for DecodeError {
+    #[inline]
+    fn from(e: CheckedHrpstringError) -> Self { Self::Checked(e) }
+}
+
+impl From<PaddingError> for DecodeError {
+    #[inline]
+    fn from(e: PaddingError) -> Self { Self::Padding(e) }
+}
+
+impl CorrectableError for DecodeError {
+    fn residue_error(&self) -> Option<&InvalidResidueError> {
+        match self {
+            DecodeError::Checked(ref e) => e.residue_error(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    // Test vector from BIP-352.
+    const ADDRESS: &str = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+
+    const SCAN_KEY: [u8; 33] = [
+        0x02, 0x20, 0xbc, 0xfa, 0xc5, 0xb9, 0x9e, 0x04, 0xad, 0x1a, 0x06, 0xdd, 0xfb, 0x01, 0x6e,
+        0xe1, 0x35, 0x82, 0x60, 0x9d, 0x60, 0xb6, 0x29, 0x1e, 0x98, 0xd0, 0x1a, 0x9b, 0xc9, 0xa1,
+        0x6c, 0x96, 0xd4,
+    ];
+    const SPEND_KEY: [u8; 33] = [
+        0x02, 0x5c, 0xc9, 0x85, 0x6d, 0x6f, 0x83, 0x75, 0x35, 0x0e, 0x12, 0x39, 0x78, 0xda, 0xac,
+        0x20, 0x0c, 0x26, 0x0c, 0xb5, 0xb5, 0xae, 0x83, 0x10, 0x6c, 0xab, 0x90, 0x48, 0x4d, 0xcd,
+        0x8f, 0xcf, 0x36,
+    ];
+
+    /// Encodes an address with an arbitrary version and payload.
+    fn encode_raw(hrp: Hrp, version: Fe32, data: &[u8]) -> String {
+        data.iter()
+            .copied()
+            .bytes_to_fes()
+            .with_checksum::<Bech32m>(&hrp)
+            .with_witness_version(version)
+            .chars()
+            .collect()
+    }
+
+    #[test]
+    fn decode_bip_352() {
+        let decoded = decode(ADDRESS).unwrap();
+        assert_eq!(decoded.hrp, hrp::SP);
+        assert!(decoded.has_valid_hrp());
+        assert_eq!(decoded.version, VERSION_0);
+        assert_eq!(decoded.scan_key, SCAN_KEY);
+        assert_eq!(decoded.spend_key, SPEND_KEY);
+
+        assert_eq!(decode(&ADDRESS.to_uppercase()).unwrap(), decoded);
+    }
+
+    #[test]
+    fn roundtrip() {
+        assert_eq!(encode(hrp::SP, &SCAN_KEY, &SPEND_KEY), ADDRESS);
+
+        for hrp in [hrp::TSP, hrp::SPRT].iter() {
+            let s = encode(*hrp, &SCAN_KEY, &SPEND_KEY);
+            let decoded = decode(&s).unwrap();
+            assert_eq!(decoded.hrp, *hrp);
+            assert_eq!(decoded.scan_key, SCAN_KEY);
+            assert_eq!(decoded.spend_key, SPEND_KEY);
+        }
+    }
+
+    #[test]
+    fn future_versions() {
+        let mut data = SCAN_KEY.to_vec();
+        data.extend_from_slice(&SPEND_KEY);
+        data.extend_from_slice(&[0xab; 500]);
+
+        // Version 1 with extra data, longer than a segwit address may be.
+        let s = encode_raw(hrp::SP, Fe32::P, &data);
+        assert!(s.len() > 90 && s.len() <= MAX_STRING_LENGTH);
+        let decoded = decode(&s).unwrap();
+        assert_eq!(decoded.version, Fe32::P);
+        assert_eq!(decoded.scan_key, SCAN_KEY);
+        assert_eq!(decoded.spend_key, SPEND_KEY);
+
+        // Version 0 must not have extra data.
+        let s = encode_raw(hrp::SP, VERSION_0, &data);
+        assert_eq!(decode(&s), Err(DecodeError::InvalidDataLength(566)));
+
+        // Version 31 is reserved.
+        let s = encode_raw(hrp::SP, Fe32::L, &data[..66]);
+        assert_eq!(decode(&s), Err(DecodeError::InvalidVersion(Fe32::L)));
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        let s = encode_raw(hrp::SP, Fe32::P, &SCAN_KEY);
+        assert_eq!(decode(&s), Err(DecodeError::InvalidDataLength(33)));
+
+        // Too long for a bech32m string.
+        let s = encode_raw(hrp::SP, Fe32::P, &[0; 700]);
+        assert!(matches!(decode(&s), Err(DecodeError::Checked(_))));
+
+        let s = crate::encode::<Bech32m>(hrp::SP, &[]).unwrap();
+        assert_eq!(decode(&s), Err(DecodeError::MissingVersion));
+    }
+}
@@ -0,0 +1,468 @@
+// SPDX-License-Identifier: MIT
+
+//! Push-style encoding of bech32 strings.
+//!
+//! The [`Encoder`] and the iterator adaptors in [`primitives::iter`] need the whole payload as an
+//! iterator up front. That is awkward for producers that generate data piecemeal, e.g. a
+//! serializer writing one field at a time. The writers in this module instead accept bytes or
+//! field elements one call at a time, regroup bytes into 5-bit field elements internally, and
+//! append the checksum when [`Bech32Writer::finalize`] is called.
+//!
+//! The checksum code length ([`Checksum::CODE_LENGTH`]) is enforced as data arrives: a write that
+//! would make the final string too long fails with [`EncodeError::TooLong`] before any of its
+//! data is written.
+//!
+//! If writing to the underlying writer fails, part of the string may already have been written
+//! and the bech32 writer should be discarded.
+//!
+//! # Examples
+//!
+//! ```
+//! use bech32::writer::Bech32Writer;
+//! use bech32::{hrp, Bech32, Fe32};
+//!
+//! let mut s = String::new();
+//! let mut w = Bech32Writer::<Bech32, _>::new(hrp::BC, &mut s).expect("hrp fits");
+//! w.write_fe(Fe32::Q).expect("witness version");
+//! w.write_bytes(&[0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94]).expect("fits");
+//! w.write_bytes(&[0x1c, 0x45, 0xd1, 0xb3, 0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6]).expect("fits");
+//! w.finalize().expect("writing to a string cannot fail");
+//!
+//! assert_eq!(s, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+//! ```
+//!
+//! [`Encoder`]: crate::primitives::encode::Encoder
+//! [`primitives::iter`]: crate::primitives::iter
+
+use core::fmt;
+
+use crate::primitives::checksum::{Engine, PackedFe32, PreparedHrp};
+use crate::primitives::decode::CodeLengthError;
+#[cfg(feature = "std")]
+use crate::EncodeIoError;
+use crate::{Checksum, EncodeError, Fe32, Hrp};
+
+/// Writes a lowercase bech32 string to a [`fmt::Write`], one byte or field element at a time.
+///
+/// Bytes are regrouped into field elements as in [`ByteIterExt::bytes_to_fes`]: if a field
+/// element is written while some bits of a previous byte are still pending, the pending bits are
+/// first padded with zeros to a whole field element.
+///
+/// [`ByteIterExt::bytes_to_fes`]: crate::ByteIterExt::bytes_to_fes
+pub struct Bech32Writer<Ck: Checksum, W: fmt::Write> {
+    writer: W,
+    state: State<Ck>,
+}
+
+impl<Ck: Checksum, W: fmt::Write> Bech32Writer<Ck, W> {
+    /// Constructs a new writer, writing the HRP and separator to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the HRP and checksum alone exceed the code length, or if writing fails.
+    #[inline]
+    pub fn new(hrp: Hrp, writer: W) -> Result<Self, EncodeError> {
+        let mut engine = Engine::new();
+        engine.input_hrp(hrp);
+        Self::from_engine(&hrp, engine, writer)
+    }
+
+    /// Constructs a new writer using a cached HRP checksum midstate.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the HRP and checksum alone exceed the code length, or if writing fails.
+    #[inline]
+    pub fn with_prepared_hrp(prepared: &PreparedHrp<Ck>, writer: W) -> Result<Self, EncodeError> {
+        Self::from_engine(prepared.hrp(), prepared.engine(), writer)
+    }
+
+    fn from_engine(hrp: &Hrp, engine: Engine<Ck>, mut writer: W) -> Result<Self, EncodeError> {
+        let state = State::new(hrp.len(), engine);
+        state.check_length(0, 0)?;
+
+        for c in hrp.lowercase_char_iter() {
+            writer.write_char(c)?;
+        }
+        writer.write_char('1')?;
+
+        Ok(Bech32Writer { writer, state })
+    }
+
+    /// Returns the number of characters the string will have if finalized now.
+    #[inline]
+    pub fn encoded_length(&self) -> usize { self.state.encoded_length(0, 0) }
+
+    /// Writes a single field element.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the string would exceed the code length, or if writing fails.
+    #[inline]
+    pub fn write_fe(&mut self, fe: Fe32) -> Result<(), EncodeError> {
+        let writer = &mut self.writer;
+        Ok(self.state.push_fe(fe, |fe| writer.write_char(fe.to_char()))??)
+    }
+
+    /// Writes a single byte.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the string would exceed the code length, or if writing fails.
+    #[inline]
+    pub fn write_byte(&mut self, byte: u8) -> Result<(), EncodeError> { self.write_bytes(&[byte]) }
+
+    /// Writes a slice of bytes.
+    ///
+    /// The length is checked before anything is written, so on a [`EncodeError::TooLong`] error
+    /// none of `bytes` has been written.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the string would exceed the code length, or if writing fails.
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+        let writer = &mut self.writer;
+        Ok(self.state.push_bytes(bytes, |fe| writer.write_char(fe.to_char()))??)
+    }
+
+    /// Pads any pending bits, writes the checksum and returns the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing fails.
+    #[inline]
+    pub fn finalize(mut self) -> Result<W, EncodeError> {
+        let writer = &mut self.writer;
+        self.state.finish(|fe| writer.write_char(fe.to_char()))?;
+        Ok(self.writer)
+    }
+}
+
+impl<Ck: Checksum, W: fmt::Write + fmt::Debug> fmt::Debug for Bech32Writer<Ck, W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Bech32Writer")
+            .field("writer", &self.writer)
+            .field("encoded_length", &self.encoded_length())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Writes a lowercase bech32 string to an [`io::Write`], one byte or field element at a time.
+///
+/// This is the [`io::Write`] flavour of [`Bech32Writer`]. It also implements [`io::Write`]
+/// itself, so payload bytes can be written into it by anything that writes to a byte sink.
+///
+/// Every character is written to the underlying writer as soon as it is known, wrap it in a
+/// [`BufWriter`] if that writer is expensive to call.
+///
+/// [`io::Write`]: std::io::Write
+/// [`BufWriter`]: std::io::BufWriter
+#[cfg(feature = "std")]
+pub struct Bech32IoWriter<Ck: Checksum, W: std::io::Write> {
+    writer: W,
+    state: State<Ck>,
+}
+
+#[cfg(feature = "std")]
+impl<Ck: Checksum, W: std::io::Write> Bech32IoWriter<Ck, W> {
+    /// Constructs a new writer, writing the HRP and separator to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the HRP and checksum alone exceed the code length, or if writing fails.
+    #[inline]
+    pub fn new(hrp: Hrp, writer: W) -> Result<Self, EncodeIoError> {
+        let mut engine = Engine::new();
+        engine.input_hrp(hrp);
+        Self::from_engine(&hrp, engine, writer)
+    }
+
+    /// Constructs a new writer using a cached HRP checksum midstate.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the HRP and checksum alone exceed the code length, or if writing fails.
+    #[inline]
+    pub fn with_prepared_hrp(prepared: &PreparedHrp<Ck>, writer: W) -> Result<Self, EncodeIoError> {
+        Self::from_engine(prepared.hrp(), prepared.engine(), writer)
+    }
+
+    fn from_engine(hrp: &Hrp, engine: Engine<Ck>, mut writer: W) -> Result<Self, EncodeIoError> {
+        let state = State::new(hrp.len(), engine);
+        state.check_length(0, 0)?;
+
+        let mut buf = [0u8; crate::primitives::hrp::MAX_HRP_LEN + 1];
+        for (i, c) in hrp.lowercase_char_iter().chain(Some('1')).enumerate() {
+            buf[i] = c as u8;
+        }
+        writer.write_all(&buf[..hrp.len() + 1])?;
+
+        Ok(Bech32IoWriter { writer, state })
+    }
+
+    /// Returns the number of characters the string will have if finalized now.
+    #[inline]
+    pub fn encoded_length(&self) -> usize { self.state.encoded_length(0, 0) }
+
+    /// Writes a single field element.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the string would exceed the code length, or if writing fails.
+    #[inline]
+    pub fn write_fe(&mut self, fe: Fe32) -> Result<(), EncodeIoError> {
+        let writer = &mut self.writer;
+        Ok(self.state.push_fe(fe, |fe| writer.write_all(&[fe.to_char() as u8]))??)
+    }
+
+    /// Writes a single byte.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the string would exceed the code length, or if writing fails.
+    #[inline]
+    pub fn write_byte(&mut self, byte: u8) -> Result<(), EncodeIoError> {
+        self.write_bytes(&[byte])
+    }
+
+    /// Writes a slice of bytes.
+    ///
+    /// The length is checked before anything is written, so on a [`EncodeIoError::TooLong`]
+    /// error none of `bytes` has been written.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the string would exceed the code length, or if writing fails.
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), EncodeIoError> {
+        let writer = &mut self.writer;
+        Ok(self.state.push_bytes(bytes, |fe| writer.write_all(&[fe.to_char() as u8]))??)
+    }
+
+    /// Pads any pending bits, writes the checksum and returns the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing fails.
+    #[inline]
+    pub fn finalize(mut self) -> Result<W, EncodeIoError> {
+        let writer = &mut self.writer;
+        self.state.finish(|fe| writer.write_all(&[fe.to_char() as u8]))?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Ck: Checksum, W: std::io::Write> std::io::Write for Bech32IoWriter<Ck, W> {
+    /// Writes all of `buf` as payload bytes, or none of it if the string would become too long.
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.write_bytes(buf) {
+            Ok(()) => Ok(buf.len()),
+            Err(EncodeIoError::Write(e)) => Err(e),
+            Err(e) => Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, e)),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> { self.writer.flush() }
+}
+
+#[cfg(feature = "std")]
+impl<Ck: Checksum, W: std::io::Write + fmt::Debug> fmt::Debug for Bech32IoWriter<Ck, W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Bech32IoWriter")
+            .field("writer", &self.writer)
+            .field("encoded_length", &self.encoded_length())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Checksum and bit regrouping state shared by the fmt and io writers.
+struct State<Ck: Checksum> {
+    engine: Engine<Ck>,
+    /// Length of the HRP plus separator.
+    prefix_len: usize,
+    /// Number of data field elements written so far.
+    fes: usize,
+    /// Pending bits not yet written, right aligned.
+    bits: u16,
+    /// Number of pending bits, always less than 5.
+    n_bits: usize,
+}
+
+impl<Ck: Checksum> State<Ck> {
+    fn new(hrp_len: usize, engine: Engine<Ck>) -> Self {
+        State { engine, prefix_len: hrp_len + 1, fes: 0, bits: 0, n_bits: 0 }
+    }
+
+    /// The final string length if `extra_bits` bits and then `extra_fes` field elements were
+    /// written now.
+    fn encoded_length(&self, extra_bits: usize, extra_fes: usize) -> usize {
+        let bits = extra_bits.saturating_add(self.n_bits);
+        let data_fes = self.fes.saturating_add((bits / 5) + usize::from(bits % 5 != 0));
+        self.prefix_len
+            .saturating_add(data_fes)
+            .saturating_add(extra_fes)
+            .saturating_add(Ck::CHECKSUM_LENGTH)
+    }
+
+    fn check_length(&self, extra_bits: usize, extra_fes: usize) -> Result<(), CodeLengthError> {
+        let len = self.encoded_length(extra_bits, extra_fes);
+        if len > Ck::CODE_LENGTH {
+            Err(CodeLengthError { encoded_length: len, code_length: Ck::CODE_LENGTH })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Inputs `fe` into the checksum and passes it to `emit`.
+    fn emit<E, F: FnMut(Fe32) -> Result<(), E>>(
+        &mut self,
+        fe: Fe32,
+        emit: &mut F,
+    ) -> Result<(), E> {
+        self.engine.input_fe(fe);
+        self.fes += 1;
+        emit(fe)
+    }
+
+    /// Pads the pending bits, if any, to a whole field element and emits it.
+    fn pad<E, F: FnMut(Fe32) -> Result<(), E>>(&mut self, emit: &mut F) -> Result<(), E> {
+        if self.n_bits > 0 {
+            let fe = Fe32((self.bits << (5 - self.n_bits)) as u8 & 0x1f);
+            self.bits = 0;
+            self.n_bits = 0;
+            self.emit(fe, emit)?;
+        }
+        Ok(())
+    }
+
+    fn push_fe<E, F: FnMut(Fe32) -> Result<(), E>>(
+        &mut self,
+        fe: Fe32,
+        mut emit: F,
+    ) -> Result<Result<(), E>, CodeLengthError> {
+        self.check_length(0, 1)?;
+        Ok(self.pad(&mut emit).and_then(|()| self.emit(fe, &mut emit)))
+    }
+
+    fn push_bytes<E, F: FnMut(Fe32) -> Result<(), E>>(
+        &mut self,
+        bytes: &[u8],
+        mut emit: F,
+    ) -> Result<Result<(), E>, CodeLengthError> {
+        self.check_length(bytes.len().saturating_mul(8), 0)?;
+        Ok(self.push_bytes_unchecked(bytes, &mut emit))
+    }
+
+    fn push_bytes_unchecked<E, F: FnMut(Fe32) -> Result<(), E>>(
+        &mut self,
+        bytes: &[u8],
+        emit: &mut F,
+    ) -> Result<(), E> {
+        for &byte in bytes {
+            self.bits = (self.bits << 8) | u16::from(byte);
+            self.n_bits += 8;
+            while self.n_bits >= 5 {
+                self.n_bits -= 5;
+                let fe = Fe32((self.bits >> self.n_bits) as u8 & 0x1f);
+                self.emit(fe, emit)?;
+            }
+            self.bits &= (1 << self.n_bits) - 1;
+        }
+        Ok(())
+    }
+
+    fn finish<E, F: FnMut(Fe32) -> Result<(), E>>(&mut self, mut emit: F) -> Result<(), E> {
+        self.pad(&mut emit)?;
+        self.engine.input_target_residue();
+        let residue = *self.engine.residue();
+        for i in (0..Ck::CHECKSUM_LENGTH).rev() {
+            emit(Fe32(residue.unpack(i)))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::primitives::iter::{ByteIterExt, Fe32IterExt};
+    use crate::{hrp, segwit, Bech32, Bech32m, Codex32, NoChecksum};
+
+    fn check<Ck: Checksum>(hrp: Hrp, data: &[u8]) {
+        let want = crate::encode::<Ck>(hrp, data).expect("valid data");
+
+        // Every way of splitting `data` into two writes gives the same string.
+        for split in 0..=data.len() {
+            let mut w = Bech32Writer::<Ck, _>::new(hrp, String::new()).expect("valid hrp");
+            w.write_bytes(&data[..split]).expect("fits");
+            for &b in &data[split..] {
+                w.write_byte(b).expect("fits");
+            }
+            assert_eq!(w.encoded_length(), want.len());
+            assert_eq!(w.finalize().expect("infallible"), want);
+
+            let mut w = Bech32IoWriter::<Ck, _>::new(hrp, vec![]).expect("valid hrp");
+            w.write_bytes(&data[..split]).expect("fits");
+            std::io::Write::write_all(&mut w, &data[split..]).expect("fits");
+            assert_eq!(w.finalize().expect("infallible"), want.as_bytes());
+        }
+    }
+
+    #[test]
+    fn matches_encode() {
+        let data = (0..40_u8).map(|i| i.wrapping_mul(37)).collect::<Vec<u8>>();
+        for len in 0..data.len() {
+            check::<Bech32>(hrp::BC, &data[..len]);
+            check::<Bech32m>(hrp::TB, &data[..len]);
+            check::<NoChecksum>(Hrp::parse_unchecked("abc"), &data[..len]);
+        }
+        check::<Codex32>(Hrp::parse_unchecked("ms"), &data);
+    }
+
+    #[test]
+    fn matches_segwit_encode() {
+        let program = [0xab; 32];
+        let want = segwit::encode_v1(hrp::BC, &program).expect("valid program");
+
+        let prepared = PreparedHrp::<Bech32m>::new(hrp::BC);
+        let mut w = Bech32Writer::with_prepared_hrp(&prepared, String::new()).expect("valid hrp");
+        w.write_fe(segwit::VERSION_1).expect("fits");
+        w.write_bytes(&program).expect("fits");
+        assert_eq!(w.finalize().expect("infallible"), want);
+    }
+
+    #[test]
+    fn fe_after_partial_byte_is_padded() {
+        let fes = [0x75_u8].iter().copied().bytes_to_fes().chain(Some(Fe32::P));
+        let want = fes.with_checksum::<Bech32m>(&hrp::BC).chars().collect::<String>();
+
+        let mut w = Bech32Writer::<Bech32m, _>::new(hrp::BC, String::new()).expect("valid hrp");
+        w.write_byte(0x75).expect("fits");
+        w.write_fe(Fe32::P).expect("fits");
+        assert_eq!(w.finalize().expect("infallible"), want);
+    }
+
+    #[test]
+    fn enforces_code_length() {
+        // 632 bytes are 1012 field elements, so 4 + 1 + 1012 + 6 = 1023 characters is exactly the
+        // bech32m code length. One more byte, or even one more field element,
+        // overflows it.
+        let hrp = Hrp::parse_unchecked("abcd");
+        let data = [0_u8; 632];
+
+        let mut w = Bech32Writer::<Bech32m, _>::new(hrp, String::new()).expect("valid hrp");
+        w.write_bytes(&data).expect("maximum length fits");
+        assert_eq!(w.encoded_length(), 1023);
+        assert!(matches!(w.write_byte(0), Err(EncodeError::TooLong(_))));
+        assert!(matches!(w.write_fe(Fe32::Q), Err(EncodeError::TooLong(_))));
+        // A failed write leaves the writer usable.
+        assert_eq!(w.finalize().expect("infallible").len(), 1023);
+
+        let mut w = Bech32IoWriter::<Bech32m, _>::new(hrp, vec![]).expect("valid hrp");
+        assert!(matches!(w.write_bytes(&[0; 633]), Err(EncodeIoError::TooLong(_))));
+        let err = std::io::Write::write(&mut w, &[0; 633]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}